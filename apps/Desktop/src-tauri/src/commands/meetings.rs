@@ -0,0 +1,343 @@
+use serde::Serialize;
+use tauri::{Emitter, State};
+use gijiroku21_core::storage::{
+    prepare_migration, MeetingData, MeetingId, MeetingPage, MeetingRepository, MeetingSort,
+    MeetingUpdate, MigrationProgress, MigrationReport, SearchQuery, SearchResult, StorageError,
+};
+use crate::state::{AppState, MeetingState, RecordingStatus};
+
+/// 会議一覧で1回に返す既定の件数
+const DEFAULT_PAGE_SIZE: usize = 50;
+
+/// 会議削除の確認内容・結果
+#[derive(Debug, Clone, Serialize)]
+pub struct MeetingDeletion {
+    pub meeting_id: MeetingId,
+    pub title: String,
+    /// 削除される（された）ファイルの合計サイズ
+    pub size_bytes: u64,
+    /// 削除するときに渡す確認トークン（確認後に会議の内容が変わると無効になる）
+    pub confirmation: String,
+    /// 実際に削除したか（確認のみならfalse）
+    pub deleted: bool,
+}
+
+/// 録音中の会議なら編集・削除を拒否する
+async fn ensure_not_recording(meeting_state: &MeetingState, meeting_id: &MeetingId) -> Result<(), String> {
+    let recording = meeting_state.get_status().await != RecordingStatus::Idle
+        && meeting_state
+            .get_current_meeting()
+            .await
+            .is_some_and(|m| &m.id == meeting_id);
+    if recording {
+        return Err("The meeting is being recorded".to_string());
+    }
+    Ok(())
+}
+
+/// 保存済みの会議の概要を並べ替えて取得（`offset` 件目から最大 `limit` 件）
+#[tauri::command]
+pub async fn list_meetings(
+    app_state: State<'_, AppState>,
+    sort: Option<MeetingSort>,
+    offset: Option<usize>,
+    limit: Option<usize>,
+) -> Result<MeetingPage, String> {
+    let storage = app_state.meeting_repository().await?;
+    tauri::async_runtime::spawn_blocking(move || {
+        storage
+            .list_meeting_summaries(
+                sort.unwrap_or_default(),
+                offset.unwrap_or(0),
+                limit.unwrap_or(DEFAULT_PAGE_SIZE),
+            )
+            .map_err(|e| e.to_string())
+    })
+    .await
+    .map_err(|e| e.to_string())?
+}
+
+/// 文字起こしを全文検索（日付・タグ・話者で絞り込み可能）
+#[tauri::command]
+pub async fn search_meetings(
+    app_state: State<'_, AppState>,
+    query: SearchQuery,
+) -> Result<Vec<SearchResult>, String> {
+    let storage = app_state.meeting_repository().await?;
+    tauri::async_runtime::spawn_blocking(move || {
+        storage.search_meetings(&query).map_err(|e| e.to_string())
+    })
+    .await
+    .map_err(|e| e.to_string())?
+}
+
+/// 会議の全データを取得
+#[tauri::command]
+pub async fn get_meeting(
+    app_state: State<'_, AppState>,
+    meeting_id: MeetingId,
+) -> Result<MeetingData, String> {
+    let storage = app_state.meeting_repository().await?;
+    tauri::async_runtime::spawn_blocking(move || {
+        storage.load_meeting(&meeting_id).map_err(|e| e.to_string())
+    })
+    .await
+    .map_err(|e| e.to_string())?
+}
+
+/// 会議のタイトル・要約・文字起こし・タグを編集して保存
+#[tauri::command]
+pub async fn update_meeting(
+    app_state: State<'_, AppState>,
+    meeting_state: State<'_, MeetingState>,
+    meeting_id: MeetingId,
+    update: MeetingUpdate,
+) -> Result<MeetingData, String> {
+    // 保存先の移行中に書き込むと、移行後に元の保存先ごと削除されて失われる
+    let _storage_lock = app_state.lock_storage()?;
+    ensure_not_recording(&meeting_state, &meeting_id).await?;
+    if update.title.as_deref().is_some_and(|title| title.trim().is_empty()) {
+        return Err("Title must not be empty".to_string());
+    }
+
+    let storage = app_state.meeting_repository().await?;
+    tauri::async_runtime::spawn_blocking(move || {
+        storage.update_meeting(&meeting_id, update).map_err(|e| e.to_string())
+    })
+    .await
+    .map_err(|e| e.to_string())?
+}
+
+/// 会議を削除
+///
+/// `confirmation` を指定しなければ削除せずに対象の内容と確認トークンを返す。UIはそれを表示して
+/// 確認を取り、返されたトークンを `confirmation` に渡して呼び直す。確認後に会議の内容が
+/// 変わっていた場合は削除しない
+#[tauri::command]
+pub async fn delete_meeting(
+    app_state: State<'_, AppState>,
+    meeting_state: State<'_, MeetingState>,
+    meeting_id: MeetingId,
+    confirmation: Option<String>,
+) -> Result<MeetingDeletion, String> {
+    let _storage_lock = app_state.lock_storage()?;
+    ensure_not_recording(&meeting_state, &meeting_id).await?;
+
+    let storage = app_state.meeting_repository().await?;
+    tauri::async_runtime::spawn_blocking(move || {
+        let footprint = storage.meeting_footprint(&meeting_id).map_err(|e| match e {
+            StorageError::NotFound(_) => format!("Meeting not found: {}", meeting_id),
+            e => e.to_string(),
+        })?;
+        let token = footprint.token();
+        // meeting.json のない（異常終了した）会議もIDをタイトル代わりにして削除できるようにする
+        let title = storage
+            .load_meeting(&meeting_id)
+            .map(|meeting| meeting.title)
+            .unwrap_or_else(|_| meeting_id.to_string());
+
+        let deleted = match confirmation {
+            Some(confirmation) if confirmation == token => {
+                storage.delete_meeting(&meeting_id).map_err(|e| e.to_string())?;
+                println!("[Storage] 会議を削除しました: {} ({})", title, meeting_id);
+                true
+            }
+            Some(_) => {
+                return Err("The meeting has changed since deletion was confirmed".to_string());
+            }
+            None => false,
+        };
+
+        Ok(MeetingDeletion {
+            meeting_id,
+            title,
+            size_bytes: footprint.size_bytes,
+            confirmation: token,
+            deleted,
+        })
+    })
+    .await
+    .map_err(|e| e.to_string())?
+}
+
+/// 異常終了して復元できる会議のIDを取得（録音中の会議は除く）
+#[tauri::command]
+pub async fn list_recoverable_meetings(
+    app_state: State<'_, AppState>,
+    meeting_state: State<'_, MeetingState>,
+) -> Result<Vec<MeetingId>, String> {
+    let storage = app_state.meeting_repository().await?;
+    let current = meeting_state.get_current_meeting().await.map(|m| m.id);
+
+    tauri::async_runtime::spawn_blocking(move || {
+        let mut meetings = storage.list_recoverable_meetings().map_err(|e| e.to_string())?;
+        meetings.retain(|id| Some(id) != current.as_ref());
+        Ok(meetings)
+    })
+    .await
+    .map_err(|e| e.to_string())?
+}
+
+/// ジャーナルと音声ファイルから会議を復元して保存
+#[tauri::command]
+pub async fn recover_meeting(
+    app_state: State<'_, AppState>,
+    meeting_state: State<'_, MeetingState>,
+    meeting_id: MeetingId,
+) -> Result<MeetingData, String> {
+    let _storage_lock = app_state.lock_storage()?;
+    if meeting_state.get_current_meeting().await.is_some_and(|m| m.id == meeting_id) {
+        return Err("Cannot recover a meeting that is being recorded".to_string());
+    }
+
+    let storage = app_state.meeting_repository().await?;
+    tauri::async_runtime::spawn_blocking(move || {
+        storage.recover_meeting(&meeting_id).map_err(|e| e.to_string())
+    })
+    .await
+    .map_err(|e| e.to_string())?
+}
+
+/// 保存先ディレクトリを変更（`migrate` なら既存の会議を新しい保存先へ移す）
+///
+/// 移行は全件のコピーと照合が済んでから新しい保存先を設定に保存し、その後で元を削除する。
+/// 途中で失敗した場合はコピーした分を削除して設定も変更しない。
+/// 移行中は録音を開始できない。進捗は "storage_migration_progress" で通知する
+#[tauri::command]
+pub async fn change_save_directory(
+    app_state: State<'_, AppState>,
+    meeting_state: State<'_, MeetingState>,
+    app_handle: tauri::AppHandle,
+    directory: Option<String>,
+    migrate: bool,
+) -> Result<MigrationReport, String> {
+    let _storage_lock = app_state.lock_storage()?;
+    if meeting_state.get_status().await != RecordingStatus::Idle {
+        return Err("Cannot change the save directory while recording".to_string());
+    }
+
+    let mut settings = app_state.get_settings().await;
+    let from = settings.meeting_storage()?;
+    settings.save_directory = directory.filter(|dir| !dir.trim().is_empty());
+    let to = settings.meeting_storage()?;
+    if !migrate {
+        app_state.update_settings(settings).await?;
+        return Ok(MigrationReport::default());
+    }
+
+    // コピー・照合（元は残す）
+    let prepared = {
+        let (from, to) = (from.clone(), to.clone());
+        let app_handle = app_handle.clone();
+        tauri::async_runtime::spawn_blocking(move || {
+            prepare_migration(&from, &to, |progress| emit_migration_progress(&app_handle, progress))
+                .map_err(|e| format!("Migration failed and was rolled back: {}", e))
+        })
+        .await
+        .map_err(|e| e.to_string())??
+    };
+
+    // 元を削除する前に新しい保存先を設定に保存する（保存できなければ移行を取りやめる）
+    if let Err(e) = app_state.update_settings(settings).await {
+        let app_handle = app_handle.clone();
+        tauri::async_runtime::spawn_blocking(move || {
+            prepared.abort(|progress| emit_migration_progress(&app_handle, progress))
+        })
+        .await
+        .map_err(|e| e.to_string())?;
+        return Err(format!("Migration was rolled back because the settings could not be saved: {}", e));
+    }
+
+    tauri::async_runtime::spawn_blocking(move || {
+        prepared.finish(&from, &to, |progress| emit_migration_progress(&app_handle, progress))
+    })
+    .await
+    .map_err(|e| e.to_string())
+}
+
+fn emit_migration_progress(app_handle: &tauri::AppHandle, progress: &MigrationProgress) {
+    if let Err(e) = app_handle.emit("storage_migration_progress", progress) {
+        eprintln!("[Storage] イベント送信失敗: {}", e);
+    }
+}
+
+/// 起動時に復元できる会議を探し、あればUIへ通知する
+pub fn notify_recoverable_meetings<R: tauri::Runtime>(
+    app_handle: &tauri::AppHandle<R>,
+    storage: &dyn MeetingRepository,
+) {
+    let meetings = match storage.list_recoverable_meetings() {
+        Ok(meetings) => meetings,
+        Err(e) => {
+            eprintln!("Failed to look for recoverable meetings: {}", e);
+            return;
+        }
+    };
+    if meetings.is_empty() {
+        return;
+    }
+
+    println!("[Storage] 復元できる会議が {} 件あります", meetings.len());
+    if let Err(e) = app_handle.emit("recoverable_meetings", &meetings) {
+        eprintln!("[Storage] イベント送信失敗: {}", e);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::state::Settings;
+    use gijiroku21_core::storage::{MemoryMeetingRepository, MEETING_SCHEMA_VERSION};
+    use std::sync::Arc;
+    use tauri::Manager;
+
+    #[tokio::test]
+    async fn test_writes_are_rejected_while_storage_is_locked() {
+        let repository = MemoryMeetingRepository::new();
+        let meeting_id = MeetingId::new();
+        repository
+            .save_meeting(&MeetingData {
+                schema_version: MEETING_SCHEMA_VERSION,
+                id: meeting_id.clone(),
+                title: "定例会".to_string(),
+                started_at: chrono::Utc::now(),
+                ended_at: None,
+                transcript: Vec::new(),
+                summary: None,
+                audio_file: None,
+                pauses: Vec::new(),
+                tags: Vec::new(),
+            })
+            .unwrap();
+
+        let app = tauri::test::mock_app();
+        app.manage(
+            AppState::from_settings(Settings::default()).with_repository(Arc::new(repository.clone())),
+        );
+        app.manage(MeetingState::new());
+
+        // 保存先の移行中（ロックを保持している間）は書き込まない
+        let app_state = app.state::<AppState>();
+        let storage_lock = app_state.lock_storage().unwrap();
+        let update = MeetingUpdate {
+            title: Some("変更後".to_string()),
+            ..MeetingUpdate::default()
+        };
+        assert!(update_meeting(app.state(), app.state(), meeting_id.clone(), update.clone())
+            .await
+            .is_err());
+        let confirmation = repository.meeting_footprint(&meeting_id).unwrap().token();
+        assert!(delete_meeting(app.state(), app.state(), meeting_id.clone(), Some(confirmation))
+            .await
+            .is_err());
+        assert!(recover_meeting(app.state(), app.state(), meeting_id.clone()).await.is_err());
+        assert_eq!(repository.load_meeting(&meeting_id).unwrap().title, "定例会");
+
+        // ロックを解放すれば書き込める
+        drop(storage_lock);
+        let updated = update_meeting(app.state(), app.state(), meeting_id.clone(), update)
+            .await
+            .unwrap();
+        assert_eq!(updated.title, "変更後");
+    }
+}
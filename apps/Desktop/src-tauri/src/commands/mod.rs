@@ -1,7 +1,9 @@
 pub mod system;
 pub mod recording;
 pub mod transcription;
+pub mod meetings;
 
 pub use system::*;
 pub use recording::*;
 pub use transcription::*;
+pub use meetings::*;
@@ -1,14 +1,47 @@
-use tauri::State;
-use gijiroku21_core::audio::AudioCapture;
-use gijiroku21_core::storage::MeetingStorage;
+use tauri::{State, Emitter};
+use gijiroku21_core::audio::{
+    analyze_clip, AudioBuffer, AudioCapture, AudioSource, AudioSourceConfig, AudioSubscriber,
+    BusError, ClipAnalysis, CpalSource, InputDeviceInfo, InputTrack, LevelReading, LevelWarning,
+    LevelWatch, WHISPER_SAMPLE_RATE,
+};
+use gijiroku21_core::storage::{
+    AudioSink, JournalEntry, MeetingId, MeetingJournal, MeetingRepository, TranscriptSegment,
+};
+use chrono::{DateTime, Utc};
 use gijiroku21_core::asr::{WhisperModel, StreamingTranscriber, StreamingConfig, AsrModel};
-use std::sync::Arc;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use serde::Serialize;
 use tokio::sync::{mpsc, RwLock};
-use crate::state::{MeetingState, AppState, Settings};
+use crate::state::{MeetingState, AppState, Settings, AudioGap};
 use crate::commands::system::default_model_dir;
 use std::path::PathBuf;
 use crate::commands::transcription::emit_transcript_segment;
 
+/// デバイス状態の確認間隔
+const DEVICE_CHECK_INTERVAL: Duration = Duration::from_secs(1);
+
+/// 音声ファイル書き込みスレッドが新しい音声を待つ最大時間
+const AUDIO_WRITER_POLL_INTERVAL: Duration = Duration::from_millis(500);
+
+/// 自動保存の最短間隔
+const MIN_AUTO_SAVE_INTERVAL: Duration = Duration::from_secs(5);
+
+/// 入力レベルを通知する間隔（約10Hz）
+const LEVEL_METER_INTERVAL: Duration = Duration::from_millis(100);
+
+/// 音声データが途絶えてから切断とみなすまでの時間
+const DEVICE_STALL_TIMEOUT: Duration = Duration::from_secs(3);
+
+/// 録音スレッド内で音声入力ソースを開く関数
+///
+/// cpalのストリームはスレッドをまたげないため、ソース自体ではなく開き方を渡す
+pub type SourceFactory = Box<dyn FnOnce() -> Result<Box<dyn AudioSource>, String> + Send>;
+
+/// 録音スレッドと文字起こしタスクで共有するジャーナル
+type SharedJournal = Arc<Mutex<MeetingJournal>>;
+
 /// 録音コマンド
 pub enum RecordingCommand {
     Stop,
@@ -53,6 +86,10 @@ impl Default for RecordingManager {
 }
 
 /// 録音を開始
+///
+/// 設定の入力デバイスから録音し、システム音声のデバイスが設定されていればミックスする。
+/// ファイル再生などのソースはWebViewから開かせず、`audio_recording_thread` を直接呼ぶ場合のみ使う。
+/// 入力を開けず録音を始められなければ会議を終了して `Err` を返し、"recording_error" を通知する
 #[tauri::command]
 pub async fn start_recording(
     meeting_state: State<'_, MeetingState>,
@@ -60,7 +97,9 @@ pub async fn start_recording(
     app_state: State<'_, AppState>,
     app_handle: tauri::AppHandle,
     title: String,
-) -> Result<String, String> {
+) -> Result<MeetingId, String> {
+    // 保存先の移行中は開始しない（録音中の状態になるまでロックを保持する）
+    let _storage_lock = app_state.lock_storage()?;
     // 設定を取得（モデル/トークナイザーディレクトリ）
     let settings = app_state.get_settings().await;
     // 会議を開始
@@ -74,6 +113,21 @@ pub async fn start_recording(
     // Stateの内部データを取得（TauriのStateはすでにArc<T>をラップしている）
     let meeting_state_handle = meeting_state.inner().clone();
     let settings_clone: Settings = settings.clone();
+    let system_audio = settings.system_audio_device.clone();
+    let source = AudioSourceConfig::Device {
+        name: settings.input_device.clone(),
+    };
+    let open_source: SourceFactory = Box::new(move || {
+        source.open().map_err(|e| format!("Failed to open audio source: {}", e))
+    });
+    // 保存先を開けなくても録音と文字起こしは続ける
+    let storage = match app_state.meeting_repository().await {
+        Ok(storage) => Some(storage),
+        Err(e) => {
+            eprintln!("Failed to open meeting storage: {}", e);
+            None
+        }
+    };
 
     // チャネルを作成
     let (tx, rx) = mpsc::channel::<RecordingCommand>(32);
@@ -82,80 +136,176 @@ pub async fn start_recording(
     recording_manager.set_sender(tx).await;
 
     // 別スレッドで録音処理を実行（std::threadを使用）
+    let (started_tx, started_rx) = tokio::sync::oneshot::channel();
+    let thread_app_handle = app_handle.clone();
     std::thread::spawn(move || {
         // 新しいtokioランタイムを作成
         let rt = tokio::runtime::Runtime::new().expect("Failed to create runtime");
         rt.block_on(async move {
-            audio_recording_thread(rx, meeting_id, meeting_state_handle, settings_clone, app_handle).await;
+            audio_recording_thread(
+                rx,
+                meeting_id,
+                meeting_state_handle,
+                settings_clone,
+                storage,
+                open_source,
+                system_audio,
+                thread_app_handle,
+                started_tx,
+            )
+            .await;
         });
     });
 
-    Ok(meeting_info.id)
+    // 録音が始まったか（入力を開けたか）を待つ
+    let error = match started_rx.await {
+        Ok(Ok(())) => return Ok(meeting_info.id),
+        Ok(Err(e)) => e,
+        Err(_) => "Recording thread exited before starting".to_string(),
+    };
+    meeting_state.end_meeting().await;
+    recording_manager.clear_sender().await;
+    if let Err(e) = app_handle.emit("recording_error", &RecordingErrorEvent {
+        meeting_id: meeting_info.id,
+        error: error.clone(),
+    }) {
+        eprintln!("[Audio] イベント送信失敗: {}", e);
+    }
+    Err(error)
 }
 
 /// 録音処理スレッド（非同期タスク）
-async fn audio_recording_thread(
+///
+/// 入力はデバイスに限らず、`open_source` が開く任意のソース（ファイル再生・パイプなど）を使える。
+/// `system_audio` を指定すると、そのデバイスの音声をマイクとミックスする。
+/// 録音を始められたか（失敗ならその理由）を `started` へ送る
+#[allow(clippy::too_many_arguments)]
+async fn audio_recording_thread<R: tauri::Runtime>(
     mut rx: mpsc::Receiver<RecordingCommand>,
-    meeting_id: String,
+    meeting_id: MeetingId,
     meeting_state: MeetingState,
     settings: Settings,
-    app_handle: tauri::AppHandle,
+    storage: Option<Arc<dyn MeetingRepository>>,
+    open_source: SourceFactory,
+    system_audio: Option<String>,
+    app_handle: tauri::AppHandle<R>,
+    started: tokio::sync::oneshot::Sender<Result<(), String>>,
 ) {
-    // AudioCaptureを初期化
-    let mut capture = match AudioCapture::new() {
-        Ok(c) => c,
+    // 音声入力ソースを開いてAudioCaptureを初期化
+    let source = match open_source() {
+        Ok(source) => source,
         Err(e) => {
-            eprintln!("Failed to create audio capture: {}", e);
+            eprintln!("{}", e);
+            let _ = started.send(Err(e));
             return;
         }
     };
+    let mut capture = match AudioCapture::from_source(source) {
+        Ok(capture) => capture,
+        Err(e) => {
+            let error = format!("Failed to create audio capture: {}", e);
+            eprintln!("{}", error);
+            let _ = started.send(Err(error));
+            return;
+        }
+    };
+    capture.set_processing(settings.audio_processing.clone());
+    if let Err(e) = capture.set_channel_mode(settings.channel_mode) {
+        eprintln!("Failed to prepare channel tracks: {}", e);
+    }
+    if let Some(name) = system_audio.as_deref() {
+        // 見つからないときに既定のデバイス（マイク）で代用すると同じ音声を二重に録るため、
+        // 名前が一致するデバイスだけを使う
+        match CpalSource::open_exact(name) {
+            Ok(source) => capture.set_secondary_source(Some(Box::new(source))),
+            Err(e) => eprintln!("Failed to open system audio device: {}", e),
+        }
+    }
+    capture.set_source_gains(settings.microphone_gain_db, settings.system_audio_gain_db);
 
-    if let Err(e) = capture.initialize() {
-        eprintln!("Failed to initialize audio device: {}", e);
-        return;
+    // 音声ファイルへ書き込むトラックを購読しておく（開始前に購読すれば最初から受け取れる）
+    // （チャンネル別トラックやミックス前のマイク/システム音声も個別に保存）
+    let mut audio_tracks = Vec::new();
+    if storage.is_some() {
+        let channels = (0..capture.channel_count())
+            .filter_map(|ch| capture.subscribe_channel(ch).map(|sub| (Some(channel_label(ch)), sub)));
+        let inputs = [InputTrack::Microphone, InputTrack::System]
+            .into_iter()
+            .filter_map(|track| {
+                capture.subscribe_input(track).map(|sub| (Some(track.label().to_string()), sub))
+            });
+        audio_tracks.extend(
+            std::iter::once((None, capture.subscribe()))
+                .chain(channels)
+                .chain(inputs),
+        );
     }
 
+    // 文字起こし用の16kHz音声を音声バスから受け取り、文字起こしごとのバッファへ貯める
+    // （チャンネル別トラックがあればチャンネルごとに、なければミックスを文字起こしする）
+    let asr_inputs: Vec<_> = if capture.channel_count() > 0 {
+        (0..capture.channel_count())
+            .filter_map(|ch| capture.subscribe_channel_resampled(ch).map(|sub| (Some(ch), sub)))
+            .collect()
+    } else {
+        vec![(None, capture.subscribe_resampled())]
+    }
+    .into_iter()
+    .map(|(channel, subscriber)| {
+        let buffer = Arc::new(AudioBuffer::new(WHISPER_SAMPLE_RATE as usize * 60));
+        let follower = Arc::clone(&buffer);
+        tokio::spawn(async move { follower.follow(subscriber).await });
+        (channel, buffer)
+    })
+    .collect();
+
+    // 保存先にはまだ何も作っていないため、開始できなければ購読を捨てるだけで
+    // 復元候補に見える会議フォルダは残らない
     if let Err(e) = capture.start_recording() {
-        eprintln!("Failed to start recording: {}", e);
+        let error = format!("Failed to start recording: {}", e);
+        eprintln!("{}", error);
+        let _ = started.send(Err(error));
         return;
     }
+    let _ = started.send(Ok(()));
 
-    // ASRモデルの初期化
-    let mut whisper_model = WhisperModel::new();
-
-    // モデルディレクトリを決定（未設定ならプロジェクト相対 models/asr）
-    let model_dir: PathBuf = settings
-        .model_directory
-        .as_ref()
-        .map(PathBuf::from)
-        .unwrap_or_else(default_model_dir);
-
-    // 単一ファイル (whisper-small.onnx) があればそれを優先し、
-    // なければ encoder_model.onnx/decoder_model.onnx を前提にディレクトリを渡す
-    let single_model_path = model_dir.join("whisper-small.onnx");
-    let init_target = if single_model_path.exists() {
-        single_model_path.to_string_lossy().to_string()
-    } else {
-        model_dir.to_string_lossy().to_string()
-    };
-
-    if let Err(e) = whisper_model.initialize(&init_target) {
-        eprintln!("Failed to initialize Whisper model: {}", e);
-        // モデル初期化に失敗した場合も録音自体は続行し、
-        // 文字起こし処理側でエラーとして扱う
+    // 会議全体を音声ファイルへ逐次書き込むスレッドを起動
+    let mut writer_threads = Vec::new();
+    // 値を進めると書き込みスレッドがWAVヘッダを更新する（自動保存のチェックポイント）
+    let audio_checkpoint = Arc::new(AtomicU64::new(0));
+    if let Some(storage) = storage.as_ref() {
+        for (track, subscriber) in audio_tracks {
+            let storage = Arc::clone(storage);
+            let meeting_id = meeting_id.clone();
+            let checkpoint = Arc::clone(&audio_checkpoint);
+            writer_threads.push(std::thread::spawn(move || {
+                run_audio_writer(storage, meeting_id, track, subscriber, checkpoint)
+            }));
+        }
     }
 
-    let model = Arc::new(whisper_model);
+    // 異常終了しても復元できるよう、会議中の出来事をジャーナルへ追記する
+    let journal = open_meeting_journal(storage.as_deref(), &meeting_state).await;
+
+    // ASRモデルの初期化
+    // モデル初期化に失敗した場合も録音自体は続行し、
+    // 文字起こし処理側でエラーとして扱う
+    let model = Arc::new(load_whisper_model(&settings));
+    // ASRにはキャプチャ側で逐次16kHzに変換済みの音声を貯めたバッファを渡す
     let config = StreamingConfig {
         chunk_duration: 30.0,
         interval_sec: 5.0,
-        input_sample_rate: capture.sample_rate(),
+        input_sample_rate: WHISPER_SAMPLE_RATE,
         overlap_duration: 1.0,
     };
     
-    let mut transcriber = StreamingTranscriber::new(model.clone(), config);
-    let buffer = capture.get_buffer();
-    let buffer_arc = Arc::new(buffer);
+    let mut transcribers: Vec<_> = asr_inputs
+        .into_iter()
+        .map(|(channel, buffer)| {
+            let transcriber = StreamingTranscriber::new(model.clone(), config.clone());
+            (transcriber, buffer, channel.map(channel_label))
+        })
+        .collect();
     
     // 文字起こしタスク用のフラグ
     let transcription_enabled = meeting_state.transcription_enabled.clone();
@@ -167,32 +317,50 @@ async fn audio_recording_thread(
         *flag = true;
     }
     
-    // 文字起こしタスクを起動
-    let buffer_for_transcription = buffer_arc.clone();
+    // 文字起こしタスクを起動（停止時は処理中のチャンクを終えてから抜ける）
     let app_handle_clone = app_handle.clone();
-    tokio::spawn(async move {
+    let meeting_state_clone = meeting_state.clone();
+    let journal_clone = journal.clone();
+    let (transcription_stop_tx, mut transcription_stop_rx) = tokio::sync::watch::channel(false);
+    let mut transcription_task = tokio::spawn(async move {
         let mut tick = tokio::time::interval(tokio::time::Duration::from_secs(5));
         
         loop {
-            tick.tick().await;
+            tokio::select! {
+                _ = tick.tick() => {}
+                _ = transcription_stop_rx.changed() => break,
+            }
             
             // 文字起こしが有効な場合のみ処理
-            if *transcription_enabled_clone.read().await {
-                match transcriber.process_next_chunk(&buffer_for_transcription).await {
+            if !*transcription_enabled_clone.read().await {
+                continue;
+            }
+
+            for (transcriber, buffer, label) in transcribers.iter_mut() {
+                match transcriber.process_next_chunk(buffer).await {
                     Ok(Some(segments)) => {
                         for segment in segments {
                             println!("[ASR] {:.2}s - {:.2}s: {}", 
                                 segment.start, segment.end, segment.text);
                             
-                            // UIにイベント送信
+                            // UIにイベント送信（チャンネル別の場合はチャンネル名を話者とする）
                             let ui_segment = crate::commands::transcription::TranscriptSegment {
                                 start: segment.start,
                                 end: segment.end,
                                 text: segment.text.clone(),
                                 confidence: segment.confidence,
-                                speaker: segment.speaker.clone(),
+                                speaker: label.clone().or_else(|| segment.speaker.clone()),
                             };
                             emit_transcript_segment(&app_handle_clone, &ui_segment);
+
+                            // 停止時に meeting.json へ保存するため確定分を保持する
+                            let segment = TranscriptSegment {
+                                timestamp: ui_segment.start,
+                                text: ui_segment.text,
+                                speaker: ui_segment.speaker,
+                            };
+                            append_journal(&journal_clone, &JournalEntry::Segment(segment.clone()));
+                            meeting_state_clone.add_transcript(segment).await;
                         }
                     }
                     Ok(None) => {
@@ -206,51 +374,517 @@ async fn audio_recording_thread(
         }
     });
 
-    // コマンドを待機
-    while let Some(cmd) = rx.recv().await {
-        match cmd {
-            RecordingCommand::Stop => {
-                // 文字起こし停止
-                *transcription_enabled.write().await = false;
-                
-                // 録音を停止して保存
-                if let Err(e) = capture.stop_recording() {
-                    eprintln!("Failed to stop recording: {}", e);
+    // デバイス監視の状態
+    let mut health_tick = tokio::time::interval(DEVICE_CHECK_INTERVAL);
+    let mut paused = false;
+    let mut last_position = capture.timeline_position_sec();
+    let mut last_progress = Instant::now();
+    let mut lost: Option<DeviceLoss> = None;
+    let mut reported_overruns = 0;
+    let mut source_finished = false;
+
+    // 入力レベルの監視
+    let mut level_tick = tokio::time::interval(LEVEL_METER_INTERVAL);
+    let mut level_watch = LevelWatch::default();
+
+    // 自動保存（有効な場合のみ、録音中の会議を一定間隔で保存する）
+    let auto_save_interval =
+        Duration::from_secs(settings.auto_save_interval_sec).max(MIN_AUTO_SAVE_INTERVAL);
+    let mut auto_save_tick = tokio::time::interval_at(
+        tokio::time::Instant::now() + auto_save_interval,
+        auto_save_interval,
+    );
+
+    // コマンドを待機しつつ、定期的にデバイスの状態を確認
+    loop {
+        tokio::select! {
+            cmd = rx.recv() => {
+                let Some(cmd) = cmd else { break };
+                match cmd {
+                    RecordingCommand::Stop => {
+                        // 文字起こし停止
+                        *transcription_enabled.write().await = false;
+
+                        // 録音を停止して保存（音声バスを閉じて受け手に終了を伝える）
+                        if let Err(e) = capture.finish() {
+                            eprintln!("Failed to stop recording: {}", e);
+                        }
+
+                        // 切断中に停止した場合も欠落区間として記録する
+                        if let Some(loss) = lost.take() {
+                            meeting_state.record_gap(loss.into_gap()).await;
+                        }
+
+                        // 書き込みスレッドが残りを書き終えるのを待つ（joinは非同期ワーカーを塞がないよう別スレッドで）
+                        let writers = std::mem::take(&mut writer_threads);
+                        let joined = tokio::task::spawn_blocking(move || {
+                            for handle in writers {
+                                if handle.join().is_err() {
+                                    eprintln!("Audio writer thread panicked");
+                                }
+                            }
+                        })
+                        .await;
+                        if let Err(e) = joined {
+                            eprintln!("Failed to wait for audio writer threads: {}", e);
+                        }
+
+                        // 処理中の文字起こしを待ってから会議データを保存する
+                        let _ = transcription_stop_tx.send(true);
+                        if let Err(e) = (&mut transcription_task).await {
+                            eprintln!("Transcription task failed: {}", e);
+                        }
+
+                        meeting_state.end_meeting().await;
+                        if let Some(ended_at) = meeting_state.get_current_meeting().await.and_then(|m| m.ended_at) {
+                            append_journal(&journal, &JournalEntry::Stopped { at: ended_at });
+                        }
+                        sync_journal(&journal);
+                        if let Some(storage) = storage.as_deref() {
+                            if let Some(saved_at) = save_meeting_data(storage, &meeting_state).await {
+                                report_saved(&app_handle, &meeting_state, &meeting_id, saved_at, true).await;
+                            }
+                        }
+                        break;
+                    }
+                    RecordingCommand::Pause => {
+                        // 文字起こし一時停止
+                        *transcription_enabled.write().await = false;
+
+                        if let Err(e) = capture.stop_recording() {
+                            eprintln!("Failed to pause recording: {}", e);
+                        }
+                        if let Some(loss) = lost.take() {
+                            meeting_state.record_gap(loss.into_gap()).await;
+                        }
+                        paused = true;
+                        let audio_position = capture.timeline_position_sec();
+                        meeting_state.pause(audio_position).await;
+                        append_journal(&journal, &JournalEntry::Paused { at: Utc::now(), audio_position });
+                        sync_journal(&journal);
+                    }
+                    RecordingCommand::Resume => {
+                        // 文字起こし再開
+                        *transcription_enabled.write().await = true;
+
+                        if let Err(e) = capture.start_recording() {
+                            eprintln!("Failed to resume recording: {}", e);
+                        }
+                        paused = false;
+                        last_progress = Instant::now();
+                        level_watch.reset();
+                        meeting_state.resume().await;
+                        append_journal(&journal, &JournalEntry::Resumed { at: Utc::now() });
+                    }
+                }
+            }
+            _ = auto_save_tick.tick(), if settings.auto_save && storage.is_some() => {
+                // 音声ファイルのヘッダとジャーナルを書き出し、途中経過の meeting.json を保存する
+                audio_checkpoint.fetch_add(1, Ordering::Relaxed);
+                sync_journal(&journal);
+                if let Some(storage) = storage.as_deref() {
+                    if let Some(saved_at) = save_meeting_data(storage, &meeting_state).await {
+                        report_saved(&app_handle, &meeting_state, &meeting_id, saved_at, false).await;
+                    }
+                }
+            }
+            _ = level_tick.tick() => {
+                // 一時停止中・切断中は集計が進まないため通知しない
+                if paused || lost.is_some() {
+                    continue;
+                }
+                let Some(reading) = capture.take_level() else { continue };
+
+                if let Err(e) = app_handle.emit("audio_level", &AudioLevelEvent {
+                    meeting_id: meeting_id.clone(),
+                    level: reading,
+                }) {
+                    eprintln!("[Audio] イベント送信失敗: {}", e);
                 }
 
-                let samples = buffer_arc.get_all().await;
-                let sample_rate = capture.sample_rate();
+                for warning in level_watch.update(&reading) {
+                    eprintln!("[Audio] level warning: {:?}", warning);
+                    if let Err(e) = app_handle.emit("audio_level_warning", &AudioLevelWarningEvent {
+                        meeting_id: meeting_id.clone(),
+                        warning,
+                    }) {
+                        eprintln!("[Audio] イベント送信失敗: {}", e);
+                    }
+                }
+            }
+            _ = health_tick.tick() => {
+                // 前回の同期以降に追記したジャーナルをディスクへ書き出す
+                if let Some(journal) = journal.as_ref() {
+                    if let Err(e) = journal.lock().unwrap().sync_if_due() {
+                        eprintln!("Failed to sync meeting journal: {}", e);
+                    }
+                }
+
+                if paused {
+                    continue;
+                }
+
+                // 切断中は経過時間を無音で補填しながら再接続を試みる
+                if let Some(loss) = lost.as_mut() {
+                    let now = Instant::now();
+                    capture.insert_silence((now - loss.filled_until).as_secs_f64());
+                    loss.filled_until = now;
+
+                    match capture.reopen() {
+                        Ok(()) => {
+                            let gap = lost.take().unwrap().into_gap();
+                            emit_device_event(&app_handle, "device_restored", &DeviceEvent {
+                                meeting_id: meeting_id.clone(),
+                                device: capture.device_name(),
+                                reason: gap.reason.clone(),
+                                gap_seconds: Some(gap.duration),
+                            });
+                            meeting_state.record_gap(gap).await;
+
+                            last_position = capture.timeline_position_sec();
+                            last_progress = Instant::now();
+                            level_watch.reset();
+                        }
+                        Err(e) => {
+                            // 再接続は1秒ごとに試みるため、同じ失敗は切断ごとに一度だけ記録する
+                            let error = e.to_string();
+                            if loss.last_error.as_ref() != Some(&error) {
+                                eprintln!("Failed to reopen audio device ({}): {}", loss.reason, error);
+                                loss.last_error = Some(error);
+                            }
+                        }
+                    }
+                    continue;
+                }
+
+                // 取り出しが追いつかず破棄された音声があれば通知
+                let overruns = capture.overrun_count();
+                if overruns > reported_overruns {
+                    eprintln!("[Audio] {} samples dropped (buffer overrun)", overruns - reported_overruns);
+                    reported_overruns = overruns;
+                    if let Err(e) = app_handle.emit("audio_overrun", &OverrunEvent {
+                        meeting_id: meeting_id.clone(),
+                        dropped_samples: overruns,
+                    }) {
+                        eprintln!("[Audio] イベント送信失敗: {}", e);
+                    }
+                }
 
-                // ストレージに保存
-                if let Ok(storage) = MeetingStorage::default_location() {
-                    if let Err(e) = storage.save_audio(&meeting_id, &samples, sample_rate) {
-                        eprintln!("Failed to save audio: {}", e);
+                // ファイル再生などの有限のソースは、読み終えても切断とはみなさない
+                if capture.is_source_finished() {
+                    if !source_finished {
+                        source_finished = true;
+                        println!("[Audio] audio source reached the end ({:.1}s)", capture.timeline_position_sec());
                     }
+                    continue;
                 }
 
-                meeting_state.end_meeting().await;
-                break;
+                let position = capture.timeline_position_sec();
+                if position > last_position {
+                    last_position = position;
+                    last_progress = Instant::now();
+                }
+
+                // ストリームエラー、または一定時間データが届かなければ切断とみなす
+                let reason = capture.take_device_error().or_else(|| {
+                    (last_progress.elapsed() >= DEVICE_STALL_TIMEOUT)
+                        .then(|| "No audio data received".to_string())
+                });
+                if let Some(reason) = reason {
+                    eprintln!("Audio device lost: {}", reason);
+                    let _ = capture.stop_recording();
+                    emit_device_event(&app_handle, "device_lost", &DeviceEvent {
+                        meeting_id: meeting_id.clone(),
+                        device: capture.device_name(),
+                        reason: reason.clone(),
+                        gap_seconds: None,
+                    });
+                    lost = Some(DeviceLoss {
+                        since: last_progress,
+                        filled_until: last_progress,
+                        position: last_position,
+                        reason,
+                        last_error: None,
+                    });
+                }
+            }
+        }
+    }
+}
+
+/// 設定に従ってWhisperモデルを読み込む（失敗時は未ロードのまま返す）
+fn load_whisper_model(settings: &Settings) -> WhisperModel {
+    let mut whisper_model = WhisperModel::new();
+
+    // モデルディレクトリを決定（未設定ならプロジェクト相対 models/asr）
+    let model_dir: PathBuf = settings
+        .model_directory
+        .as_ref()
+        .map(PathBuf::from)
+        .unwrap_or_else(default_model_dir);
+
+    // 単一ファイル (whisper-small.onnx) があればそれを優先し、
+    // なければ encoder_model.onnx/decoder_model.onnx を前提にディレクトリを渡す
+    let single_model_path = model_dir.join("whisper-small.onnx");
+    let init_target = if single_model_path.exists() {
+        single_model_path.to_string_lossy().to_string()
+    } else {
+        model_dir.to_string_lossy().to_string()
+    };
+
+    if let Err(e) = whisper_model.initialize(&init_target) {
+        eprintln!("Failed to initialize Whisper model: {}", e);
+    }
+
+    whisper_model
+}
+
+/// 現在の会議のジャーナルを開いて開始を記録
+async fn open_meeting_journal(
+    storage: Option<&dyn MeetingRepository>,
+    meeting_state: &MeetingState,
+) -> Option<SharedJournal> {
+    let storage = storage?;
+    let meeting = meeting_state.get_current_meeting().await?;
+
+    let journal = match storage.recovery_journal(&meeting.id) {
+        Ok(journal) => journal.map(|journal| Arc::new(Mutex::new(journal))),
+        Err(e) => {
+            eprintln!("Failed to open meeting journal: {}", e);
+            None
+        }
+    };
+    append_journal(&journal, &JournalEntry::Started {
+        meeting_id: meeting.id,
+        title: meeting.title,
+        started_at: meeting.started_at,
+    });
+    sync_journal(&journal);
+    journal
+}
+
+/// ジャーナルに1件追記（失敗しても録音は続ける）
+fn append_journal(journal: &Option<SharedJournal>, entry: &JournalEntry) {
+    if let Some(journal) = journal {
+        if let Err(e) = journal.lock().unwrap().append(entry) {
+            eprintln!("Failed to write meeting journal: {}", e);
+        }
+    }
+}
+
+/// ジャーナルをすぐにディスクへ同期（状態遷移の直後など）
+fn sync_journal(journal: &Option<SharedJournal>) {
+    if let Some(journal) = journal {
+        if let Err(e) = journal.lock().unwrap().sync() {
+            eprintln!("Failed to sync meeting journal: {}", e);
+        }
+    }
+}
+
+/// 現在の会議情報と確定済みの文字起こしを meeting.json に保存
+///
+/// 録音中に呼び出すと終了時刻のない途中経過として保存する。保存できれば保存時刻を返す
+async fn save_meeting_data(
+    storage: &dyn MeetingRepository,
+    meeting_state: &MeetingState,
+) -> Option<DateTime<Utc>> {
+    let meeting = meeting_state.get_current_meeting().await?;
+    let transcript = meeting_state.get_transcript().await;
+
+    let audio_file = storage.audio_file(&meeting.id, None);
+    let data = meeting.to_meeting_data(transcript, audio_file);
+    match storage.save_meeting(&data) {
+        Ok(()) => {
+            println!(
+                "[Storage] 会議を保存しました: {}（{}セグメント）",
+                data.id,
+                data.transcript.len()
+            );
+            Some(Utc::now())
+        }
+        Err(e) => {
+            eprintln!("Failed to save meeting: {}", e);
+            None
+        }
+    }
+}
+
+/// 保存時刻を記録してUIへ通知
+async fn report_saved<R: tauri::Runtime>(
+    app_handle: &tauri::AppHandle<R>,
+    meeting_state: &MeetingState,
+    meeting_id: &MeetingId,
+    saved_at: DateTime<Utc>,
+    finished: bool,
+) {
+    meeting_state.mark_saved(saved_at).await;
+    if let Err(e) = app_handle.emit("meeting_saved", &MeetingSavedEvent {
+        meeting_id: meeting_id.clone(),
+        saved_at,
+        finished,
+    }) {
+        eprintln!("[Storage] イベント送信失敗: {}", e);
+    }
+}
+
+/// チャンネル別トラックの表示名（`channel` は0始まり）
+fn channel_label(channel: usize) -> String {
+    format!("ch{}", channel + 1)
+}
+
+/// キャプチャした音声を会議の音声ファイルへ逐次書き込む
+///
+/// `track` が指定されていればその名前のトラックのファイル（`audio_{track}.wav`）に書き込む。
+/// ファイルは最初に届いた音声のサンプルレートで作成し、以降レートが
+/// 変わった場合は変換して同じファイルに追記する。音声バスが閉じると確定する。
+/// `subscriber` は欠落しない受け手（`AudioCapture::subscribe` など）を渡すこと
+fn run_audio_writer(
+    storage: Arc<dyn MeetingRepository>,
+    meeting_id: MeetingId,
+    track: Option<String>,
+    mut subscriber: AudioSubscriber,
+    checkpoint: Arc<AtomicU64>,
+) {
+    let mut writer: Option<Box<dyn AudioSink>> = None;
+    // 直前に届いた音声のサンプルレート（欠落区間の無音に使う）
+    let mut last_rate = None;
+    let mut flushed_checkpoint = 0;
+
+    loop {
+        // チェックポイントが要求されていればヘッダを更新して再生可能な状態にする
+        let requested = checkpoint.load(Ordering::Relaxed);
+        if requested != flushed_checkpoint {
+            flushed_checkpoint = requested;
+            if let Some(Err(e)) = writer.as_mut().map(|w| w.flush()) {
+                eprintln!("Failed to flush audio file: {}", e);
             }
-            RecordingCommand::Pause => {
-                // 文字起こし一時停止
-                *transcription_enabled.write().await = false;
-                
-                if let Err(e) = capture.stop_recording() {
-                    eprintln!("Failed to pause recording: {}", e);
+        }
+
+        let chunk = match subscriber.recv_blocking(AUDIO_WRITER_POLL_INTERVAL) {
+            Ok(Some(chunk)) => chunk,
+            Ok(None) => continue,
+            Err(BusError::Lagged(lost)) => {
+                // 書き込みが追いつかず失われた区間は無音で埋めて時刻を揃える
+                eprintln!("[Audio] writer fell behind; {} samples lost", lost);
+                if let (Some(w), Some(rate)) = (writer.as_mut(), last_rate) {
+                    let silence = vec![0.0; lost as usize];
+                    if let Err(e) = w.write_at_rate(&silence, rate) {
+                        eprintln!("Failed to write audio: {}", e);
+                        return;
+                    }
                 }
-                meeting_state.pause().await;
+                continue;
             }
-            RecordingCommand::Resume => {
-                // 文字起こし再開
-                *transcription_enabled.write().await = true;
-                
-                if let Err(e) = capture.start_recording() {
-                    eprintln!("Failed to resume recording: {}", e);
+            Err(BusError::Closed) => break,
+        };
+        last_rate = Some(chunk.sample_rate);
+
+        if writer.is_none() {
+            match storage.create_audio_sink(&meeting_id, track.as_deref(), chunk.sample_rate) {
+                Ok(w) => writer = Some(w),
+                Err(e) => {
+                    eprintln!("Failed to create audio file: {}", e);
+                    return;
                 }
-                meeting_state.resume().await;
+            }
+        }
+
+        if let Some(w) = writer.as_mut() {
+            if let Err(e) = w.write_at_rate(&chunk.samples, chunk.sample_rate) {
+                eprintln!("Failed to write audio: {}", e);
+                return;
             }
         }
     }
+
+    if let Some(w) = writer {
+        if let Err(e) = w.finalize() {
+            eprintln!("Failed to finalize audio file: {}", e);
+        }
+    }
+}
+
+/// デバイス切断中の状態
+struct DeviceLoss {
+    /// 最後に音声を受け取った時刻
+    since: Instant,
+    /// 無音で補填済みの時刻
+    filled_until: Instant,
+    /// 切断時点の会議タイムライン上の位置（秒）
+    position: f64,
+    reason: String,
+    /// 直前の再接続の失敗理由
+    last_error: Option<String>,
+}
+
+impl DeviceLoss {
+    fn into_gap(self) -> AudioGap {
+        AudioGap {
+            start: self.position,
+            duration: (self.filled_until - self.since).as_secs_f64(),
+            reason: self.reason,
+        }
+    }
+}
+
+/// UI送信用のデバイス状態イベント
+#[derive(Debug, Clone, Serialize)]
+pub struct DeviceEvent {
+    pub meeting_id: MeetingId,
+    pub device: Option<String>,
+    pub reason: String,
+    /// 補填した欠落区間の長さ（秒）。復帰時のみ
+    pub gap_seconds: Option<f64>,
+}
+
+/// UI送信用の録音開始失敗の通知
+#[derive(Debug, Clone, Serialize)]
+pub struct RecordingErrorEvent {
+    pub meeting_id: MeetingId,
+    pub error: String,
+}
+
+/// UI送信用のオーバーラン通知
+#[derive(Debug, Clone, Serialize)]
+pub struct OverrunEvent {
+    pub meeting_id: MeetingId,
+    /// 録音開始からの累計破棄サンプル数
+    pub dropped_samples: u64,
+}
+
+/// UI送信用の保存通知（自動保存・停止時の保存）
+#[derive(Debug, Clone, Serialize)]
+pub struct MeetingSavedEvent {
+    pub meeting_id: MeetingId,
+    pub saved_at: DateTime<Utc>,
+    /// 停止時の最終保存かどうか
+    pub finished: bool,
+}
+
+/// UI送信用の入力レベル（約10Hz）
+#[derive(Debug, Clone, Serialize)]
+pub struct AudioLevelEvent {
+    pub meeting_id: MeetingId,
+    #[serde(flatten)]
+    pub level: LevelReading,
+}
+
+/// UI送信用の入力レベル警告（無音の継続・繰り返しのクリップ）
+#[derive(Debug, Clone, Serialize)]
+pub struct AudioLevelWarningEvent {
+    pub meeting_id: MeetingId,
+    #[serde(flatten)]
+    pub warning: LevelWarning,
+}
+
+fn emit_device_event<R: tauri::Runtime>(
+    app_handle: &tauri::AppHandle<R>,
+    event: &str,
+    payload: &DeviceEvent,
+) {
+    if let Err(e) = app_handle.emit(event, payload) {
+        eprintln!("[Audio] イベント送信失敗: {}", e);
+    }
 }
 
 /// 録音を停止
@@ -288,6 +922,14 @@ pub async fn get_recording_status(
     Ok(serde_json::to_string(&status).unwrap_or_default())
 }
 
+/// 現在の会議を最後に保存した時刻を取得（未保存ならNone）
+#[tauri::command]
+pub async fn get_last_saved_at(
+    meeting_state: State<'_, MeetingState>,
+) -> Result<Option<DateTime<Utc>>, String> {
+    Ok(meeting_state.get_last_saved_at().await)
+}
+
 /// 利用可能な音声入力デバイス一覧を取得
 #[tauri::command]
 pub async fn list_audio_devices() -> Result<Vec<String>, String> {
@@ -296,3 +938,309 @@ pub async fn list_audio_devices() -> Result<Vec<String>, String> {
     
     Ok(capture.list_input_devices())
 }
+
+/// 音声入力デバイスの詳細情報（対応サンプルレート/チャンネル数）を取得
+#[tauri::command]
+pub async fn list_audio_device_info() -> Result<Vec<InputDeviceInfo>, String> {
+    let capture = AudioCapture::new()
+        .map_err(|e| format!("Failed to create audio capture: {}", e))?;
+
+    Ok(capture.list_input_device_info())
+}
+
+/// マイクテストの録音時間の上限（秒）
+const MIC_TEST_MAX_SECONDS: f64 = 30.0;
+
+/// マイクテストの結果
+#[derive(Debug, Clone, Serialize)]
+pub struct MicTestReport {
+    /// 実際に使用したデバイス名
+    pub device: Option<String>,
+    /// 検出したサンプルレート
+    pub sample_rate: u32,
+    #[serde(flatten)]
+    pub analysis: ClipAnalysis,
+    /// 試し録音の文字起こし（要求時かつモデルが利用可能な場合のみ）
+    pub transcript: Option<String>,
+}
+
+/// 短時間の試し録音でマイクの状態を確認（オンボーディング用）
+///
+/// ノイズフロア・発話レベル・推定SN比・クリップ数・サンプルレートを返し、
+/// `transcribe` が真ならASRに通した結果も付ける
+#[tauri::command]
+pub async fn run_mic_test(
+    app_state: State<'_, AppState>,
+    device: Option<String>,
+    seconds: f64,
+    transcribe: Option<bool>,
+) -> Result<MicTestReport, String> {
+    if !seconds.is_finite() || seconds <= 0.0 {
+        return Err(format!("Invalid test duration: {}", seconds));
+    }
+    let seconds = seconds.min(MIC_TEST_MAX_SECONDS);
+    let settings = app_state.get_settings().await;
+    let transcribe = transcribe.unwrap_or(false);
+
+    // cpalのストリームはスレッドをまたげないため、専用スレッドで録音から分析まで行う
+    tokio::task::spawn_blocking(move || {
+        let mut capture = AudioCapture::new()
+            .map_err(|e| format!("Failed to create audio capture: {}", e))?;
+        match device.as_deref() {
+            Some(name) => capture.initialize_with_device(name),
+            None => capture.initialize(),
+        }
+        .map_err(|e| format!("Failed to initialize audio device: {}", e))?;
+        capture.set_processing(settings.audio_processing.clone());
+
+        capture.start_recording()
+            .map_err(|e| format!("Failed to start recording: {}", e))?;
+        std::thread::sleep(Duration::from_secs_f64(seconds));
+        capture.finish()
+            .map_err(|e| format!("Failed to stop recording: {}", e))?;
+
+        if let Some(error) = capture.take_device_error() {
+            return Err(format!("Audio device error: {}", error));
+        }
+
+        // レベルは前処理前の音声で測る
+        let samples = capture.get_buffer().get_all_blocking();
+        if samples.is_empty() {
+            return Err("No audio data was captured".to_string());
+        }
+        let analysis = analyze_clip(&samples, capture.sample_rate());
+
+        let transcript = if transcribe {
+            let model = load_whisper_model(&settings);
+            if model.is_loaded() {
+                let audio = capture.get_resampled_buffer().get_all_blocking();
+                match model.transcribe(&audio) {
+                    Ok(result) => Some(result.full_text),
+                    Err(e) => {
+                        eprintln!("Mic test transcription failed: {}", e);
+                        None
+                    }
+                }
+            } else {
+                None
+            }
+        } else {
+            None
+        };
+
+        Ok(MicTestReport {
+            device: capture.device_name(),
+            sample_rate: capture.sample_rate(),
+            analysis,
+            transcript,
+        })
+    })
+    .await
+    .map_err(|e| format!("Mic test task failed: {}", e))?
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use gijiroku21_core::audio::capture::{AudioCaptureError, Result as CaptureResult};
+    use gijiroku21_core::audio::{Pacing, SourceFormat, SourceSink, ToneSource};
+    use gijiroku21_core::storage::{MeetingSort, MeetingStorage, MemoryMeetingRepository};
+    use std::f32::consts::PI;
+    use std::sync::atomic::AtomicBool;
+
+    /// 読み終えたことをテストから確かめられるソース
+    struct WatchedSource {
+        inner: ToneSource,
+        finished: Arc<AtomicBool>,
+    }
+
+    impl AudioSource for WatchedSource {
+        fn name(&self) -> Option<String> {
+            self.inner.name()
+        }
+
+        fn format(&self) -> CaptureResult<SourceFormat> {
+            self.inner.format()
+        }
+
+        fn start(&mut self, sink: SourceSink) -> CaptureResult<()> {
+            self.inner.start(sink)
+        }
+
+        fn stop(&mut self) -> CaptureResult<()> {
+            self.inner.stop()
+        }
+
+        fn is_finished(&self) -> bool {
+            let finished = self.inner.is_finished();
+            if finished {
+                self.finished.store(true, Ordering::Release);
+            }
+            finished
+        }
+    }
+
+    #[tokio::test]
+    async fn test_source_open_failure_is_reported() {
+        let meeting_state = MeetingState::new();
+        meeting_state.start_meeting("テスト会議".to_string()).await;
+        let meeting_id = meeting_state.get_current_meeting().await.unwrap().id;
+        let open_source: SourceFactory = Box::new(|| Err("no device".to_string()));
+
+        let app = tauri::test::mock_app();
+        let (_tx, rx) = mpsc::channel(4);
+        let (started_tx, started_rx) = tokio::sync::oneshot::channel();
+        audio_recording_thread(
+            rx,
+            meeting_id,
+            meeting_state,
+            Settings::default(),
+            None,
+            open_source,
+            None,
+            app.handle().clone(),
+            started_tx,
+        )
+        .await;
+
+        assert_eq!(started_rx.await.unwrap(), Err("no device".to_string()));
+    }
+
+    /// 取り込みを開始できないソース
+    struct BrokenSource;
+
+    impl AudioSource for BrokenSource {
+        fn name(&self) -> Option<String> {
+            Some("broken".to_string())
+        }
+
+        fn format(&self) -> CaptureResult<SourceFormat> {
+            Ok(SourceFormat {
+                sample_rate: 16000,
+                channels: 1,
+            })
+        }
+
+        fn start(&mut self, _sink: SourceSink) -> CaptureResult<()> {
+            Err(AudioCaptureError::SourceError("device busy".to_string()))
+        }
+
+        fn stop(&mut self) -> CaptureResult<()> {
+            Ok(())
+        }
+    }
+
+    #[tokio::test]
+    async fn test_start_failure_leaves_nothing_to_recover() {
+        let dir = std::env::temp_dir().join(format!("gijiroku21-start-failure-{}", uuid::Uuid::new_v4()));
+        let storage = MeetingStorage::new(&dir).unwrap();
+        let meeting_state = MeetingState::new();
+        meeting_state.start_meeting("テスト会議".to_string()).await;
+        let meeting_id = meeting_state.get_current_meeting().await.unwrap().id;
+        let open_source: SourceFactory = Box::new(|| Ok(Box::new(BrokenSource) as Box<dyn AudioSource>));
+
+        let app = tauri::test::mock_app();
+        let (_tx, rx) = mpsc::channel(4);
+        let (started_tx, started_rx) = tokio::sync::oneshot::channel();
+        audio_recording_thread(
+            rx,
+            meeting_id,
+            meeting_state,
+            Settings::default(),
+            Some(Arc::new(storage.clone()) as Arc<dyn MeetingRepository>),
+            open_source,
+            None,
+            app.handle().clone(),
+            started_tx,
+        )
+        .await;
+
+        // 会議フォルダ（ジャーナル・音声ファイル）を作っていない
+        assert!(started_rx.await.unwrap().is_err());
+        assert!(storage.list_meetings().unwrap().is_empty());
+        assert!(storage.list_recoverable_meetings().unwrap().is_empty());
+        std::fs::remove_dir_all(dir).ok();
+    }
+
+    #[tokio::test]
+    async fn test_recording_is_saved_to_repository() {
+        const RATE: u32 = 16000;
+        let repository = MemoryMeetingRepository::new();
+        // モデルがなくても録音と保存は続く（文字起こしだけが失敗する）
+        let no_model = std::env::temp_dir().join(format!("gijiroku21-no-model-{}", uuid::Uuid::new_v4()));
+        let settings = Settings {
+            auto_save: false,
+            model_directory: Some(no_model.to_string_lossy().to_string()),
+            ..Settings::default()
+        };
+        let app_state =
+            AppState::from_settings(settings.clone()).with_repository(Arc::new(repository.clone()));
+
+        let meeting_state = MeetingState::new();
+        meeting_state.start_meeting("テスト会議".to_string()).await;
+        let meeting_id = meeting_state.get_current_meeting().await.unwrap().id;
+
+        let finished = Arc::new(AtomicBool::new(false));
+        let source_finished = Arc::clone(&finished);
+        let open_source: SourceFactory = Box::new(move || {
+            let inner = ToneSource::new(440.0, 0.5, RATE, Some(Duration::from_secs(1)), Pacing::RealTime);
+            Ok(Box::new(WatchedSource {
+                inner,
+                finished: source_finished,
+            }) as Box<dyn AudioSource>)
+        });
+
+        let app = tauri::test::mock_app();
+        let (tx, rx) = mpsc::channel(4);
+        let (started_tx, started_rx) = tokio::sync::oneshot::channel();
+        let recording = audio_recording_thread(
+            rx,
+            meeting_id.clone(),
+            meeting_state.clone(),
+            settings,
+            Some(app_state.meeting_repository().await.unwrap()),
+            open_source,
+            None,
+            app.handle().clone(),
+            started_tx,
+        );
+        // 録音が始まり、ソースを読み終えてから停止する
+        let control = async move {
+            assert_eq!(started_rx.await.unwrap(), Ok(()));
+            let deadline = Instant::now() + Duration::from_secs(10);
+            while !finished.load(Ordering::Acquire) {
+                assert!(Instant::now() < deadline, "source did not finish");
+                tokio::time::sleep(Duration::from_millis(50)).await;
+            }
+            tx.send(RecordingCommand::Stop).await.unwrap();
+        };
+        tokio::join!(recording, control);
+
+        assert_eq!(meeting_state.get_status().await, crate::state::RecordingStatus::Idle);
+        let saved = repository.load_meeting(&meeting_id).unwrap();
+        assert_eq!(saved.title, "テスト会議");
+        assert!(saved.ended_at.is_some());
+        assert_eq!(saved.audio_file.as_deref(), Some("audio.wav"));
+        assert!(saved.transcript.is_empty());
+
+        // 会議全体の音声はソースの正弦波そのまま
+        let audio = repository.audio(&meeting_id, None).unwrap();
+        assert_eq!(audio.sample_rate, RATE);
+        assert_eq!(audio.samples.len(), RATE as usize);
+        for (i, sample) in audio.samples.iter().enumerate().step_by(97) {
+            let t = i as f32 / RATE as f32;
+            let expected = 0.5 * (2.0 * PI * 440.0 * t).sin();
+            assert!((sample - expected).abs() < 1e-3, "sample {i}: {sample} != {expected}");
+        }
+
+        // 会議一覧も同じ保存先から取得される
+        let page = app_state
+            .meeting_repository()
+            .await
+            .unwrap()
+            .list_meeting_summaries(MeetingSort::NewestFirst, 0, 10)
+            .unwrap();
+        assert_eq!(page.total, 1);
+        assert_eq!(page.meetings[0].id, meeting_id);
+    }
+}
@@ -34,6 +34,9 @@ pub enum AppError {
     #[error("Invalid input: {0}")]
     InvalidInput(String),
 
+    #[error("Storage error: {0}")]
+    Storage(#[from] gijiroku21_core::storage::StorageError),
+
     #[error("Unknown error: {0}")]
     Unknown(String),
 }
@@ -31,10 +31,21 @@ pub fn run() {
             commands::pause_recording,
             commands::resume_recording,
             commands::get_recording_status,
+            commands::get_last_saved_at,
             commands::list_audio_devices,
+            commands::list_audio_device_info,
+            commands::run_mic_test,
             commands::start_transcription,
             commands::stop_transcription,
             commands::is_transcription_enabled,
+            commands::list_recoverable_meetings,
+            commands::recover_meeting,
+            commands::change_save_directory,
+            commands::list_meetings,
+            commands::search_meetings,
+            commands::get_meeting,
+            commands::update_meeting,
+            commands::delete_meeting,
         ])
         .setup(|app| {
             // アプリ起動時の初期化処理
@@ -47,6 +58,21 @@ pub fn run() {
                 }
             });
 
+            // 異常終了した会議が残っていればUIへ通知
+            let app_handle = app.handle().clone();
+            let app_state = app.state::<AppState>().inner().clone();
+            tauri::async_runtime::spawn(async move {
+                match app_state.meeting_repository().await {
+                    Ok(storage) => {
+                        let _ = tauri::async_runtime::spawn_blocking(move || {
+                            commands::notify_recoverable_meetings(&app_handle, storage.as_ref());
+                        })
+                        .await;
+                    }
+                    Err(e) => eprintln!("Failed to open meeting storage: {}", e),
+                }
+            });
+
             Ok(())
         })
         .run(tauri::generate_context!())
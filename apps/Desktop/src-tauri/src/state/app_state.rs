@@ -1,7 +1,11 @@
 use serde::{Deserialize, Serialize};
 use std::sync::Arc;
-use tokio::sync::RwLock;
+use tokio::sync::{Mutex, MutexGuard, RwLock};
 use crate::error::{AppError, AppResult};
+use gijiroku21_core::audio::{ChannelMode, PipelineConfig};
+use gijiroku21_core::storage::{
+    backup_path, read_with_backup, write_atomic_validated, MeetingRepository, MeetingStorage,
+};
 
 /// NPU検出結果
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -22,12 +26,38 @@ pub struct Settings {
     pub use_llm: bool,
     /// 自動保存設定
     pub auto_save: bool,
-    /// 保存先ディレクトリ
+    /// 自動保存で録音中の会議を保存する間隔（秒）
+    #[serde(default = "default_auto_save_interval_sec")]
+    pub auto_save_interval_sec: u64,
+    /// 保存先ディレクトリ（会議フォルダを置く場所。未指定時はプラットフォームのデータディレクトリ）
     pub save_directory: Option<String>,
     /// モデルディレクトリ（未指定時はプロジェクト相対 models/asr）
     pub model_directory: Option<String>,
     /// Tokenizerディレクトリ（未指定時はプロジェクト相対 models/tokenizer）
     pub tokenizer_directory: Option<String>,
+    /// 録音に使用する入力デバイス名（未指定時はシステム既定のデバイス）
+    #[serde(default)]
+    pub input_device: Option<String>,
+    /// ASR前の音声前処理（各段の有効/無効とパラメータ）
+    #[serde(default)]
+    pub audio_processing: PipelineConfig,
+    /// 複数チャンネル入力の扱い（チャンネルごとに保存・文字起こしするか）
+    #[serde(default)]
+    pub channel_mode: ChannelMode,
+    /// マイクとミックスして録音するシステム音声の入力デバイス名
+    /// （PulseAudio/PipeWireのモニターソースなど。未指定ならマイクのみ）
+    #[serde(default)]
+    pub system_audio_device: Option<String>,
+    /// ミックス時のマイクのゲイン（dB）
+    #[serde(default)]
+    pub microphone_gain_db: f32,
+    /// ミックス時のシステム音声のゲイン（dB）
+    #[serde(default)]
+    pub system_audio_gain_db: f32,
+}
+
+fn default_auto_save_interval_sec() -> u64 {
+    60
 }
 
 impl Default for Settings {
@@ -37,9 +67,16 @@ impl Default for Settings {
             asr_model_size: "small".to_string(),
             use_llm: true,
             auto_save: true,
+            auto_save_interval_sec: default_auto_save_interval_sec(),
             save_directory: None,
             model_directory: None,
             tokenizer_directory: None,
+            input_device: None,
+            audio_processing: PipelineConfig::default(),
+            channel_mode: ChannelMode::default(),
+            system_audio_device: None,
+            microphone_gain_db: 0.0,
+            system_audio_gain_db: 0.0,
         }
     }
 }
@@ -56,26 +93,33 @@ impl Settings {
         Ok(config_dir.join("settings.json"))
     }
 
-    /// 設定を読み込み
+    /// 設定を読み込み（settings.json が壊れていればバックアップを読む）
     pub fn load() -> AppResult<Self> {
         let path = Self::config_path()?;
         
-        if !path.exists() {
+        if !path.exists() && !backup_path(&path).exists() {
             let settings = Self::default();
             settings.save()?;
             return Ok(settings);
         }
 
-        let content = std::fs::read_to_string(path)?;
-        let settings: Settings = serde_json::from_str(&content)?;
-        Ok(settings)
+        read_with_backup(path, |content| -> AppResult<Self> {
+            Ok(serde_json::from_str(content)?)
+        })
+    }
+
+    /// 保存先ディレクトリの設定に従って会議ストレージを開く
+    pub fn meeting_storage(&self) -> AppResult<MeetingStorage> {
+        Ok(MeetingStorage::from_setting(self.save_directory.as_deref())?)
     }
 
     /// 設定を保存
     pub fn save(&self) -> AppResult<()> {
         let path = Self::config_path()?;
         let content = serde_json::to_string_pretty(self)?;
-        std::fs::write(path, content)?;
+        write_atomic_validated(path, content, |current| {
+            serde_json::from_str::<Self>(current).is_ok()
+        })?;
         Ok(())
     }
 }
@@ -87,18 +131,33 @@ pub struct AppState {
     pub npu_info: Arc<RwLock<Option<NpuInfo>>>,
     /// アプリケーション設定
     pub settings: Arc<RwLock<Settings>>,
+    /// 会議の保存先（Noneなら設定の保存先ディレクトリ）
+    repository: Option<Arc<dyn MeetingRepository>>,
+    /// 保存先の変更（移行）と録音開始を排他するロック
+    storage_lock: Arc<Mutex<()>>,
 }
 
 impl AppState {
     pub fn new() -> Self {
-        let settings = Settings::load().unwrap_or_default();
-        
+        Self::from_settings(Settings::load().unwrap_or_default())
+    }
+
+    /// 設定ファイルを読まずに指定の設定で作成（テスト用）
+    pub fn from_settings(settings: Settings) -> Self {
         AppState {
             npu_info: Arc::new(RwLock::new(None)),
             settings: Arc::new(RwLock::new(settings)),
+            repository: None,
+            storage_lock: Arc::new(Mutex::new(())),
         }
     }
 
+    /// 会議の保存先を差し替える（テストでメモリ上のリポジトリを使う場合など）
+    pub fn with_repository(mut self, repository: Arc<dyn MeetingRepository>) -> Self {
+        self.repository = Some(repository);
+        self
+    }
+
     /// NPU情報を初期化
     pub async fn initialize_npu(&self) -> AppResult<()> {
         // Windows での DirectML 存在チェックに基づく簡易検出
@@ -159,6 +218,28 @@ impl AppState {
         Ok(())
     }
 
+    /// 現在の設定の保存先で会議ストレージを開く
+    pub async fn meeting_storage(&self) -> AppResult<MeetingStorage> {
+        self.settings.read().await.meeting_storage()
+    }
+
+    /// 会議の保存先を取得（録音・会議一覧の操作はこれを使う）
+    pub async fn meeting_repository(&self) -> AppResult<Arc<dyn MeetingRepository>> {
+        match &self.repository {
+            Some(repository) => Ok(Arc::clone(repository)),
+            None => Ok(Arc::new(self.meeting_storage().await?)),
+        }
+    }
+
+    /// 保存先を使う操作のロックを取得（保存先の移行中ならエラー）
+    ///
+    /// 保存先の変更は移行が終わるまで、録音開始は録音中の状態になるまで保持する
+    pub fn lock_storage(&self) -> AppResult<MutexGuard<'_, ()>> {
+        self.storage_lock
+            .try_lock()
+            .map_err(|_| AppError::State("The save directory is being changed".to_string()))
+    }
+
     /// NPU情報を取得
     pub async fn get_npu_info(&self) -> Option<NpuInfo> {
         self.npu_info.read().await.clone()
@@ -1,8 +1,11 @@
 use serde::{Deserialize, Serialize};
 use std::sync::Arc;
 use tokio::sync::RwLock;
-use uuid::Uuid;
 use chrono::{DateTime, Utc};
+use gijiroku21_core::storage::{
+    MeetingData, MeetingId, PauseInterval, PauseTimeline, TranscriptSegment,
+    MEETING_SCHEMA_VERSION,
+};
 
 /// 録音状態
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
@@ -17,11 +20,59 @@ pub enum RecordingStatus {
 /// 会議メタデータ
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct MeetingMetadata {
-    pub id: String,
+    pub id: MeetingId,
     pub title: String,
     pub started_at: DateTime<Utc>,
     pub ended_at: Option<DateTime<Utc>>,
+    /// 開始から終了までの経過時間（一時停止中を含む）
     pub duration_seconds: Option<u64>,
+    /// 実際に録音していた時間（一時停止中を除く）
+    #[serde(default)]
+    pub active_duration_seconds: Option<u64>,
+    /// デバイス切断などで音声を取得できなかった区間
+    #[serde(default)]
+    pub audio_gaps: Vec<AudioGap>,
+    /// 一時停止していた区間
+    #[serde(default)]
+    pub pauses: Vec<PauseInterval>,
+}
+
+impl MeetingMetadata {
+    /// 音声上の時刻と実時刻の対応
+    pub fn timeline(&self) -> PauseTimeline<'_> {
+        PauseTimeline::new(self.started_at, &self.pauses)
+    }
+
+    /// 保存用の会議データを作成
+    pub fn to_meeting_data(
+        &self,
+        transcript: Vec<TranscriptSegment>,
+        audio_file: Option<String>,
+    ) -> MeetingData {
+        MeetingData {
+            schema_version: MEETING_SCHEMA_VERSION,
+            id: self.id.clone(),
+            title: self.title.clone(),
+            started_at: self.started_at,
+            ended_at: self.ended_at,
+            transcript,
+            summary: None,
+            audio_file,
+            pauses: self.pauses.clone(),
+            tags: Vec::new(),
+        }
+    }
+}
+
+/// 音声を取得できなかった区間（無音で補填済み）
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AudioGap {
+    /// 区間の開始位置（会議開始からの秒数）
+    pub start: f64,
+    /// 区間の長さ（秒）
+    pub duration: f64,
+    /// 発生理由
+    pub reason: String,
 }
 
 /// 会議の状態
@@ -31,10 +82,12 @@ pub struct MeetingState {
     pub status: Arc<RwLock<RecordingStatus>>,
     /// 現在の会議情報
     pub current_meeting: Arc<RwLock<Option<MeetingMetadata>>>,
-    /// 文字起こし中間結果（リアルタイム表示用）
-    pub transcript_buffer: Arc<RwLock<Vec<String>>>,
+    /// 現在の会議で確定した文字起こしセグメント（停止時に保存する）
+    pub transcript_buffer: Arc<RwLock<Vec<TranscriptSegment>>>,
     /// 文字起こし有効フラグ
     pub transcription_enabled: Arc<RwLock<bool>>,
+    /// 現在の会議を最後に保存した時刻
+    pub last_saved_at: Arc<RwLock<Option<DateTime<Utc>>>>,
 }
 
 impl MeetingState {
@@ -44,21 +97,27 @@ impl MeetingState {
             current_meeting: Arc::new(RwLock::new(None)),
             transcript_buffer: Arc::new(RwLock::new(Vec::new())),
             transcription_enabled: Arc::new(RwLock::new(false)),
+            last_saved_at: Arc::new(RwLock::new(None)),
         }
     }
 
     /// 新しい会議を開始
     pub async fn start_meeting(&self, title: String) {
         let meeting = MeetingMetadata {
-            id: Uuid::new_v4().to_string(),
+            id: MeetingId::new(),
             title,
             started_at: Utc::now(),
             ended_at: None,
             duration_seconds: None,
+            active_duration_seconds: None,
+            audio_gaps: Vec::new(),
+            pauses: Vec::new(),
         };
 
         let mut current = self.current_meeting.write().await;
         *current = Some(meeting);
+        self.transcript_buffer.write().await.clear();
+        *self.last_saved_at.write().await = None;
 
         let mut status = self.status.write().await;
         *status = RecordingStatus::Recording;
@@ -68,30 +127,69 @@ impl MeetingState {
     pub async fn end_meeting(&self) {
         let mut current = self.current_meeting.write().await;
         if let Some(ref mut meeting) = *current {
-            meeting.ended_at = Some(Utc::now());
-            
-            let duration = meeting.ended_at.unwrap()
+            let ended_at = Utc::now();
+            meeting.ended_at = Some(ended_at);
+            // 一時停止したまま終了した場合は終了時刻で区間を閉じる
+            if let Some(pause) = meeting.pauses.last_mut().filter(|p| p.resumed_at.is_none()) {
+                pause.resumed_at = Some(ended_at);
+            }
+
+            let duration = ended_at
                 .signed_duration_since(meeting.started_at)
                 .num_seconds();
             meeting.duration_seconds = Some(duration as u64);
+            meeting.active_duration_seconds = Some(meeting.timeline().active_sec(ended_at) as u64);
         }
 
         let mut status = self.status.write().await;
         *status = RecordingStatus::Idle;
     }
 
-    /// 録音を一時停止
-    pub async fn pause(&self) {
+    /// 録音を一時停止（`audio_position` は一時停止した時点の音声上の位置（秒））
+    pub async fn pause(&self, audio_position: f64) {
+        if let Some(ref mut meeting) = *self.current_meeting.write().await {
+            if meeting.pauses.last().is_none_or(|p| p.resumed_at.is_some()) {
+                meeting.pauses.push(PauseInterval {
+                    paused_at: Utc::now(),
+                    resumed_at: None,
+                    audio_position,
+                });
+            }
+        }
+
         let mut status = self.status.write().await;
         *status = RecordingStatus::Paused;
     }
 
     /// 録音を再開
     pub async fn resume(&self) {
+        if let Some(ref mut meeting) = *self.current_meeting.write().await {
+            if let Some(pause) = meeting.pauses.last_mut().filter(|p| p.resumed_at.is_none()) {
+                pause.resumed_at = Some(Utc::now());
+            }
+        }
+
         let mut status = self.status.write().await;
         *status = RecordingStatus::Recording;
     }
 
+    /// 音声の欠落区間を記録
+    pub async fn record_gap(&self, gap: AudioGap) {
+        if let Some(ref mut meeting) = *self.current_meeting.write().await {
+            meeting.audio_gaps.push(gap);
+        }
+    }
+
+    /// 現在の会議を保存した時刻を記録
+    pub async fn mark_saved(&self, saved_at: DateTime<Utc>) {
+        *self.last_saved_at.write().await = Some(saved_at);
+    }
+
+    /// 現在の会議を最後に保存した時刻を取得
+    pub async fn get_last_saved_at(&self) -> Option<DateTime<Utc>> {
+        *self.last_saved_at.read().await
+    }
+
     /// 現在の録音状態を取得
     pub async fn get_status(&self) -> RecordingStatus {
         self.status.read().await.clone()
@@ -102,10 +200,10 @@ impl MeetingState {
         self.current_meeting.read().await.clone()
     }
 
-    /// 文字起こしテキストを追加
-    pub async fn add_transcript(&self, text: String) {
+    /// 確定した文字起こしセグメントを追加
+    pub async fn add_transcript(&self, segment: TranscriptSegment) {
         let mut buffer = self.transcript_buffer.write().await;
-        buffer.push(text);
+        buffer.push(segment);
     }
 
     /// 文字起こしバッファをクリア
@@ -115,7 +213,7 @@ impl MeetingState {
     }
 
     /// 文字起こし全体を取得
-    pub async fn get_transcript(&self) -> Vec<String> {
+    pub async fn get_transcript(&self) -> Vec<TranscriptSegment> {
         self.transcript_buffer.read().await.clone()
     }
 }
@@ -2,4 +2,4 @@ pub mod app_state;
 pub mod meeting_state;
 
 pub use app_state::{AppState, Settings, NpuInfo};
-pub use meeting_state::MeetingState;
+pub use meeting_state::{MeetingState, AudioGap, RecordingStatus};
@@ -12,9 +12,31 @@ export interface Settings {
   asr_model_size: string;
   use_llm: boolean;
   auto_save: boolean;
+  /** 自動保存の間隔（秒） */
+  auto_save_interval_sec?: number;
   save_directory: string | null;
   model_directory?: string | null;
   tokenizer_directory?: string | null;
+  input_device?: string | null;
+  audio_processing?: AudioProcessingConfig;
+  channel_mode?: "mixdown" | "separate";
+  /** マイクとミックスするシステム音声（モニターソース）の入力デバイス名 */
+  system_audio_device?: string | null;
+  microphone_gain_db?: number;
+  system_audio_gain_db?: number;
+}
+
+export interface AudioProcessingConfig {
+  dc_removal: boolean;
+  high_pass: boolean;
+  high_pass_cutoff_hz: number;
+  agc: boolean;
+  agc_target_dbfs: number;
+  agc_max_gain_db: number;
+  noise_suppression: boolean;
+  noise_suppression_strength: number;
+  limiter: boolean;
+  limiter_threshold: number;
 }
 
 export interface SystemInfo {
@@ -105,3 +127,249 @@ export async function getRecordingStatus(): Promise<RecordingStatus> {
 export async function listAudioDevices(): Promise<string[]> {
   return await invoke<string[]>("list_audio_devices");
 }
+
+// 音声入力デバイスの詳細情報
+export interface InputDeviceInfo {
+  name: string;
+  is_default: boolean;
+  default_sample_rate: number | null;
+  default_channels: number | null;
+  sample_rates: number[];
+  channels: number[];
+  sample_formats: string[];
+}
+
+// 音声デバイスの詳細情報一覧を取得
+export async function listAudioDeviceInfo(): Promise<InputDeviceInfo[]> {
+  return await invoke<InputDeviceInfo[]>("list_audio_device_info");
+}
+
+// マイクテストの結果
+export interface MicTestReport {
+  device: string | null;
+  sample_rate: number;
+  noise_floor_dbfs: number;
+  speech_level_dbfs: number;
+  snr_db: number;
+  peak_dbfs: number;
+  clipped_samples: number;
+  duration_sec: number;
+  transcript: string | null;
+}
+
+// 短時間の試し録音でマイクをテスト（transcribe=trueで文字起こしも実行）
+export async function runMicTest(
+  device: string | null,
+  seconds: number,
+  transcribe = false
+): Promise<MicTestReport> {
+  return await invoke<MicTestReport>("run_mic_test", { device, seconds, transcribe });
+}
+
+// 音声デバイスの切断/復帰イベント（"device_lost" / "device_restored"）
+export interface DeviceEvent {
+  meeting_id: string;
+  device: string | null;
+  reason: string;
+  gap_seconds: number | null;
+}
+
+// 録音を開始できなかったときの通知（"recording_error"）
+export interface RecordingErrorEvent {
+  meeting_id: string;
+  error: string;
+}
+
+// 音声バッファのオーバーラン通知（"audio_overrun"）
+export interface OverrunEvent {
+  meeting_id: string;
+  dropped_samples: number;
+}
+
+export interface AudioLevelEvent {
+  meeting_id: string;
+  rms_dbfs: number;
+  peak_dbfs: number;
+  clipped_samples: number;
+  duration_sec: number;
+}
+
+export type AudioLevelWarningEvent =
+  | { meeting_id: string; kind: "silence"; seconds: number }
+  | { meeting_id: string; kind: "clipping"; clipped_blocks: number };
+
+export interface TranscriptEntry {
+  timestamp: number;
+  text: string;
+  speaker: string | null;
+}
+
+export interface PauseInterval {
+  paused_at: string;
+  resumed_at: string | null;
+  audio_position: number;
+}
+
+// 保存済みの会議データ（meeting.json）
+export interface MeetingData {
+  schema_version: number;
+  id: string;
+  title: string;
+  started_at: string;
+  ended_at: string | null;
+  transcript: TranscriptEntry[];
+  summary: string | null;
+  audio_file: string | null;
+  pauses: PauseInterval[];
+  tags: string[];
+}
+
+// 異常終了して復元できる会議のID（起動時は "recoverable_meetings" イベントでも通知）
+export async function listRecoverableMeetings(): Promise<string[]> {
+  return await invoke<string[]>("list_recoverable_meetings");
+}
+
+// ジャーナルと音声ファイルから会議を復元
+export async function recoverMeeting(meetingId: string): Promise<MeetingData> {
+  return await invoke<MeetingData>("recover_meeting", { meetingId });
+}
+
+// 会議の保存通知（"meeting_saved"）。finished=false は録音中の自動保存
+export interface MeetingSavedEvent {
+  meeting_id: string;
+  saved_at: string;
+  finished: boolean;
+}
+
+// 現在の会議を最後に保存した時刻（未保存ならnull）
+export async function getLastSavedAt(): Promise<string | null> {
+  return await invoke<string | null>("get_last_saved_at");
+}
+
+// 保存先移行の進捗（"storage_migration_progress"）
+export interface MigrationProgress {
+  phase: "copying" | "verifying" | "removing" | "rolling_back";
+  meeting_id: string;
+  completed: number;
+  total: number;
+}
+
+// 保存先移行の結果
+export interface MigrationReport {
+  migrated: string[];
+  not_removed: string[];
+  bytes_copied: number;
+}
+
+// 保存先ディレクトリを変更（migrate=true なら既存の会議を移行。失敗時は元に戻して設定も変えない）
+export async function changeSaveDirectory(
+  directory: string | null,
+  migrate: boolean
+): Promise<MigrationReport> {
+  return await invoke<MigrationReport>("change_save_directory", { directory, migrate });
+}
+
+// 会議一覧の1件
+export interface MeetingSummary {
+  id: string;
+  title: string;
+  started_at: string;
+  ended_at: string | null;
+  duration_sec: number | null; // 一時停止を除いた録音時間
+  tags: string[];
+  snippet: string;
+}
+
+export type MeetingSort = "newest_first" | "oldest_first" | "title" | "longest_first";
+
+export interface MeetingPage {
+  meetings: MeetingSummary[];
+  total: number; // ページ分割前の件数
+  unreadable: UnreadableMeeting[]; // meeting.json を読み込めなかった会議
+}
+
+export interface UnreadableMeeting {
+  meeting_id: string;
+  error: string;
+}
+
+// 会議の編集内容（省略した項目は変更しない。summary="" で要約を削除）
+export interface MeetingUpdate {
+  title?: string;
+  summary?: string;
+  transcript?: TranscriptEntry[];
+  tags?: string[];
+}
+
+// 会議削除の確認内容・結果
+export interface MeetingDeletion {
+  meeting_id: string;
+  title: string;
+  size_bytes: number;
+  confirmation: string;
+  deleted: boolean;
+}
+
+// 保存済みの会議一覧（既定は新しい順に50件）
+export async function listMeetings(options?: {
+  sort?: MeetingSort;
+  offset?: number;
+  limit?: number;
+}): Promise<MeetingPage> {
+  return await invoke<MeetingPage>("list_meetings", {
+    sort: options?.sort ?? null,
+    offset: options?.offset ?? null,
+    limit: options?.limit ?? null,
+  });
+}
+
+export async function getMeeting(meetingId: string): Promise<MeetingData> {
+  return await invoke<MeetingData>("get_meeting", { meetingId });
+}
+
+export async function updateMeeting(meetingId: string, update: MeetingUpdate): Promise<MeetingData> {
+  return await invoke<MeetingData>("update_meeting", { meetingId, update });
+}
+
+// confirmation なしで削除対象と確認トークンを取得し、そのトークンを渡すと削除
+export async function deleteMeeting(
+  meetingId: string,
+  confirmation?: string
+): Promise<MeetingDeletion> {
+  return await invoke<MeetingDeletion>("delete_meeting", {
+    meetingId,
+    confirmation: confirmation ?? null,
+  });
+}
+
+// 会議の検索条件（text は空白区切りの語をすべて含む会議を探す）
+export interface SearchQuery {
+  text?: string;
+  from?: string; // 開始日時がこの時刻以降（ISO 8601）
+  to?: string; // 開始日時がこの時刻より前
+  tags?: string[]; // すべて付いている会議
+  speaker?: string;
+  limit?: number;
+}
+
+export interface SnippetPart {
+  text: string;
+  highlighted: boolean;
+}
+
+export interface SearchHit {
+  position: number;
+  timestamp: number; // 音声上の時刻（秒）
+  speaker: string | null;
+  snippet: SnippetPart[];
+}
+
+export interface SearchResult {
+  meeting: MeetingSummary;
+  hits: SearchHit[];
+  hit_count: number;
+}
+
+export async function searchMeetings(query: SearchQuery): Promise<SearchResult[]> {
+  return await invoke<SearchResult[]>("search_meetings", { query });
+}
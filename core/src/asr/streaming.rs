@@ -69,9 +69,9 @@ impl<M: AsrModel + Send + Sync> StreamingTranscriber<M> {
             return Ok(None);
         }
         
-        // 最新のチャンクを取得
-        let chunk = buffer
-            .get_chunk(self.config.chunk_duration, self.config.input_sample_rate)
+        // 最新のチャンクを、会議開始からの絶対的な先頭時刻とともに取得
+        let (chunk_start_time, chunk) = buffer
+            .get_latest_chunk(self.config.chunk_duration, self.config.input_sample_rate)
             .await;
         
         if chunk.is_empty() {
@@ -84,8 +84,7 @@ impl<M: AsrModel + Send + Sync> StreamingTranscriber<M> {
         // ASR実行
         let result = self.model.transcribe(&resampled)?;
         
-        // タイムスタンプを調整（バッファの開始位置からの相対時間）
-        let chunk_start_time = buffer_duration - (chunk.len() as f64 / self.config.input_sample_rate as f64);
+        // タイムスタンプを調整（チャンク内の相対時刻 → 会議開始からの時刻）
         let adjusted_segments: Vec<TranscriptionSegment> = result
             .segments
             .into_iter()
@@ -208,4 +207,24 @@ mod tests {
         assert!(result2.is_ok());
         assert!(result2.unwrap().is_none());
     }
+
+    #[tokio::test]
+    async fn test_segment_timestamps_survive_buffer_eviction() {
+        let model = Arc::new(DummyModel);
+        let config = StreamingConfig {
+            chunk_duration: 5.0,
+            interval_sec: 2.0,
+            input_sample_rate: 16000,
+            overlap_duration: 0.5,
+        };
+        let mut transcriber = StreamingTranscriber::new(model, config);
+
+        // 10秒分のバッファに90秒分を書き込み、先頭80秒は削除済み
+        let buffer = AudioBuffer::new(16000 * 10);
+        buffer.push(&vec![0.1; 16000 * 90]).await;
+
+        let segments = transcriber.process_next_chunk(&buffer).await.unwrap().unwrap();
+        assert!((segments[0].start - 85.0).abs() < 1e-6);
+        assert!((segments[0].end - 90.0).abs() < 1e-6);
+    }
 }
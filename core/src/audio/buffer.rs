@@ -1,64 +1,150 @@
+use super::bus::{AudioSubscriber, BusError};
 use std::sync::Arc;
+use thiserror::Error;
 use tokio::sync::RwLock;
 
+#[derive(Debug, Error, PartialEq)]
+pub enum BufferError {
+    #[error("Audio range starting at sample {requested} has been evicted (oldest available: {available})")]
+    Evicted { requested: u64, available: u64 },
+}
+
+/// バッファの中身と、書き込み開始からの累計サンプル数
+struct BufferState {
+    samples: Vec<f32>,
+    total_written: u64,
+}
+
+impl BufferState {
+    /// 保持している先頭サンプルの絶対位置
+    fn start_sample(&self) -> u64 {
+        self.total_written - self.samples.len() as u64
+    }
+
+    fn append(&mut self, samples: &[f32], capacity: usize) {
+        self.total_written += samples.len() as u64;
+
+        // 容量を超えた場合は古いデータを削除
+        if samples.len() >= capacity {
+            self.samples.clear();
+            self.samples.extend_from_slice(&samples[samples.len() - capacity..]);
+            return;
+        }
+        if self.samples.len() + samples.len() > capacity {
+            let overflow = self.samples.len() + samples.len() - capacity;
+            self.samples.drain(0..overflow);
+        }
+        self.samples.extend_from_slice(samples);
+    }
+
+    fn append_silence(&mut self, count: u64, capacity: usize) {
+        let retained = count.min(capacity as u64) as usize;
+        self.append(&vec![0.0; retained], capacity);
+        self.total_written += count - retained as u64;
+    }
+}
+
 /// リングバッファ形式の音声データ保存
+///
+/// 保持するのは直近 `capacity` サンプルのみだが、位置と長さは書き込み開始からの
+/// 絶対サンプル数で扱うため、古いデータが削除されてもタイムスタンプはずれない
 pub struct AudioBuffer {
-    buffer: Arc<RwLock<Vec<f32>>>,
+    state: Arc<RwLock<BufferState>>,
     capacity: usize,
 }
 
 impl AudioBuffer {
     pub fn new(capacity: usize) -> Self {
         AudioBuffer {
-            buffer: Arc::new(RwLock::new(Vec::with_capacity(capacity))),
+            state: Arc::new(RwLock::new(BufferState {
+                samples: Vec::with_capacity(capacity),
+                total_written: 0,
+            })),
             capacity,
         }
     }
 
     /// 音声データを追加（非同期）
     pub async fn push(&self, samples: &[f32]) {
-        let mut buffer = self.buffer.write().await;
-        
-        // 容量を超えた場合は古いデータを削除
-        if buffer.len() + samples.len() > self.capacity {
-            let overflow = buffer.len() + samples.len() - self.capacity;
-            buffer.drain(0..overflow);
-        }
-        
-        buffer.extend_from_slice(samples);
+        self.state.write().await.append(samples, self.capacity);
     }
 
     /// 音声データを追加（同期版）
     /// cpal のコールバックスレッドなど、Tokio ランタイム外から呼び出すために使用
     pub fn push_blocking(&self, samples: &[f32]) {
-        let mut buffer = self.buffer.blocking_write();
+        self.state.blocking_write().append(samples, self.capacity);
+    }
+
+    /// 無音を追加
+    ///
+    /// 保持するのは容量分までだが、タイムラインは `count` サンプル分すべて進める
+    pub async fn push_silence(&self, count: u64) {
+        self.state.write().await.append_silence(count, self.capacity);
+    }
+
+    /// 無音を追加（同期版）
+    pub fn push_silence_blocking(&self, count: u64) {
+        self.state.blocking_write().append_silence(count, self.capacity);
+    }
 
-        if buffer.len() + samples.len() > self.capacity {
-            let overflow = buffer.len() + samples.len() - self.capacity;
-            buffer.drain(0..overflow);
+    /// 音声バスの受け手から読み続けてバッファへ追加（バスが閉じるまで）
+    ///
+    /// 読み遅れて欠落した区間は無音で埋め、バッファ上の位置を配信側と揃える
+    pub async fn follow(&self, mut subscriber: AudioSubscriber) {
+        loop {
+            match subscriber.recv().await {
+                Ok(chunk) => self.push(&chunk.samples).await,
+                Err(BusError::Lagged(lost)) => self.push_silence(lost).await,
+                Err(BusError::Closed) => break,
+            }
         }
+    }
 
-        buffer.extend_from_slice(samples);
+    /// 保持できる最大サンプル数
+    pub fn capacity(&self) -> usize {
+        self.capacity
     }
 
     /// バッファ内の全データを取得
     pub async fn get_all(&self) -> Vec<f32> {
-        self.buffer.read().await.clone()
+        self.state.read().await.samples.clone()
+    }
+
+    /// バッファ内の全データを取得（同期版。Tokio ランタイム外から呼び出す）
+    pub fn get_all_blocking(&self) -> Vec<f32> {
+        self.state.blocking_read().samples.clone()
     }
 
     /// バッファをクリア
+    ///
+    /// 保持しているデータのみ破棄し、累計サンプル数（タイムライン）は維持する
     pub async fn clear(&self) {
-        self.buffer.write().await.clear();
+        self.state.write().await.samples.clear();
+    }
+
+    /// バッファをクリア（同期版）
+    pub fn clear_blocking(&self) {
+        self.state.blocking_write().samples.clear();
     }
 
     /// バッファ内のサンプル数
     pub async fn len(&self) -> usize {
-        self.buffer.read().await.len()
+        self.state.read().await.samples.len()
     }
 
     /// バッファが空かどうか
     pub async fn is_empty(&self) -> bool {
-        self.buffer.read().await.is_empty()
+        self.state.read().await.samples.is_empty()
+    }
+
+    /// 書き込み開始からの累計サンプル数
+    pub async fn total_written(&self) -> u64 {
+        self.state.read().await.total_written
+    }
+
+    /// 保持している先頭サンプルの絶対位置
+    pub async fn start_sample(&self) -> u64 {
+        self.state.read().await.start_sample()
     }
 
     /// 最新のN秒分のチャンクを取得（ASR用）
@@ -70,43 +156,135 @@ impl AudioBuffer {
     /// # Returns
     /// 指定された長さの音声データ。データが不足している場合は利用可能な分のみ返す
     pub async fn get_chunk(&self, duration_sec: f32, sample_rate: u32) -> Vec<f32> {
+        self.get_latest_chunk(duration_sec, sample_rate).await.1
+    }
+
+    /// 最新のN秒分のチャンクを、その先頭の絶対位置（秒）とともに取得
+    ///
+    /// 位置とデータを同じロック内で取得するため、書き込みと競合してもずれない
+    pub async fn get_latest_chunk(&self, duration_sec: f32, sample_rate: u32) -> (f64, Vec<f32>) {
         let chunk_size = (duration_sec * sample_rate as f32) as usize;
-        let buffer = self.buffer.read().await;
-        
-        if buffer.len() <= chunk_size {
-            // バッファ全体を返す
-            buffer.clone()
-        } else {
-            // 最新のchunk_size分を返す
-            buffer[buffer.len() - chunk_size..].to_vec()
-        }
+        let state = self.state.read().await;
+        let start_idx = state.samples.len().saturating_sub(chunk_size);
+        let start_sample = state.start_sample() + start_idx as u64;
+
+        (
+            start_sample as f64 / sample_rate as f64,
+            state.samples[start_idx..].to_vec(),
+        )
     }
 
-    /// 指定された範囲のチャンクを取得（タイムスタンプベース）
+    /// 指定された範囲のチャンクを取得（会議開始からの絶対時刻）
     /// 
     /// # Arguments
     /// * `start_sec` - 開始位置（秒）
     /// * `end_sec` - 終了位置（秒）
     /// * `sample_rate` - サンプルレート（Hz）
-    pub async fn get_chunk_range(&self, start_sec: f32, end_sec: f32, sample_rate: u32) -> Vec<f32> {
-        let start_sample = (start_sec * sample_rate as f32) as usize;
-        let end_sample = (end_sec * sample_rate as f32) as usize;
-        let buffer = self.buffer.read().await;
-        
-        let start_idx = start_sample.min(buffer.len());
-        let end_idx = end_sample.min(buffer.len());
-        
-        if start_idx >= end_idx {
-            return Vec::new();
+    ///
+    /// # Returns
+    /// 指定範囲の音声データ（未書き込みの部分は含まない）。
+    /// 開始位置がすでに削除されている場合は `BufferError::Evicted`
+    pub async fn get_chunk_range(
+        &self,
+        start_sec: f32,
+        end_sec: f32,
+        sample_rate: u32,
+    ) -> Result<Vec<f32>, BufferError> {
+        let start_sample = (start_sec as f64 * sample_rate as f64) as u64;
+        let end_sample = (end_sec as f64 * sample_rate as f64) as u64;
+        let state = self.state.read().await;
+
+        let available = state.start_sample();
+        if start_sample < available {
+            return Err(BufferError::Evicted { requested: start_sample, available });
         }
-        
-        buffer[start_idx..end_idx].to_vec()
+
+        let end_sample = end_sample.min(state.total_written);
+        if start_sample >= end_sample {
+            return Ok(Vec::new());
+        }
+
+        let start_idx = (start_sample - available) as usize;
+        let end_idx = (end_sample - available) as usize;
+        Ok(state.samples[start_idx..end_idx].to_vec())
     }
 
-    /// バッファの総時間を取得（秒）
+    /// 書き込み開始からの総時間を取得（秒）
     pub async fn duration_sec(&self, sample_rate: u32) -> f32 {
-        let len = self.len().await;
-        len as f32 / sample_rate as f32
+        let total = self.total_written().await;
+        (total as f64 / sample_rate as f64) as f32
     }
 }
 
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_duration_is_absolute_after_eviction() {
+        let buffer = AudioBuffer::new(100);
+        buffer.push(&[0.0; 80]).await;
+        buffer.push(&[0.0; 80]).await;
+
+        assert_eq!(buffer.len().await, 100);
+        assert_eq!(buffer.total_written().await, 160);
+        assert_eq!(buffer.start_sample().await, 60);
+        assert_eq!(buffer.duration_sec(10).await, 16.0);
+    }
+
+    #[tokio::test]
+    async fn test_chunk_range_uses_absolute_positions() {
+        let buffer = AudioBuffer::new(100);
+        let samples: Vec<f32> = (0..160).map(|i| i as f32).collect();
+        buffer.push(&samples).await;
+
+        // 先頭60サンプルは削除済み
+        let chunk = buffer.get_chunk_range(7.0, 9.0, 10).await.unwrap();
+        assert_eq!(chunk, (70..90).map(|i| i as f32).collect::<Vec<_>>());
+
+        let evicted = buffer.get_chunk_range(5.0, 9.0, 10).await;
+        assert_eq!(evicted, Err(BufferError::Evicted { requested: 50, available: 60 }));
+
+        // 未書き込みの範囲は切り詰める
+        let tail = buffer.get_chunk_range(15.0, 20.0, 10).await.unwrap();
+        assert_eq!(tail.len(), 10);
+    }
+
+    #[tokio::test]
+    async fn test_latest_chunk_reports_start_time() {
+        let buffer = AudioBuffer::new(100);
+        buffer.push(&[0.0; 250]).await;
+
+        let (start, chunk) = buffer.get_latest_chunk(5.0, 10).await;
+        assert_eq!(chunk.len(), 50);
+        assert_eq!(start, 20.0);
+    }
+
+    #[test]
+    fn test_silence_advances_timeline_beyond_capacity() {
+        let buffer = AudioBuffer::new(100);
+        buffer.push_blocking(&[1.0; 10]);
+        buffer.push_silence_blocking(1000);
+
+        let rt = tokio::runtime::Runtime::new().unwrap();
+        assert_eq!(rt.block_on(buffer.total_written()), 1010);
+        assert_eq!(rt.block_on(buffer.len()), 100);
+    }
+
+    #[tokio::test]
+    async fn test_follow_fills_lagged_range_with_silence() {
+        use crate::audio::{AudioBus, AudioChunk};
+
+        let bus = AudioBus::new(4);
+        let buffer = AudioBuffer::new(100);
+        let subscriber = bus.subscribe();
+        for value in [1.0, 2.0, 3.0] {
+            bus.publish(AudioChunk { sample_rate: 16000, samples: vec![value; 2] });
+        }
+        bus.close();
+
+        buffer.follow(subscriber).await;
+        assert_eq!(buffer.total_written().await, 6);
+        assert_eq!(buffer.get_all().await, vec![0.0, 0.0, 2.0, 2.0, 3.0, 3.0]);
+    }
+}
@@ -0,0 +1,365 @@
+/// 複数の受け手に音声を配信するブロードキャスト型バス
+///
+/// ディスク書き込み・ASR・VAD・レベルメーター・話者分離など、それぞれの受け手が
+/// 独立した読み出し位置を持ち、前回以降に追加された音声だけを受け取る。
+/// 保持量を超えて読み遅れた受け手には、失われたサンプル数を `BusError::Lagged` で通知する。
+/// ディスク保存のように欠落が許されない受け手は、読み終えるまで音声を保持させる
+/// `subscribe_lossless` を使う
+use std::collections::{HashMap, VecDeque};
+use std::sync::{Arc, Condvar, Mutex};
+use std::time::Duration;
+use thiserror::Error;
+use tokio::sync::Notify;
+
+use super::AudioChunk;
+
+#[derive(Debug, Error, PartialEq)]
+pub enum BusError {
+    #[error("Subscriber fell behind; {0} samples were dropped")]
+    Lagged(u64),
+
+    #[error("Audio bus closed")]
+    Closed,
+}
+
+struct BusState {
+    /// 保持中のチャンク（先頭サンプルの絶対位置とともに保持）
+    chunks: VecDeque<(u64, Arc<AudioChunk>)>,
+    /// 保持中のサンプル数
+    retained: usize,
+    /// 配信開始からの累計サンプル数
+    total_written: u64,
+    closed: bool,
+    /// 欠落させない受け手ごとの読み出し位置（これより後の音声は保持量を超えても削除しない）
+    pins: HashMap<u64, u64>,
+    next_pin: u64,
+}
+
+impl BusState {
+    /// 保持している最古のサンプルの絶対位置
+    fn oldest(&self) -> u64 {
+        self.chunks.front().map(|(start, _)| *start).unwrap_or(self.total_written)
+    }
+
+    /// 容量を超えた古いチャンクを削除（最新のチャンクと、欠落させない受け手が未読のチャンクは残す）
+    fn evict(&mut self, capacity: usize) {
+        let pinned = self.pins.values().min().copied().unwrap_or(u64::MAX);
+        while self.retained > capacity && self.chunks.len() > 1 {
+            let (start, oldest) = &self.chunks[0];
+            if start + oldest.samples.len() as u64 > pinned {
+                break;
+            }
+            self.retained -= oldest.samples.len();
+            self.chunks.pop_front();
+        }
+    }
+
+    /// `cursor` 以降のチャンクを取り出して `cursor` を進める
+    fn take(&self, cursor: &mut u64) -> Result<Option<AudioChunk>, BusError> {
+        let oldest = self.oldest();
+        if *cursor < oldest {
+            let lost = oldest - *cursor;
+            *cursor = oldest;
+            return Err(BusError::Lagged(lost));
+        }
+
+        let mut merged: Option<AudioChunk> = None;
+        for (start, chunk) in self.chunks.iter() {
+            if *start < *cursor {
+                continue;
+            }
+            match merged.as_mut() {
+                None => merged = Some(AudioChunk::clone(chunk)),
+                Some(m) if m.sample_rate == chunk.sample_rate => {
+                    m.samples.extend_from_slice(&chunk.samples)
+                }
+                Some(_) => break,
+            }
+        }
+
+        match merged {
+            Some(chunk) => {
+                *cursor += chunk.samples.len() as u64;
+                Ok(Some(chunk))
+            }
+            None if self.closed => Err(BusError::Closed),
+            None => Ok(None),
+        }
+    }
+}
+
+struct BusShared {
+    state: Mutex<BusState>,
+    capacity: usize,
+    /// 同期の受け手向け通知
+    changed: Condvar,
+    /// 非同期の受け手向け通知
+    notify: Notify,
+}
+
+/// 音声バス（クローンすると同じバスを指す）
+#[derive(Clone)]
+pub struct AudioBus {
+    shared: Arc<BusShared>,
+}
+
+impl AudioBus {
+    /// 新しいバスを作成
+    ///
+    /// # Arguments
+    /// * `capacity` - 読み遅れた受け手のために保持する最大サンプル数
+    pub fn new(capacity: usize) -> Self {
+        AudioBus {
+            shared: Arc::new(BusShared {
+                state: Mutex::new(BusState {
+                    chunks: VecDeque::new(),
+                    retained: 0,
+                    total_written: 0,
+                    closed: false,
+                    pins: HashMap::new(),
+                    next_pin: 0,
+                }),
+                capacity,
+                changed: Condvar::new(),
+                notify: Notify::new(),
+            }),
+        }
+    }
+
+    /// 音声を配信
+    pub fn publish(&self, chunk: AudioChunk) {
+        if chunk.samples.is_empty() {
+            return;
+        }
+
+        {
+            let mut state = self.shared.state.lock().unwrap();
+            let start = state.total_written;
+            state.total_written += chunk.samples.len() as u64;
+            state.retained += chunk.samples.len();
+            state.chunks.push_back((start, Arc::new(chunk)));
+            state.evict(self.shared.capacity);
+        }
+
+        self.wake();
+    }
+
+    /// 受け手を追加（追加時点以降の音声を受け取る）
+    pub fn subscribe(&self) -> AudioSubscriber {
+        let cursor = self.shared.state.lock().unwrap().total_written;
+        AudioSubscriber {
+            shared: Arc::clone(&self.shared),
+            cursor,
+            pin: None,
+        }
+    }
+
+    /// 読み遅れても音声を失わない受け手を追加（会議全体のディスク保存など向け）
+    ///
+    /// 受け手が読み終えるまでバスが音声を保持し続けるため、読み続けるか破棄すること
+    pub fn subscribe_lossless(&self) -> AudioSubscriber {
+        let mut state = self.shared.state.lock().unwrap();
+        let cursor = state.total_written;
+        let pin = state.next_pin;
+        state.next_pin += 1;
+        state.pins.insert(pin, cursor);
+        AudioSubscriber {
+            shared: Arc::clone(&self.shared),
+            cursor,
+            pin: Some(pin),
+        }
+    }
+
+    /// バスを閉じる（受け手は残りを読み終えると `BusError::Closed` を受け取る）
+    pub fn close(&self) {
+        self.shared.state.lock().unwrap().closed = true;
+        self.wake();
+    }
+
+    /// 配信開始からの累計サンプル数
+    pub fn total_written(&self) -> u64 {
+        self.shared.state.lock().unwrap().total_written
+    }
+
+    fn wake(&self) {
+        self.shared.changed.notify_all();
+        self.shared.notify.notify_waiters();
+    }
+}
+
+/// バスの受け手（独立した読み出し位置を持つ）
+pub struct AudioSubscriber {
+    shared: Arc<BusShared>,
+    cursor: u64,
+    /// `subscribe_lossless` で追加した場合の保持位置のキー
+    pin: Option<u64>,
+}
+
+impl AudioSubscriber {
+    /// 次に読み出すサンプルの絶対位置
+    pub fn position(&self) -> u64 {
+        self.cursor
+    }
+
+    /// 新しい音声があれば取り出す（ブロックしない）
+    ///
+    /// 連続する同じサンプルレートのチャンクは1つにまとめて返す
+    ///
+    /// # Returns
+    /// 新しい音声がなければ `Ok(None)`。読み遅れて音声が失われていれば
+    /// 一度だけ `Err(BusError::Lagged)` を返し、読み出し位置を最古の保持位置へ進める
+    pub fn try_recv(&mut self) -> Result<Option<AudioChunk>, BusError> {
+        let shared = Arc::clone(&self.shared);
+        let mut state = shared.state.lock().unwrap();
+        let received = state.take(&mut self.cursor);
+        self.release(&mut state);
+        received
+    }
+
+    /// 新しい音声が届くまで最大 `timeout` 待って取り出す
+    pub fn recv_blocking(&mut self, timeout: Duration) -> Result<Option<AudioChunk>, BusError> {
+        let shared = Arc::clone(&self.shared);
+        let cursor = self.cursor;
+        let state = shared.state.lock().unwrap();
+        let (mut state, _) = shared
+            .changed
+            .wait_timeout_while(state, timeout, |s| s.total_written == cursor && !s.closed)
+            .unwrap();
+        let received = state.take(&mut self.cursor);
+        self.release(&mut state);
+        received
+    }
+
+    /// 新しい音声が届くまで待って取り出す（非同期）
+    pub async fn recv(&mut self) -> Result<AudioChunk, BusError> {
+        let shared = Arc::clone(&self.shared);
+        loop {
+            let notified = shared.notify.notified();
+            tokio::pin!(notified);
+            notified.as_mut().enable();
+
+            if let Some(chunk) = self.try_recv()? {
+                return Ok(chunk);
+            }
+            notified.await;
+        }
+    }
+
+    /// 読み終えた位置までの音声の保持を解除
+    fn release(&self, state: &mut BusState) {
+        if let Some(pin) = self.pin {
+            state.pins.insert(pin, self.cursor);
+        }
+    }
+}
+
+impl Drop for AudioSubscriber {
+    fn drop(&mut self) {
+        if let Some(pin) = self.pin {
+            if let Ok(mut state) = self.shared.state.lock() {
+                state.pins.remove(&pin);
+                state.evict(self.shared.capacity);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn chunk(rate: u32, samples: &[f32]) -> AudioChunk {
+        AudioChunk { sample_rate: rate, samples: samples.to_vec() }
+    }
+
+    #[test]
+    fn test_subscribers_have_independent_cursors() {
+        let bus = AudioBus::new(100);
+        let mut early = bus.subscribe();
+        bus.publish(chunk(16000, &[1.0, 2.0]));
+        let mut late = bus.subscribe();
+        bus.publish(chunk(16000, &[3.0]));
+
+        assert_eq!(early.try_recv().unwrap().unwrap().samples, vec![1.0, 2.0, 3.0]);
+        assert_eq!(late.try_recv().unwrap().unwrap().samples, vec![3.0]);
+        assert_eq!(early.try_recv(), Ok(None));
+        assert_eq!(early.position(), 3);
+    }
+
+    #[test]
+    fn test_lagging_subscriber_is_notified() {
+        let bus = AudioBus::new(4);
+        let mut subscriber = bus.subscribe();
+        for i in 0..5 {
+            bus.publish(chunk(16000, &[i as f32; 2]));
+        }
+
+        assert_eq!(subscriber.try_recv(), Err(BusError::Lagged(6)));
+        assert_eq!(subscriber.try_recv().unwrap().unwrap().samples, vec![3.0, 3.0, 4.0, 4.0]);
+    }
+
+    #[test]
+    fn test_lossless_subscriber_keeps_unread_audio() {
+        let bus = AudioBus::new(4);
+        let mut writer = bus.subscribe_lossless();
+        for i in 0..5 {
+            bus.publish(chunk(16000, &[i as f32; 2]));
+        }
+
+        // 保持量を超えても、欠落させない受け手は全区間を受け取る
+        let received = writer.try_recv().unwrap().unwrap();
+        assert_eq!(received.samples.len(), 10);
+        assert_eq!(received.samples[0], 0.0);
+
+        // 読み終えた分は保持量に従って削除される
+        bus.publish(chunk(16000, &[5.0; 2]));
+        assert_eq!(bus.shared.state.lock().unwrap().retained, 4);
+
+        // 受け手を破棄すると保持を解除し、通常の受け手は読み遅れる
+        let mut meter = bus.subscribe();
+        for i in 6..9 {
+            bus.publish(chunk(16000, &[i as f32; 2]));
+        }
+        assert_eq!(bus.shared.state.lock().unwrap().retained, 8);
+        drop(writer);
+        assert!(bus.shared.state.lock().unwrap().pins.is_empty());
+        assert_eq!(meter.try_recv(), Err(BusError::Lagged(2)));
+    }
+
+    #[test]
+    fn test_rate_change_splits_chunks() {
+        let bus = AudioBus::new(100);
+        let mut subscriber = bus.subscribe();
+        bus.publish(chunk(48000, &[1.0]));
+        bus.publish(chunk(44100, &[2.0]));
+
+        assert_eq!(subscriber.try_recv().unwrap().unwrap().sample_rate, 48000);
+        assert_eq!(subscriber.try_recv().unwrap().unwrap().sample_rate, 44100);
+    }
+
+    #[test]
+    fn test_close_after_drain() {
+        let bus = AudioBus::new(100);
+        let mut subscriber = bus.subscribe();
+        bus.publish(chunk(16000, &[1.0]));
+        bus.close();
+
+        assert!(subscriber.recv_blocking(Duration::from_millis(10)).unwrap().is_some());
+        assert_eq!(subscriber.recv_blocking(Duration::from_millis(10)), Err(BusError::Closed));
+    }
+
+    #[tokio::test]
+    async fn test_async_recv_wakes_on_publish() {
+        let bus = AudioBus::new(100);
+        let mut subscriber = bus.subscribe();
+
+        let publisher = bus.clone();
+        tokio::spawn(async move {
+            tokio::time::sleep(Duration::from_millis(10)).await;
+            publisher.publish(chunk(16000, &[0.5]));
+        });
+
+        let received = subscriber.recv().await.unwrap();
+        assert_eq!(received.samples, vec![0.5]);
+    }
+}
@@ -1,9 +1,17 @@
 use cpal::traits::{DeviceTrait, HostTrait, StreamTrait};
 use cpal::{Device, Host, Stream, StreamConfig};
-use std::sync::Arc;
+use serde::{Deserialize, Serialize};
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::thread::JoinHandle;
+use std::time::Duration;
 use thiserror::Error;
 
-use super::AudioBuffer;
+use super::{
+    ring_buffer, AudioBuffer, AudioBus, AudioSource, AudioSubscriber, BusError, LevelMeter,
+    LevelReading, MixedBlock, PipelineConfig, ProcessingPipeline, RingConsumer, SourceFormat,
+    SourceSink, StreamMixer, StreamingResampler, WHISPER_SAMPLE_RATE,
+};
 
 #[derive(Debug, Error)]
 pub enum AudioCaptureError {
@@ -15,19 +23,157 @@ pub enum AudioCaptureError {
     
     #[error("No default input device found")]
     NoDefaultDevice,
+
+    #[error("Input device not found: {0}")]
+    DeviceNotFound(String),
     
     #[error("Configuration error: {0}")]
     ConfigError(String),
+
+    #[error("Audio source error: {0}")]
+    SourceError(String),
 }
 
 pub type Result<T> = std::result::Result<T, AudioCaptureError>;
 
+/// コールバックと取り出しスレッドの間のリングバッファ長（秒）
+const RING_BUFFER_SEC: u32 = 2;
+
+/// 取り出しスレッドがリングバッファを確認する間隔
+const WORKER_POLL_INTERVAL: Duration = Duration::from_millis(10);
+
+/// 音声バスが読み遅れた受け手のために保持する長さ（秒）
+const BUS_RETENTION_SEC: usize = 10;
+
+/// 副入力（システム音声）の到着を待つ最大時間。超えた分は無音としてミックスする
+const MIX_MAX_LATENCY: Duration = Duration::from_millis(200);
+
+/// 一般的なサンプルレート（対応レート一覧の列挙に使用）
+const COMMON_SAMPLE_RATES: [u32; 8] = [8000, 16000, 22050, 32000, 44100, 48000, 88200, 96000];
+
+/// キャプチャしたモノラル音声の断片（音声バスで配信する単位）
+#[derive(Debug, Clone, PartialEq)]
+pub struct AudioChunk {
+    /// サンプルレート（Hz）
+    pub sample_rate: u32,
+    pub samples: Vec<f32>,
+}
+
+/// 複数チャンネル入力の扱い
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ChannelMode {
+    /// 全チャンネルを平均してモノラルにする
+    #[default]
+    Mixdown,
+    /// モノラルのミックスに加え、各チャンネルを個別のトラックとして配信する
+    /// （マルチ入力インターフェースにつないだ複数のピンマイクなど）
+    Separate,
+}
+
+/// 2系統入力（`AudioCapture::set_secondary_source`）のときの入力ごとのトラック
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum InputTrack {
+    /// 主入力（マイク）
+    Microphone,
+    /// 副入力（PulseAudio/PipeWireのモニターなど、相手側の音声）
+    System,
+}
+
+impl InputTrack {
+    /// 保存ファイル名などに使う短い名前
+    pub fn label(&self) -> &'static str {
+        match self {
+            InputTrack::Microphone => "mic",
+            InputTrack::System => "system",
+        }
+    }
+
+    fn index(&self) -> usize {
+        match self {
+            InputTrack::Microphone => 0,
+            InputTrack::System => 1,
+        }
+    }
+}
+
+/// チャンネル（または入力）ごとのトラック
+#[derive(Clone)]
+struct ChannelTrack {
+    /// デバイスのサンプルレートのまま配信する音声バス
+    bus: AudioBus,
+    /// 16kHzに変換・前処理済みの音声
+    resampled_buffer: Arc<AudioBuffer>,
+    /// 16kHzに変換・前処理済みの音声を配信するバス
+    resampled_bus: AudioBus,
+}
+
+impl ChannelTrack {
+    fn new() -> Self {
+        ChannelTrack {
+            bus: AudioBus::new(48000 * BUS_RETENTION_SEC),
+            resampled_buffer: Arc::new(AudioBuffer::new(WHISPER_SAMPLE_RATE as usize * 60)),
+            resampled_bus: AudioBus::new(WHISPER_SAMPLE_RATE as usize * BUS_RETENTION_SEC),
+        }
+    }
+
+    fn close(&self) {
+        self.bus.close();
+        self.resampled_bus.close();
+    }
+}
+
+/// 入力デバイスの詳細情報
+#[derive(Debug, Clone, Serialize)]
+pub struct InputDeviceInfo {
+    /// デバイス名
+    pub name: String,
+    /// システムの既定入力デバイスかどうか
+    pub is_default: bool,
+    /// 既定のサンプルレート（Hz）
+    pub default_sample_rate: Option<u32>,
+    /// 既定のチャンネル数
+    pub default_channels: Option<u16>,
+    /// 対応しているサンプルレート（一般的なレートのうち対応範囲に含まれるもの）
+    pub sample_rates: Vec<u32>,
+    /// 対応しているチャンネル数
+    pub channels: Vec<u16>,
+    /// 対応しているサンプルフォーマット
+    pub sample_formats: Vec<String>,
+}
+
 /// 音声キャプチャシステム
 pub struct AudioCapture {
     host: Host,
-    device: Option<Device>,
-    stream: Option<Stream>,
+    /// 音声入力ソース（未初期化ならNone）
+    source: Option<Box<dyn AudioSource>>,
     buffer: Arc<AudioBuffer>,
+    /// 16kHzに変換済みの音声（ASR/VAD向けに一度だけ変換して共有）
+    resampled_buffer: Arc<AudioBuffer>,
+    /// リングバッファからサンプルを配る非リアルタイムスレッド
+    worker: Option<CaptureWorker>,
+    /// リングバッファの空き不足で破棄したサンプル数（累計）
+    overruns: Arc<AtomicU64>,
+    /// 録音開始からの16kHz換算サンプル数（無音補填分を含む会議タイムライン）
+    timeline_samples: Arc<AtomicU64>,
+    /// 前回取り出してからの入力レベル集計（前処理前の音声をバスから受け取る）
+    level: Mutex<LevelTap>,
+    /// デバイスのサンプルレートのまま配信する音声バス
+    bus: AudioBus,
+    /// 16kHzに変換して配信する音声バス
+    resampled_bus: AudioBus,
+    /// 16kHz側（ASR/VAD向け）に適用する前処理の設定
+    processing: PipelineConfig,
+    channel_mode: ChannelMode,
+    /// `ChannelMode::Separate` のときのチャンネルごとのトラック
+    channel_tracks: Vec<ChannelTrack>,
+    /// 主入力とミックスする副入力（システム音声など）
+    secondary: Option<Box<dyn AudioSource>>,
+    /// 副入力があるときの入力ごとのトラック（`InputTrack` の順）
+    input_tracks: Vec<ChannelTrack>,
+    /// ミックス時に主入力・副入力に掛けるゲイン（線形）
+    source_gains: (f32, f32),
     sample_rate: u32,
 }
 
@@ -35,116 +181,475 @@ impl AudioCapture {
     /// 新しいAudioCaptureインスタンスを作成
     pub fn new() -> Result<Self> {
         let host = cpal::default_host();
-        
+        let bus = AudioBus::new(48000 * BUS_RETENTION_SEC);
+
         Ok(AudioCapture {
             host,
-            device: None,
-            stream: None,
+            source: None,
             buffer: Arc::new(AudioBuffer::new(48000 * 60)), // 60秒分のバッファ
+            resampled_buffer: Arc::new(AudioBuffer::new(WHISPER_SAMPLE_RATE as usize * 60)),
+            worker: None,
+            overruns: Arc::new(AtomicU64::new(0)),
+            timeline_samples: Arc::new(AtomicU64::new(0)),
+            level: Mutex::new(LevelTap {
+                subscriber: bus.subscribe(),
+                meter: LevelMeter::new(48000),
+            }),
+            bus,
+            resampled_bus: AudioBus::new(WHISPER_SAMPLE_RATE as usize * BUS_RETENTION_SEC),
+            processing: PipelineConfig::default(),
+            channel_mode: ChannelMode::Mixdown,
+            channel_tracks: Vec::new(),
+            secondary: None,
+            input_tracks: Vec::new(),
+            source_gains: (1.0, 1.0),
             sample_rate: 48000,
         })
     }
 
+    /// 指定したソースから取り込むAudioCaptureを作成
+    pub fn from_source(source: Box<dyn AudioSource>) -> Result<Self> {
+        let mut capture = Self::new()?;
+        capture.source = Some(source);
+        Ok(capture)
+    }
+
+    /// 音声入力ソースを差し替える（録音中なら停止する）
+    pub fn set_source(&mut self, source: Box<dyn AudioSource>) {
+        let _ = self.stop_recording();
+        self.source = Some(source);
+    }
+
     /// デフォルトの入力デバイスを初期化
     pub fn initialize(&mut self) -> Result<()> {
-        let device = self.host
-            .default_input_device()
-            .ok_or(AudioCaptureError::NoDefaultDevice)?;
-        
-        self.device = Some(device);
+        let source = CpalSource::open(None)?;
+        self.set_source(Box::new(source));
+        Ok(())
+    }
+
+    /// 名前を指定して入力デバイスを初期化
+    ///
+    /// 指定したデバイスが見つからない場合は警告を出して既定のデバイスを使用する
+    pub fn initialize_with_device(&mut self, name: &str) -> Result<()> {
+        let source = CpalSource::open(Some(name))?;
+        self.set_source(Box::new(source));
+        Ok(())
+    }
+
+    /// 初期化済みソース（デバイス）の名前を取得
+    pub fn device_name(&self) -> Option<String> {
+        self.source.as_ref().and_then(|s| s.name())
+    }
+
+    /// 主入力とミックスする副入力（システム音声など）を設定する（録音中なら停止する）
+    ///
+    /// 副入力は主入力のサンプルレートに変換・時刻合わせしてミックスし、その音声を
+    /// 保存・ASRに使う。ミックス前の各入力は `subscribe_input` で個別に受け取れる
+    pub fn set_secondary_source(&mut self, source: Option<Box<dyn AudioSource>>) {
+        let _ = self.stop_recording();
+        for track in self.input_tracks.drain(..) {
+            track.close();
+        }
+        if source.is_some() {
+            self.input_tracks = vec![ChannelTrack::new(), ChannelTrack::new()];
+        }
+        self.secondary = source;
+    }
+
+    /// 副入力が設定されているか
+    pub fn has_secondary_source(&self) -> bool {
+        self.secondary.is_some()
+    }
+
+    /// 副入力（デバイス）の名前を取得
+    pub fn secondary_name(&self) -> Option<String> {
+        self.secondary.as_ref().and_then(|s| s.name())
+    }
+
+    /// ミックス時のゲイン（dB）を設定（次回の録音開始・再接続から反映）
+    pub fn set_source_gains(&mut self, primary_db: f32, secondary_db: f32) {
+        self.source_gains = (db_to_gain(primary_db), db_to_gain(secondary_db));
+    }
+
+    fn source_format(&self) -> Result<SourceFormat> {
+        self.source
+            .as_ref()
+            .ok_or_else(|| AudioCaptureError::DeviceError("Device not initialized".to_string()))?
+            .format()
+    }
+
+    /// ASR/VAD向け音声の前処理設定を変更（次回の録音開始・再接続から反映）
+    ///
+    /// ディスクへ保存するデバイスレートの音声には適用しない
+    pub fn set_processing(&mut self, config: PipelineConfig) {
+        self.processing = config;
+    }
+
+    /// 複数チャンネル入力の扱いを変更（初期化済みデバイスのチャンネル数でトラックを用意）
+    ///
+    /// 録音開始前に呼び出すことで、開始直後から各トラックを購読できる。
+    /// 録音を始めた後は、再接続でチャンネル数が変わってもトラックの構成を維持する
+    pub fn set_channel_mode(&mut self, mode: ChannelMode) -> Result<()> {
+        self.channel_mode = mode;
+        match mode {
+            ChannelMode::Separate => {
+                let channels = self.input_channels()?;
+                self.prepare_channel_tracks(channels);
+            }
+            ChannelMode::Mixdown => self.prepare_channel_tracks(0),
+        }
         Ok(())
     }
 
+    /// 初期化済みデバイスの入力チャンネル数
+    pub fn input_channels(&self) -> Result<usize> {
+        Ok(self.source_format()?.channels)
+    }
+
+    /// 個別トラックの数（`ChannelMode::Mixdown` またはモノラル入力では0）
+    pub fn channel_count(&self) -> usize {
+        self.channel_tracks.len()
+    }
+
+    /// チャンネル数に合わせてトラックを用意（数が変わった場合は既存のトラックを閉じる）
+    fn prepare_channel_tracks(&mut self, channels: usize) {
+        // モノラル入力ではミックスがそのまま唯一のトラックになる
+        let channels = if channels > 1 { channels } else { 0 };
+        if self.channel_tracks.len() == channels {
+            return;
+        }
+        for track in self.channel_tracks.drain(..) {
+            track.close();
+        }
+        self.channel_tracks = (0..channels).map(|_| ChannelTrack::new()).collect();
+    }
+
     /// 録音を開始
     pub fn start_recording(&mut self) -> Result<()> {
-        let device = self.device.as_ref()
-            .ok_or_else(|| AudioCaptureError::DeviceError("Device not initialized".to_string()))?;
+        let format = self.source_format()?;
+        if format.sample_rate != self.sample_rate {
+            // デバイスのサンプルレートのバッファに異なるレートの音声が混ざらないよう、
+            // 再接続などでレートが変わったらそれまでの分を破棄して貯め直す
+            if self.timeline_samples.load(Ordering::Relaxed) > 0 {
+                eprintln!(
+                    "[Audio] サンプルレートが {} Hz から {} Hz に変わりました",
+                    self.sample_rate, format.sample_rate
+                );
+            }
+            self.buffer.clear_blocking();
+        }
+        self.sample_rate = format.sample_rate;
+        let channels = format.channels;
 
-        let config = device.default_input_config()
-            .map_err(|e| AudioCaptureError::ConfigError(e.to_string()))?;
+        // トラックの構成は録音中に変えない（受け手や保存ファイルがトラックごとにあるため）。
+        // 再接続でチャンネル数が変わった場合は、足りないトラックを無音にし、
+        // 余ったチャンネルはミックスにのみ含める
+        let started = self.timeline_samples.load(Ordering::Relaxed) > 0;
+        if self.channel_mode == ChannelMode::Separate && !started {
+            self.prepare_channel_tracks(channels);
+        } else if !self.channel_tracks.is_empty() && self.channel_tracks.len() != channels {
+            eprintln!(
+                "[Audio] 入力チャンネル数が {} から {} に変わりました（トラック数は {} のまま録音します）",
+                self.channel_tracks.len(),
+                channels,
+                self.channel_tracks.len()
+            );
+        }
+        // 個別トラックがある場合はインターリーブのまま受け渡し、取り出しスレッドで分離する
+        let frame_len = if self.channel_tracks.is_empty() { 1 } else { channels };
 
-        self.sample_rate = config.sample_rate().0;
-        let channels = config.channels() as usize;
+        // ソースは事前確保したリングバッファに書き込むだけにし、
+        // バッファ追加・リサンプリング・受け手への送信は取り出しスレッドで行う
+        let (producer, consumer) =
+            ring_buffer((self.sample_rate * RING_BUFFER_SEC) as usize * frame_len);
+        let sink = SourceSink::new(producer, channels, frame_len > 1);
 
-        let buffer = Arc::clone(&self.buffer);
+        let secondary = self.start_secondary();
+        self.worker = Some(self.spawn_worker(consumer, frame_len, secondary));
 
-        let stream = match config.sample_format() {
-            cpal::SampleFormat::F32 => {
-                self.build_input_stream::<f32>(device, &config.into(), buffer, channels)?
-            }
-            cpal::SampleFormat::I16 => {
-                self.build_input_stream::<i16>(device, &config.into(), buffer, channels)?
+        let started = match self.source.as_mut() {
+            Some(source) => source.start(sink),
+            None => Err(AudioCaptureError::DeviceError("Device not initialized".to_string())),
+        };
+        if let Err(e) = started {
+            if let Some(secondary) = self.secondary.as_mut() {
+                let _ = secondary.stop();
             }
-            cpal::SampleFormat::U16 => {
-                self.build_input_stream::<u16>(device, &config.into(), buffer, channels)?
+            self.stop_worker();
+            return Err(e);
+        }
+        Ok(())
+    }
+
+    /// 副入力を開始し、取り出しスレッドでのミックス状態を用意する
+    ///
+    /// 副入力が開始できなくても主入力の録音は続け、システム音声のトラックは無音にする
+    fn start_secondary(&mut self) -> Option<SecondaryInput> {
+        let source = self.secondary.as_mut()?;
+
+        let consumer = match source.format() {
+            Ok(format) => {
+                let (producer, consumer) =
+                    ring_buffer((format.sample_rate * RING_BUFFER_SEC) as usize);
+                let sink = SourceSink::new(producer, format.channels, false);
+                source.start(sink).map(|_| (consumer, format.sample_rate))
             }
-            _ => {
-                return Err(AudioCaptureError::ConfigError(
-                    "Unsupported sample format".to_string()
-                ));
+            Err(e) => Err(e),
+        };
+        let consumer = match consumer {
+            Ok(consumer) => Some(consumer),
+            Err(e) => {
+                eprintln!("[Audio] システム音声の取り込みを開始できません（マイクのみ録音します）: {}", e);
+                None
             }
         };
 
-        stream.play()
-            .map_err(|e| AudioCaptureError::StreamError(e.to_string()))?;
+        let max_latency =
+            (self.sample_rate as u64 * MIX_MAX_LATENCY.as_millis() as u64 / 1000) as usize;
+        let mut mixer = StreamMixer::new(max_latency);
+        mixer.set_gains(self.source_gains.0, self.source_gains.1);
 
-        self.stream = Some(stream);
-        Ok(())
+        Some(SecondaryInput {
+            resampler: consumer
+                .as_ref()
+                .map(|(_, rate)| StreamingResampler::new(*rate, self.sample_rate)),
+            consumer: consumer.map(|(consumer, _)| consumer),
+            scratch: Vec::new(),
+            reported_overruns: 0,
+            mixer,
+            tracks: self.input_tracks
+                .iter()
+                .map(|track| ChannelTarget {
+                    track: track.clone(),
+                    resampler: StreamingResampler::new(self.sample_rate, WHISPER_SAMPLE_RATE),
+                    pipeline: ProcessingPipeline::from_config(&self.processing, WHISPER_SAMPLE_RATE),
+                })
+                .collect(),
+        })
+    }
+
+    /// リングバッファの取り出しスレッドを起動
+    fn spawn_worker(
+        &self,
+        consumer: RingConsumer,
+        frame_len: usize,
+        secondary: Option<SecondaryInput>,
+    ) -> CaptureWorker {
+        let running = Arc::new(AtomicBool::new(true));
+        let targets = CaptureTargets {
+            buffer: Arc::clone(&self.buffer),
+            resampled_buffer: Arc::clone(&self.resampled_buffer),
+            timeline_samples: Arc::clone(&self.timeline_samples),
+            overruns: Arc::clone(&self.overruns),
+            bus: self.bus.clone(),
+            resampled_bus: self.resampled_bus.clone(),
+            pipeline: ProcessingPipeline::from_config(&self.processing, WHISPER_SAMPLE_RATE),
+            channels: self.channel_tracks
+                .iter()
+                .map(|track| ChannelTarget {
+                    track: track.clone(),
+                    resampler: StreamingResampler::new(self.sample_rate, WHISPER_SAMPLE_RATE),
+                    pipeline: ProcessingPipeline::from_config(&self.processing, WHISPER_SAMPLE_RATE),
+                })
+                .collect(),
+            secondary,
+            frame_len,
+            sample_rate: self.sample_rate,
+        };
+
+        let handle = {
+            let running = Arc::clone(&running);
+            std::thread::spawn(move || targets.run(consumer, running))
+        };
+
+        CaptureWorker { running, handle }
+    }
+
+    /// 取り出しスレッドを停止（残りを配り終えるまで待つ）
+    fn stop_worker(&mut self) {
+        if let Some(worker) = self.worker.take() {
+            worker.running.store(false, Ordering::Release);
+            if worker.handle.join().is_err() {
+                eprintln!("[Audio] capture worker panicked");
+            }
+        }
     }
 
     /// 録音を停止
     pub fn stop_recording(&mut self) -> Result<()> {
-        if let Some(stream) = self.stream.take() {
-            stream.pause()
-                .map_err(|e| AudioCaptureError::StreamError(e.to_string()))?;
+        let result = match self.source.as_mut() {
+            Some(source) => source.stop(),
+            None => Ok(()),
+        };
+        if let Some(secondary) = self.secondary.as_mut() {
+            if let Err(e) = secondary.stop() {
+                eprintln!("[Audio] システム音声の停止に失敗しました: {}", e);
+            }
         }
-        Ok(())
+
+        // ソース停止後に残りを配り、リサンプラーの末尾を確定させる
+        self.stop_worker();
+        result
     }
 
-    /// 入力ストリームを構築
-    fn build_input_stream<T>(
-        &self,
-        device: &Device,
-        config: &StreamConfig,
-        buffer: Arc<AudioBuffer>,
-        channels: usize,
-    ) -> Result<Stream>
-    where
-        T: cpal::Sample + cpal::SizedSample + ToFloat,
-        f32: From<T>,
-    {
-        let err_fn = |err| eprintln!("Stream error: {}", err);
+    /// デバイスのサンプルレートの音声を受け取る受け手を追加
+    ///
+    /// 読み遅れても追加時点以降の全サンプルを受け取れるため、会議全体のディスク保存などに使用する。
+    /// 受け手が読み終えるまで音声を保持し続けるので、読み続けるか破棄すること
+    pub fn subscribe(&self) -> AudioSubscriber {
+        self.bus.subscribe_lossless()
+    }
 
-        let stream = device.build_input_stream(
-            config,
-            move |data: &[T], _: &cpal::InputCallbackInfo| {
-                // サンプルをf32に変換してモノラルに変換
-                let samples: Vec<f32> = data
-                    .chunks(channels)
-                    .map(|chunk| {
-                        let sum = chunk.iter()
-                            .map(|&s| s.to_float())
-                            .sum::<f32>();
-                        sum / channels as f32
-                    })
-                    .collect();
-                // Tokio ランタイムに依存しない同期追加
-                let buffer_clone = Arc::clone(&buffer);
-                buffer_clone.push_blocking(&samples);
-            },
-            err_fn,
-            None,
-        )
-        .map_err(|e| AudioCaptureError::StreamError(e.to_string()))?;
+    /// 16kHzに変換済みの音声を受け取る受け手を追加（ASR/VAD/レベル計測など向け）
+    ///
+    /// 保持期間を超えて読み遅れた分は `BusError::Lagged` で通知して読み飛ばす
+    pub fn subscribe_resampled(&self) -> AudioSubscriber {
+        self.resampled_bus.subscribe()
+    }
 
-        Ok(stream)
+    /// 指定チャンネルのトラックをデバイスのサンプルレートで受け取る受け手を追加（`subscribe` と同様に欠落しない）
+    pub fn subscribe_channel(&self, channel: usize) -> Option<AudioSubscriber> {
+        self.channel_tracks.get(channel).map(|track| track.bus.subscribe_lossless())
+    }
+
+    /// 指定チャンネルの16kHz変換済み音声を受け取る受け手を追加（チャンネルごとの文字起こし向け）
+    pub fn subscribe_channel_resampled(&self, channel: usize) -> Option<AudioSubscriber> {
+        self.channel_tracks.get(channel).map(|track| track.resampled_bus.subscribe())
+    }
+
+    /// 指定チャンネルの16kHz変換済みバッファ（チャンネルごとの文字起こし向け）
+    pub fn get_channel_buffer(&self, channel: usize) -> Option<Arc<AudioBuffer>> {
+        self.channel_tracks.get(channel).map(|track| Arc::clone(&track.resampled_buffer))
+    }
+
+    /// 副入力があるとき、ミックス前の指定入力をデバイスのサンプルレートで受け取る受け手を追加
+    ///
+    /// 2つの入力は時刻を揃えてあり、ゲインは適用しない
+    pub fn subscribe_input(&self, track: InputTrack) -> Option<AudioSubscriber> {
+        self.input_tracks.get(track.index()).map(|track| track.bus.subscribe_lossless())
+    }
+
+    /// 副入力があるとき、ミックス前の指定入力の16kHz変換済み音声を受け取る受け手を追加
+    pub fn subscribe_input_resampled(&self, track: InputTrack) -> Option<AudioSubscriber> {
+        self.input_tracks
+            .get(track.index())
+            .map(|track| track.resampled_bus.subscribe())
     }
 
-    /// バッファを取得
+    /// 副入力があるとき、ミックス前の指定入力の16kHz変換済みバッファ
+    pub fn get_input_buffer(&self, track: InputTrack) -> Option<Arc<AudioBuffer>> {
+        self.input_tracks
+            .get(track.index())
+            .map(|track| Arc::clone(&track.resampled_buffer))
+    }
+
+    /// 録音を終了して音声バスを閉じる
+    ///
+    /// 受け手は残りの音声を読み終えると `BusError::Closed` を受け取る
+    pub fn finish(&mut self) -> Result<()> {
+        let result = self.stop_recording();
+        self.bus.close();
+        self.resampled_bus.close();
+        for track in self.channel_tracks.iter().chain(&self.input_tracks) {
+            track.close();
+        }
+        result
+    }
+
+    /// 切断されたデバイス（ソース）を開き直して録音を再開
+    pub fn reopen(&mut self) -> Result<()> {
+        let _ = self.stop_recording();
+        self.take_device_error();
+
+        if let Some(source) = self.source.as_mut() {
+            source.reopen()?;
+        }
+        if let Some(secondary) = self.secondary.as_mut() {
+            if let Err(e) = secondary.reopen() {
+                eprintln!("[Audio] システム音声を開き直せません: {}", e);
+            }
+        }
+        self.start_recording()
+    }
+
+    /// ソースが報告したデバイス喪失などのエラーを取り出す
+    pub fn take_device_error(&mut self) -> Option<String> {
+        let secondary = self
+            .secondary
+            .as_mut()
+            .and_then(|s| s.take_error())
+            .map(|e| format!("System audio: {}", e));
+        self.source.as_mut().and_then(|s| s.take_error()).or(secondary)
+    }
+
+    /// 有限のソース（ファイルなど）を最後まで読み終えたか
+    pub fn is_source_finished(&self) -> bool {
+        self.source.as_ref().is_some_and(|s| s.is_finished())
+    }
+
+    /// 前回呼び出してからの入力レベルを取得（新しい音声がなければNone）
+    ///
+    /// 一定間隔で呼び出すことで、その間隔を1ブロックとしたレベルが得られる
+    pub fn take_level(&self) -> Option<LevelReading> {
+        self.level.lock().unwrap().take()
+    }
+
+    /// リングバッファの空き不足で破棄したサンプル数（累計）
+    ///
+    /// 0 以外になった場合は取り出しスレッドが追いつかず音声が欠落している
+    pub fn overrun_count(&self) -> u64 {
+        self.overruns.load(Ordering::Relaxed)
+    }
+
+    /// 会議タイムライン上の現在位置（秒）
+    ///
+    /// 取得したサンプル数と補填した無音から算出するため、デバイスやサンプルレートが
+    /// 途中で変わっても連続した時間になる
+    pub fn timeline_position_sec(&self) -> f64 {
+        self.timeline_samples.load(Ordering::Relaxed) as f64 / WHISPER_SAMPLE_RATE as f64
+    }
+
+    /// 取得できなかった区間を無音で補填し、以降のタイムスタンプを実時間に揃える
+    ///
+    /// バッファには保持期間を超えない範囲でのみ書き込み、タイムラインは全区間進める。
+    /// 録音停止中（取り出しスレッドが動いていない間）に呼び出すこと
+    pub fn insert_silence(&self, seconds: f64) {
+        if seconds <= 0.0 {
+            return;
+        }
+
+        self.buffer
+            .push_silence_blocking((seconds * self.sample_rate as f64) as u64);
+
+        let resampled = (seconds * WHISPER_SAMPLE_RATE as f64) as u64;
+        self.resampled_buffer.push_silence_blocking(resampled);
+        self.timeline_samples.fetch_add(resampled, Ordering::Relaxed);
+
+        // バスの受け手には全区間を1秒ずつ配信する
+        publish_silence(&self.bus, seconds, self.sample_rate);
+        publish_silence(&self.resampled_bus, seconds, WHISPER_SAMPLE_RATE);
+        // 補填した無音は入力レベルに含めない
+        self.level.lock().unwrap().skip();
+
+        for track in self.channel_tracks.iter().chain(&self.input_tracks) {
+            track.resampled_buffer.push_silence_blocking(resampled);
+            publish_silence(&track.bus, seconds, self.sample_rate);
+            publish_silence(&track.resampled_bus, seconds, WHISPER_SAMPLE_RATE);
+        }
+    }
+
+    /// デバイスのサンプルレート（`sample_rate`）の音声のバッファを取得
+    ///
+    /// 再接続でサンプルレートが変わった場合は、それ以前の音声を破棄して新しいレートで貯め直す
     pub fn get_buffer(&self) -> Arc<AudioBuffer> {
         Arc::clone(&self.buffer)
     }
 
+    /// 16kHzにリサンプリング済みのバッファを取得
+    pub fn get_resampled_buffer(&self) -> Arc<AudioBuffer> {
+        Arc::clone(&self.resampled_buffer)
+    }
+
     /// サンプルレートを取得
     pub fn sample_rate(&self) -> u32 {
         self.sample_rate
@@ -162,6 +667,534 @@ impl AudioCapture {
             })
             .unwrap_or_default()
     }
+
+    /// 利用可能な入力デバイスの詳細情報を取得
+    pub fn list_input_device_info(&self) -> Vec<InputDeviceInfo> {
+        let default_name = self
+            .host
+            .default_input_device()
+            .and_then(|d| d.name().ok());
+
+        self.host
+            .input_devices()
+            .ok()
+            .map(|devices| {
+                devices
+                    .filter_map(|d| {
+                        let name = d.name().ok()?;
+                        let is_default = default_name.as_deref() == Some(name.as_str());
+                        Some(describe_input_device(&d, name, is_default))
+                    })
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
+}
+
+/// cpalの入力デバイスから取り込むソース
+pub struct CpalSource {
+    host: Host,
+    /// 要求されたデバイス名（Noneはシステム既定のデバイス）
+    requested: Option<String>,
+    device: Device,
+    stream: Option<Stream>,
+    /// 見つからないときに既定のデバイスを使うか
+    fallback: bool,
+    /// 要求したデバイスが見つからず既定のデバイスを使っているか（警告は切り替わったときだけ出す）
+    using_fallback: bool,
+    /// ストリームが報告したデバイス喪失エラー
+    error: Arc<Mutex<Option<String>>>,
+}
+
+impl CpalSource {
+    /// 入力デバイスを開く
+    ///
+    /// 指定したデバイスが見つからない場合は警告を出して既定のデバイスを使用する
+    pub fn open(name: Option<&str>) -> Result<Self> {
+        Self::open_with(name, true)
+    }
+
+    /// 名前が一致する入力デバイスだけを開く（見つからなければ `DeviceNotFound`）
+    ///
+    /// モニターソースなど、既定のデバイス（マイク）で代用すると困る入力に使う
+    pub fn open_exact(name: &str) -> Result<Self> {
+        Self::open_with(Some(name), false)
+    }
+
+    fn open_with(name: Option<&str>, fallback: bool) -> Result<Self> {
+        let host = cpal::default_host();
+        let (device, using_fallback) = resolve_input_device(&host, name, fallback)?;
+        if using_fallback {
+            warn_fallback(name);
+        }
+
+        Ok(CpalSource {
+            host,
+            requested: name.map(str::to_string),
+            device,
+            stream: None,
+            fallback,
+            using_fallback,
+            error: Arc::new(Mutex::new(None)),
+        })
+    }
+
+    /// 入力ストリームを構築
+    fn build_input_stream<T>(&self, config: &StreamConfig, mut sink: SourceSink) -> Result<Stream>
+    where
+        T: cpal::Sample + cpal::SizedSample + ToFloat,
+    {
+        let device_error = Arc::clone(&self.error);
+        let err_fn = move |err: cpal::StreamError| {
+            eprintln!("Stream error: {}", err);
+            if let cpal::StreamError::DeviceNotAvailable = err {
+                *device_error.lock().unwrap() = Some(err.to_string());
+            }
+        };
+
+        let stream = self.device.build_input_stream(
+            config,
+            move |data: &[T], _: &cpal::InputCallbackInfo| {
+                // リアルタイムスレッドのためメモリ確保やロックは行わず、
+                // 変換しながらリングバッファへ直接書き込む
+                sink.write(data.iter().map(|s| s.to_float()));
+            },
+            err_fn,
+            None,
+        )
+        .map_err(|e| AudioCaptureError::StreamError(e.to_string()))?;
+
+        Ok(stream)
+    }
+}
+
+impl AudioSource for CpalSource {
+    fn name(&self) -> Option<String> {
+        self.device.name().ok()
+    }
+
+    fn format(&self) -> Result<SourceFormat> {
+        let config = self.device.default_input_config()
+            .map_err(|e| AudioCaptureError::ConfigError(e.to_string()))?;
+        Ok(SourceFormat {
+            sample_rate: config.sample_rate().0,
+            channels: config.channels() as usize,
+        })
+    }
+
+    fn start(&mut self, sink: SourceSink) -> Result<()> {
+        let config = self.device.default_input_config()
+            .map_err(|e| AudioCaptureError::ConfigError(e.to_string()))?;
+        let sample_format = config.sample_format();
+        let config: StreamConfig = config.into();
+
+        let stream = match sample_format {
+            cpal::SampleFormat::I8 => self.build_input_stream::<i8>(&config, sink)?,
+            cpal::SampleFormat::I16 => self.build_input_stream::<i16>(&config, sink)?,
+            cpal::SampleFormat::I32 => self.build_input_stream::<i32>(&config, sink)?,
+            cpal::SampleFormat::I64 => self.build_input_stream::<i64>(&config, sink)?,
+            cpal::SampleFormat::U8 => self.build_input_stream::<u8>(&config, sink)?,
+            cpal::SampleFormat::U16 => self.build_input_stream::<u16>(&config, sink)?,
+            cpal::SampleFormat::U32 => self.build_input_stream::<u32>(&config, sink)?,
+            cpal::SampleFormat::U64 => self.build_input_stream::<u64>(&config, sink)?,
+            cpal::SampleFormat::F32 => self.build_input_stream::<f32>(&config, sink)?,
+            cpal::SampleFormat::F64 => self.build_input_stream::<f64>(&config, sink)?,
+            format => {
+                return Err(AudioCaptureError::ConfigError(
+                    format!("Unsupported sample format: {:?}", format)
+                ));
+            }
+        };
+
+        stream.play()
+            .map_err(|e| AudioCaptureError::StreamError(e.to_string()))?;
+        self.stream = Some(stream);
+        Ok(())
+    }
+
+    fn stop(&mut self) -> Result<()> {
+        // デバイス喪失後は pause が失敗し得るが、ストリームは必ず破棄する
+        match self.stream.take() {
+            Some(stream) => stream.pause()
+                .map_err(|e| AudioCaptureError::StreamError(e.to_string())),
+            None => Ok(()),
+        }
+    }
+
+    fn take_error(&mut self) -> Option<String> {
+        self.error.lock().unwrap().take()
+    }
+
+    fn reopen(&mut self) -> Result<()> {
+        let _ = self.stop();
+        // 切断中は1秒ごとに呼ばれるため、既定のデバイスへ切り替わったときだけ警告する
+        let (device, using_fallback) =
+            resolve_input_device(&self.host, self.requested.as_deref(), self.fallback)?;
+        if using_fallback && !self.using_fallback {
+            warn_fallback(self.requested.as_deref());
+        }
+        self.device = device;
+        self.using_fallback = using_fallback;
+        self.take_error();
+        Ok(())
+    }
+}
+
+/// 名前が一致する入力デバイスを検索（`fallback` なら見つからないとき既定のデバイス）
+///
+/// 指定したデバイスの代わりに既定のデバイスを返した場合は2番目の値がtrue
+fn resolve_input_device(host: &Host, name: Option<&str>, fallback: bool) -> Result<(Device, bool)> {
+    let Some(name) = name else {
+        let device = host.default_input_device().ok_or(AudioCaptureError::NoDefaultDevice)?;
+        return Ok((device, false));
+    };
+
+    let found = host
+        .input_devices()
+        .map_err(|e| AudioCaptureError::DeviceError(e.to_string()))?
+        .find(|d| d.name().map(|n| n == name).unwrap_or(false));
+    match found {
+        Some(device) => Ok((device, false)),
+        None if !fallback => Err(AudioCaptureError::DeviceNotFound(name.to_string())),
+        None => {
+            let device = host.default_input_device().ok_or(AudioCaptureError::NoDefaultDevice)?;
+            Ok((device, true))
+        }
+    }
+}
+
+/// 指定したデバイスの代わりに既定のデバイスを使うことを警告
+fn warn_fallback(name: Option<&str>) {
+    eprintln!(
+        "[Audio] {}。既定の入力デバイスを使用します",
+        AudioCaptureError::DeviceNotFound(name.unwrap_or_default().to_string())
+    );
+}
+
+/// デバイスの対応設定を InputDeviceInfo にまとめる
+fn describe_input_device(device: &Device, name: String, is_default: bool) -> InputDeviceInfo {
+    let default_config = device.default_input_config().ok();
+    let ranges: Vec<_> = device
+        .supported_input_configs()
+        .map(|configs| configs.collect())
+        .unwrap_or_default();
+
+    let mut sample_rates: Vec<u32> = COMMON_SAMPLE_RATES
+        .iter()
+        .copied()
+        .filter(|&rate| {
+            ranges.iter().any(|r| r.min_sample_rate().0 <= rate && rate <= r.max_sample_rate().0)
+        })
+        .collect();
+    if let Some(rate) = default_config.as_ref().map(|c| c.sample_rate().0) {
+        if !sample_rates.contains(&rate) {
+            sample_rates.push(rate);
+            sample_rates.sort_unstable();
+        }
+    }
+
+    let mut channels: Vec<u16> = ranges.iter().map(|r| r.channels()).collect();
+    channels.sort_unstable();
+    channels.dedup();
+
+    let mut sample_formats: Vec<String> = ranges
+        .iter()
+        .map(|r| r.sample_format().to_string())
+        .collect();
+    sample_formats.sort();
+    sample_formats.dedup();
+
+    InputDeviceInfo {
+        name,
+        is_default,
+        default_sample_rate: default_config.as_ref().map(|c| c.sample_rate().0),
+        default_channels: default_config.as_ref().map(|c| c.channels()),
+        sample_rates,
+        channels,
+        sample_formats,
+    }
+}
+
+impl Drop for AudioCapture {
+    fn drop(&mut self) {
+        let _ = self.finish();
+    }
+}
+
+/// dBを線形のゲインに変換
+fn db_to_gain(db: f32) -> f32 {
+    10f32.powf(db / 20.0)
+}
+
+/// 指定秒数の無音を1秒単位のチャンクでバスに配信
+fn publish_silence(bus: &AudioBus, seconds: f64, sample_rate: u32) {
+    let total = (seconds * sample_rate as f64) as usize;
+    for start in (0..total).step_by(sample_rate as usize) {
+        let len = (total - start).min(sample_rate as usize);
+        bus.publish(AudioChunk {
+            sample_rate,
+            samples: vec![0.0; len],
+        });
+    }
+}
+
+/// 入力レベルの集計（取り出すときに音声バスの受け手からまとめて集計する）
+struct LevelTap {
+    subscriber: AudioSubscriber,
+    meter: LevelMeter,
+}
+
+impl LevelTap {
+    fn take(&mut self) -> Option<LevelReading> {
+        loop {
+            match self.subscriber.try_recv() {
+                Ok(Some(chunk)) => self.meter.add(&chunk.samples, chunk.sample_rate),
+                Ok(None) | Err(BusError::Closed) => break,
+                // 読み遅れた区間は集計しない
+                Err(BusError::Lagged(_)) => continue,
+            }
+        }
+        self.meter.take()
+    }
+
+    /// ここまでの音声を集計せずに読み飛ばす
+    fn skip(&mut self) {
+        while let Ok(Some(_)) | Err(BusError::Lagged(_)) = self.subscriber.try_recv() {}
+        self.meter.take();
+    }
+}
+
+/// 取り出しスレッドのハンドル
+struct CaptureWorker {
+    running: Arc<AtomicBool>,
+    handle: JoinHandle<()>,
+}
+
+/// 取り出しスレッドがサンプルを配る先
+struct CaptureTargets {
+    buffer: Arc<AudioBuffer>,
+    resampled_buffer: Arc<AudioBuffer>,
+    timeline_samples: Arc<AtomicU64>,
+    overruns: Arc<AtomicU64>,
+    bus: AudioBus,
+    resampled_bus: AudioBus,
+    /// 16kHz側に適用する前処理
+    pipeline: ProcessingPipeline,
+    /// チャンネルごとのトラック（`frame_len` が1のときは空）
+    channels: Vec<ChannelTarget>,
+    /// 主入力とミックスする副入力
+    secondary: Option<SecondaryInput>,
+    /// リングバッファ上の1フレームのサンプル数
+    frame_len: usize,
+    sample_rate: u32,
+}
+
+/// チャンネルごとのトラックへの配信状態
+struct ChannelTarget {
+    track: ChannelTrack,
+    resampler: StreamingResampler,
+    pipeline: ProcessingPipeline,
+}
+
+impl ChannelTarget {
+    fn push(&mut self, samples: Vec<f32>) {
+        self.track.bus.publish(AudioChunk {
+            sample_rate: self.resampler.input_rate(),
+            samples: samples.clone(),
+        });
+        let resampled = self.resampler.process(&samples);
+        self.push_resampled(resampled);
+    }
+
+    fn push_resampled(&mut self, samples: Vec<f32>) {
+        let samples = self.pipeline.process_aligned(samples);
+        self.deliver_resampled(samples);
+    }
+
+    /// リサンプラーと前処理に残っている末尾を確定させる
+    fn flush(&mut self) {
+        let tail = self.resampler.flush();
+        self.push_resampled(tail);
+        let tail = self.pipeline.flush();
+        self.deliver_resampled(tail);
+    }
+
+    fn deliver_resampled(&mut self, samples: Vec<f32>) {
+        self.track.resampled_buffer.push_blocking(&samples);
+        self.track.resampled_bus.publish(AudioChunk {
+            sample_rate: WHISPER_SAMPLE_RATE,
+            samples,
+        });
+    }
+}
+
+/// 副入力の取り込みとミックスの状態
+struct SecondaryInput {
+    /// 副入力のリングバッファ（開始に失敗した場合はNoneで、無音としてミックスする）
+    consumer: Option<RingConsumer>,
+    /// 副入力のサンプルレートから主入力のサンプルレートへの変換
+    resampler: Option<StreamingResampler>,
+    scratch: Vec<f32>,
+    reported_overruns: u64,
+    mixer: StreamMixer,
+    /// 入力ごとのトラック（`InputTrack` の順）
+    tracks: Vec<ChannelTarget>,
+}
+
+impl SecondaryInput {
+    /// 副入力のリングバッファから取り出してミキサーに渡し、取り出したサンプル数を返す
+    fn receive(&mut self, overruns: &AtomicU64) -> usize {
+        let (Some(consumer), Some(resampler)) = (self.consumer.as_mut(), self.resampler.as_mut())
+        else {
+            return 0;
+        };
+        if self.scratch.is_empty() {
+            self.scratch = vec![0.0; (resampler.input_rate() / 10).max(1) as usize];
+        }
+
+        let count = consumer.pop_slice(&mut self.scratch);
+        let total = consumer.overruns();
+        if total > self.reported_overruns {
+            overruns.fetch_add(total - self.reported_overruns, Ordering::Relaxed);
+            self.reported_overruns = total;
+        }
+        if count > 0 {
+            let samples = resampler.process(&self.scratch[..count]);
+            self.mixer.push_secondary(&samples);
+        }
+        count
+    }
+
+    /// ミックス前の各入力をトラックへ配り、ミックスした音声を返す
+    fn deliver(&mut self, block: MixedBlock) -> Vec<f32> {
+        let MixedBlock { primary, secondary, mixed } = block;
+        if let [mic, system] = &mut self.tracks[..] {
+            mic.push(primary);
+            system.push(secondary);
+        }
+        mixed
+    }
+
+    fn flush_tracks(&mut self) {
+        for track in self.tracks.iter_mut() {
+            track.flush();
+        }
+    }
+}
+
+impl CaptureTargets {
+    /// `running` が false になり、リングバッファが空になるまで配り続ける
+    fn run(mut self, mut consumer: RingConsumer, running: Arc<AtomicBool>) {
+        let mut resampler = StreamingResampler::new(self.sample_rate, WHISPER_SAMPLE_RATE);
+        let mut secondary = self.secondary.take();
+        let mut scratch = vec![0.0; (self.sample_rate / 10).max(1) as usize * self.frame_len];
+        let mut mix = Vec::with_capacity(scratch.len() / self.frame_len);
+        let mut reported_overruns = 0;
+
+        loop {
+            // 停止要求を先に読むことで、それ以前に書き込まれた分は必ず取り出せる
+            let stopping = !running.load(Ordering::Acquire);
+            let count = consumer.pop_slice(&mut scratch);
+
+            let overruns = consumer.overruns();
+            if overruns > reported_overruns {
+                // 破棄はフレーム単位なので、モノラル換算のサンプル数で数える
+                let dropped = (overruns - reported_overruns) / self.frame_len as u64;
+                self.overruns.fetch_add(dropped, Ordering::Relaxed);
+                reported_overruns = overruns;
+            }
+
+            // 副入力は主入力が途切れていても取り出し、リングバッファをあふれさせない
+            let received = secondary
+                .as_mut()
+                .map_or(0, |input| input.receive(&self.overruns));
+
+            if count == 0 {
+                if stopping {
+                    break;
+                }
+                if received == 0 {
+                    std::thread::sleep(WORKER_POLL_INTERVAL);
+                }
+                continue;
+            }
+
+            let samples = if !self.channels.is_empty() {
+                self.split_channels(&scratch[..count], &mut mix);
+                &mix[..]
+            } else {
+                &scratch[..count]
+            };
+            match secondary.as_mut() {
+                Some(input) => {
+                    input.mixer.push_primary(samples);
+                    while let Some(block) = input.mixer.mix() {
+                        let mixed = input.deliver(block);
+                        self.publish(&mixed, &mut resampler);
+                    }
+                }
+                None => self.publish(samples, &mut resampler),
+            }
+        }
+
+        // ミキサーとリサンプラーに残っている末尾を確定させる
+        if let Some(input) = secondary.as_mut() {
+            if let Some(block) = input.mixer.flush() {
+                let mixed = input.deliver(block);
+                self.publish(&mixed, &mut resampler);
+            }
+            input.flush_tracks();
+        }
+        // 前処理の遅延分も取り出し、16kHz側の長さをデバイス側と揃える
+        self.push_resampled(resampler.flush());
+        let tail = self.pipeline.flush();
+        self.deliver_resampled(tail);
+        for channel in self.channels.iter_mut() {
+            channel.flush();
+        }
+    }
+
+    /// インターリーブされたフレームを各チャンネルのトラックへ配り、平均を `mix` に入れる
+    fn split_channels(&mut self, frames: &[f32], mix: &mut Vec<f32>) {
+        let channels = self.frame_len;
+        mix.clear();
+        mix.extend(frames.chunks(channels).map(|frame| frame.iter().sum::<f32>() / channels as f32));
+
+        // デバイスのチャンネル数がトラック数より少ない場合、残りのトラックは無音にする
+        for (index, channel) in self.channels.iter_mut().enumerate() {
+            let samples = if index < channels {
+                frames.iter().skip(index).step_by(channels).copied().collect()
+            } else {
+                vec![0.0; mix.len()]
+            };
+            channel.push(samples);
+        }
+    }
+
+    /// デバイスのサンプルレートの（ミックス済み）音声を各受け手へ配る
+    fn publish(&mut self, samples: &[f32], resampler: &mut StreamingResampler) {
+        self.buffer.push_blocking(samples);
+        self.bus.publish(AudioChunk {
+            sample_rate: self.sample_rate,
+            samples: samples.to_vec(),
+        });
+        self.push_resampled(resampler.process(samples));
+    }
+
+    /// 前処理の遅延を除いて配るため、タイムライン上の位置はデバイス側の音声と揃う
+    fn push_resampled(&mut self, samples: Vec<f32>) {
+        let samples = self.pipeline.process_aligned(samples);
+        self.deliver_resampled(samples);
+    }
+
+    fn deliver_resampled(&mut self, samples: Vec<f32>) {
+        self.timeline_samples.fetch_add(samples.len() as u64, Ordering::Relaxed);
+        self.resampled_buffer.push_blocking(&samples);
+        self.resampled_bus.publish(AudioChunk {
+            sample_rate: WHISPER_SAMPLE_RATE,
+            samples,
+        });
+    }
 }
 
 impl Default for AudioCapture {
@@ -181,14 +1214,233 @@ impl ToFloat for f32 {
     }
 }
 
+impl ToFloat for f64 {
+    fn to_float(&self) -> f32 {
+        *self as f32
+    }
+}
+
+impl ToFloat for i8 {
+    fn to_float(&self) -> f32 {
+        *self as f32 / i8::MAX as f32
+    }
+}
+
 impl ToFloat for i16 {
     fn to_float(&self) -> f32 {
         *self as f32 / i16::MAX as f32
     }
 }
 
+impl ToFloat for i32 {
+    fn to_float(&self) -> f32 {
+        (*self as f64 / i32::MAX as f64) as f32
+    }
+}
+
+impl ToFloat for i64 {
+    fn to_float(&self) -> f32 {
+        (*self as f64 / i64::MAX as f64) as f32
+    }
+}
+
+impl ToFloat for u8 {
+    fn to_float(&self) -> f32 {
+        (*self as f32 / u8::MAX as f32) * 2.0 - 1.0
+    }
+}
+
 impl ToFloat for u16 {
     fn to_float(&self) -> f32 {
         (*self as f32 / u16::MAX as f32) * 2.0 - 1.0
     }
 }
+
+impl ToFloat for u32 {
+    fn to_float(&self) -> f32 {
+        ((*self as f64 / u32::MAX as f64) * 2.0 - 1.0) as f32
+    }
+}
+
+impl ToFloat for u64 {
+    fn to_float(&self) -> f32 {
+        ((*self as f64 / u64::MAX as f64) * 2.0 - 1.0) as f32
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sample_conversion_range() {
+        let extremes = [
+            (i8::MIN.to_float(), i8::MAX.to_float()),
+            (i16::MIN.to_float(), i16::MAX.to_float()),
+            (i32::MIN.to_float(), i32::MAX.to_float()),
+            (i64::MIN.to_float(), i64::MAX.to_float()),
+            (u8::MIN.to_float(), u8::MAX.to_float()),
+            (u16::MIN.to_float(), u16::MAX.to_float()),
+            (u32::MIN.to_float(), u32::MAX.to_float()),
+            (u64::MIN.to_float(), u64::MAX.to_float()),
+            ((-1.0f64).to_float(), 1.0f64.to_float()),
+        ];
+        for (min, max) in extremes {
+            assert!((min + 1.0).abs() < 0.01, "min {min}");
+            assert!((max - 1.0).abs() < 1e-6, "max {max}");
+        }
+
+        // 符号なしの中央値は無音
+        assert!(128u8.to_float().abs() < 0.01);
+        assert!((u32::MAX / 2 + 1).to_float().abs() < 1e-6);
+    }
+
+    fn drain(subscriber: &mut AudioSubscriber) -> Vec<f32> {
+        let mut samples = Vec::new();
+        while let Ok(Some(chunk)) = subscriber.try_recv() {
+            samples.extend(chunk.samples);
+        }
+        samples
+    }
+
+    #[test]
+    fn test_secondary_source_is_mixed_and_kept_as_tracks() {
+        use crate::audio::{Pacing, ToneSource};
+
+        let duration = Some(Duration::from_millis(300));
+        let mic = ToneSource::new(440.0, 0.2, 16000, duration, Pacing::RealTime);
+        // 副入力は別のサンプルレートでも主入力のレートに揃えてミックスされる
+        let system = ToneSource::new(1000.0, 0.4, 8000, duration, Pacing::RealTime);
+
+        let mut capture = AudioCapture::from_source(Box::new(mic)).unwrap();
+        capture.set_secondary_source(Some(Box::new(system)));
+        capture.set_source_gains(0.0, -6.0);
+        let mut mic_track = capture.subscribe_input(InputTrack::Microphone).unwrap();
+        let mut system_track = capture.subscribe_input(InputTrack::System).unwrap();
+        let mut mixed = capture.subscribe();
+
+        capture.start_recording().unwrap();
+        while !capture.is_source_finished() {
+            std::thread::sleep(Duration::from_millis(5));
+        }
+        std::thread::sleep(Duration::from_millis(50));
+        capture.finish().unwrap();
+
+        let mic = drain(&mut mic_track);
+        let system = drain(&mut system_track);
+        let mixed = drain(&mut mixed);
+        assert_eq!(mic.len(), 4800);
+        assert_eq!(system.len(), 4800);
+        assert_eq!(mixed.len(), 4800);
+
+        let gain = db_to_gain(-6.0);
+        for i in (0..4800).step_by(97) {
+            assert!((mixed[i] - (mic[i] + system[i] * gain)).abs() < 1e-5);
+        }
+        let system_peak = system.iter().fold(0.0f32, |m, s| m.max(s.abs()));
+        assert!((system_peak - 0.4).abs() < 0.05, "system peak {system_peak}");
+    }
+
+    #[test]
+    fn test_rate_change_starts_new_native_segment() {
+        use crate::audio::{Pacing, ToneSource};
+
+        fn record(capture: &mut AudioCapture) {
+            capture.start_recording().unwrap();
+            while !capture.is_source_finished() {
+                std::thread::sleep(Duration::from_millis(5));
+            }
+            capture.stop_recording().unwrap();
+        }
+
+        let duration = Some(Duration::from_millis(100));
+        let mut capture = AudioCapture::from_source(Box::new(ToneSource::new(
+            440.0, 0.5, 48000, duration, Pacing::AsFastAsPossible,
+        ))).unwrap();
+        record(&mut capture);
+        assert_eq!(capture.get_buffer().get_all_blocking().len(), 4800);
+
+        // 再接続で別のレートのデバイスになった場合
+        capture.set_source(Box::new(ToneSource::new(
+            440.0, 0.5, 16000, duration, Pacing::AsFastAsPossible,
+        )));
+        record(&mut capture);
+        assert_eq!(capture.sample_rate(), 16000);
+        assert_eq!(capture.get_buffer().get_all_blocking().len(), 1600);
+        // タイムラインは途切れずに続く
+        assert!((capture.timeline_position_sec() - 0.2).abs() < 0.01);
+    }
+
+    #[test]
+    fn test_channel_layout_is_kept_after_reconnect() {
+        use crate::audio::{PcmEncoding, RawPcmSource};
+
+        fn pcm(frames: usize, frame: &[f32]) -> Box<dyn AudioSource> {
+            let bytes: Vec<u8> = std::iter::repeat_n(frame, frames)
+                .flatten()
+                .flat_map(|s| s.to_le_bytes())
+                .collect();
+            let source = RawPcmSource::from_reader(
+                "test",
+                std::io::Cursor::new(bytes),
+                16000,
+                frame.len(),
+                PcmEncoding::F32Le,
+            );
+            Box::new(source)
+        }
+
+        fn record(capture: &mut AudioCapture) {
+            capture.start_recording().unwrap();
+            while !capture.is_source_finished() {
+                std::thread::sleep(Duration::from_millis(5));
+            }
+            capture.stop_recording().unwrap();
+        }
+
+        let mut capture = AudioCapture::from_source(pcm(1600, &[0.5, -0.5])).unwrap();
+        capture.set_channel_mode(ChannelMode::Separate).unwrap();
+        let mut first = capture.subscribe_channel(0).unwrap();
+        let mut second = capture.subscribe_channel(1).unwrap();
+        record(&mut capture);
+
+        // 再接続でモノラルのデバイスになっても、既存のトラックに続けて配信する
+        capture.set_source(pcm(1600, &[0.25]));
+        record(&mut capture);
+        assert_eq!(capture.channel_count(), 2);
+        capture.finish().unwrap();
+
+        let first = drain(&mut first);
+        let second = drain(&mut second);
+        assert_eq!(first.len(), 3200);
+        assert_eq!(second.len(), 3200);
+        assert!(first[..1600].iter().all(|&s| s == 0.5));
+        assert!(first[1600..].iter().all(|&s| s == 0.25));
+        assert!(second[..1600].iter().all(|&s| s == -0.5));
+        assert!(second[1600..].iter().all(|&s| s == 0.0));
+    }
+
+    #[test]
+    fn test_level_is_measured_from_bus_without_filled_silence() {
+        use crate::audio::{Pacing, ToneSource};
+
+        let duration = Some(Duration::from_millis(100));
+        let mut capture = AudioCapture::from_source(Box::new(ToneSource::new(
+            440.0, 0.5, 48000, duration, Pacing::AsFastAsPossible,
+        ))).unwrap();
+        capture.start_recording().unwrap();
+        while !capture.is_source_finished() {
+            std::thread::sleep(Duration::from_millis(5));
+        }
+        capture.stop_recording().unwrap();
+
+        let reading = capture.take_level().unwrap();
+        assert!((reading.duration_sec - 0.1).abs() < 1e-6);
+        assert!((reading.peak_dbfs - crate::audio::level::to_dbfs(0.5)).abs() < 0.1, "peak {}", reading.peak_dbfs);
+        assert!(capture.take_level().is_none());
+
+        // 切断中に補填した無音は入力レベルに含めない
+        capture.insert_silence(1.0);
+        assert!(capture.take_level().is_none());
+    }
+}
@@ -0,0 +1,354 @@
+/// 入力レベルの計測と異常検出
+///
+/// キャプチャ経路で短いブロックごとにRMS・ピーク・クリップ数を集計し、
+/// 無音が続く（ミュート・デバイス選択ミス）や繰り返しのクリップを検出する
+use serde::Serialize;
+use std::collections::VecDeque;
+use std::time::Duration;
+
+/// クリップとみなす振幅
+pub const CLIP_THRESHOLD: f32 = 0.999;
+
+/// 無音時のdBFS下限（0振幅を有限値で表すため）
+const MIN_DBFS: f32 = -120.0;
+
+/// 1ブロック分の入力レベル
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+pub struct LevelReading {
+    /// RMSレベル（dBFS）
+    pub rms_dbfs: f32,
+    /// ピークレベル（dBFS）
+    pub peak_dbfs: f32,
+    /// クリップしたサンプル数
+    pub clipped_samples: usize,
+    /// ブロックの長さ（秒）
+    pub duration_sec: f64,
+}
+
+/// サンプルを集計してブロックごとのレベルを求める
+#[derive(Debug, Clone, Default)]
+pub struct LevelMeter {
+    sum_squares: f64,
+    peak: f32,
+    clipped: usize,
+    count: usize,
+    sample_rate: u32,
+}
+
+impl LevelMeter {
+    pub fn new(sample_rate: u32) -> Self {
+        LevelMeter {
+            sample_rate,
+            ..Default::default()
+        }
+    }
+
+    /// サンプルを集計に加える（レートが変わった場合はそれまでの集計を破棄）
+    pub fn add(&mut self, samples: &[f32], sample_rate: u32) {
+        if sample_rate != self.sample_rate {
+            *self = LevelMeter::new(sample_rate);
+        }
+        for &sample in samples {
+            let abs = sample.abs();
+            self.sum_squares += (sample as f64) * (sample as f64);
+            self.peak = self.peak.max(abs);
+            if abs >= CLIP_THRESHOLD {
+                self.clipped += 1;
+            }
+        }
+        self.count += samples.len();
+    }
+
+    /// ここまでの集計を1ブロックとして取り出し、集計をリセット
+    pub fn take(&mut self) -> Option<LevelReading> {
+        if self.count == 0 || self.sample_rate == 0 {
+            return None;
+        }
+
+        let rms = (self.sum_squares / self.count as f64).sqrt() as f32;
+        let reading = LevelReading {
+            rms_dbfs: to_dbfs(rms),
+            peak_dbfs: to_dbfs(self.peak),
+            clipped_samples: self.clipped,
+            duration_sec: self.count as f64 / self.sample_rate as f64,
+        };
+        *self = LevelMeter::new(self.sample_rate);
+        Some(reading)
+    }
+}
+
+/// 入力レベルの警告
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum LevelWarning {
+    /// 入力が無音のまま続いている
+    Silence { seconds: f64 },
+    /// 短時間に繰り返しクリップしている
+    Clipping { clipped_blocks: usize },
+}
+
+/// 警告の判定条件
+#[derive(Debug, Clone)]
+pub struct LevelWatchConfig {
+    /// これ未満のRMSを無音とみなす（dBFS）
+    pub silence_threshold_dbfs: f32,
+    /// 無音がこの時間続いたら警告
+    pub silence_timeout: Duration,
+    /// クリップを数える直近の時間幅
+    pub clip_window: Duration,
+    /// 時間幅内でクリップしたブロックがこの数以上なら警告
+    pub clip_blocks: usize,
+}
+
+impl Default for LevelWatchConfig {
+    fn default() -> Self {
+        LevelWatchConfig {
+            silence_threshold_dbfs: -60.0,
+            silence_timeout: Duration::from_secs(10),
+            clip_window: Duration::from_secs(5),
+            clip_blocks: 5,
+        }
+    }
+}
+
+/// レベルの推移を監視し、状態が変わったときだけ警告を出す
+pub struct LevelWatch {
+    config: LevelWatchConfig,
+    silent_for: f64,
+    silence_warned: bool,
+    /// 直近のブロック（長さ, クリップしたか）
+    recent: VecDeque<(f64, bool)>,
+    clipping_warned: bool,
+}
+
+impl LevelWatch {
+    pub fn new(config: LevelWatchConfig) -> Self {
+        LevelWatch {
+            config,
+            silent_for: 0.0,
+            silence_warned: false,
+            recent: VecDeque::new(),
+            clipping_warned: false,
+        }
+    }
+
+    /// 1ブロック分のレベルを反映し、新たに発生した警告を返す
+    ///
+    /// 同じ警告は、状態が回復するまで繰り返さない
+    pub fn update(&mut self, reading: &LevelReading) -> Vec<LevelWarning> {
+        let mut warnings = Vec::new();
+
+        if reading.rms_dbfs < self.config.silence_threshold_dbfs {
+            self.silent_for += reading.duration_sec;
+            if !self.silence_warned && self.silent_for >= self.config.silence_timeout.as_secs_f64() {
+                self.silence_warned = true;
+                warnings.push(LevelWarning::Silence { seconds: self.silent_for });
+            }
+        } else {
+            self.silent_for = 0.0;
+            self.silence_warned = false;
+        }
+
+        self.recent.push_back((reading.duration_sec, reading.clipped_samples > 0));
+        let window = self.config.clip_window.as_secs_f64();
+        let mut total: f64 = self.recent.iter().map(|(d, _)| d).sum();
+        while total > window {
+            match self.recent.pop_front() {
+                Some((d, _)) => total -= d,
+                None => break,
+            }
+        }
+
+        let clipped_blocks = self.recent.iter().filter(|(_, clipped)| *clipped).count();
+        if clipped_blocks >= self.config.clip_blocks {
+            if !self.clipping_warned {
+                self.clipping_warned = true;
+                warnings.push(LevelWarning::Clipping { clipped_blocks });
+            }
+        } else if clipped_blocks == 0 {
+            self.clipping_warned = false;
+        }
+
+        warnings
+    }
+
+    /// 監視状態を初期化（一時停止・再接続時など）
+    pub fn reset(&mut self) {
+        self.silent_for = 0.0;
+        self.silence_warned = false;
+        self.recent.clear();
+        self.clipping_warned = false;
+    }
+}
+
+impl Default for LevelWatch {
+    fn default() -> Self {
+        Self::new(LevelWatchConfig::default())
+    }
+}
+
+/// 短い録音全体のレベル分析結果（マイクテスト用）
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ClipAnalysis {
+    /// 静かな区間のレベル（dBFS）
+    pub noise_floor_dbfs: f32,
+    /// 発話区間のレベル（dBFS）
+    pub speech_level_dbfs: f32,
+    /// 推定SN比（dB）
+    pub snr_db: f32,
+    /// ピークレベル（dBFS）
+    pub peak_dbfs: f32,
+    /// クリップしたサンプル数
+    pub clipped_samples: usize,
+    /// 録音の長さ（秒）
+    pub duration_sec: f64,
+}
+
+/// 録音を50msのフレームに分け、静かな側・大きな側の各10%をノイズ・発話とみなして分析
+pub fn analyze_clip(samples: &[f32], sample_rate: u32) -> ClipAnalysis {
+    let frame_len = (sample_rate as usize / 20).max(1);
+    let mut frame_rms: Vec<f32> = samples
+        .chunks(frame_len)
+        .map(|frame| (frame.iter().map(|s| s * s).sum::<f32>() / frame.len() as f32).sqrt())
+        .collect();
+    frame_rms.sort_by(|a, b| a.total_cmp(b));
+
+    let tail = (frame_rms.len() / 10).max(1).min(frame_rms.len());
+    let mean_power = |frames: &[f32]| {
+        if frames.is_empty() {
+            0.0
+        } else {
+            (frames.iter().map(|r| r * r).sum::<f32>() / frames.len() as f32).sqrt()
+        }
+    };
+    let noise_floor_dbfs = to_dbfs(mean_power(&frame_rms[..tail]));
+    let speech_level_dbfs = to_dbfs(mean_power(&frame_rms[frame_rms.len() - tail..]));
+
+    let mut meter = LevelMeter::new(sample_rate);
+    meter.add(samples, sample_rate);
+    let (peak_dbfs, clipped_samples) = meter
+        .take()
+        .map(|r| (r.peak_dbfs, r.clipped_samples))
+        .unwrap_or((MIN_DBFS, 0));
+
+    ClipAnalysis {
+        noise_floor_dbfs,
+        speech_level_dbfs,
+        snr_db: speech_level_dbfs - noise_floor_dbfs,
+        peak_dbfs,
+        clipped_samples,
+        duration_sec: if sample_rate == 0 { 0.0 } else { samples.len() as f64 / sample_rate as f64 },
+    }
+}
+
+/// 線形振幅をdBFSに変換
+pub fn to_dbfs(amplitude: f32) -> f32 {
+    if amplitude <= 0.0 {
+        MIN_DBFS
+    } else {
+        (20.0 * amplitude.log10()).max(MIN_DBFS)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn reading(rms_dbfs: f32, clipped_samples: usize) -> LevelReading {
+        LevelReading {
+            rms_dbfs,
+            peak_dbfs: rms_dbfs + 3.0,
+            clipped_samples,
+            duration_sec: 0.1,
+        }
+    }
+
+    #[test]
+    fn test_meter_sine_levels() {
+        let samples: Vec<f32> = (0..1600)
+            .map(|i| 0.5 * (2.0 * std::f32::consts::PI * 1000.0 * i as f32 / 16000.0).sin())
+            .collect();
+        let mut meter = LevelMeter::new(16000);
+        meter.add(&samples, 16000);
+
+        let reading = meter.take().unwrap();
+        // 振幅0.5の正弦波: ピーク約-6dBFS、RMS約-9dBFS
+        assert!((reading.peak_dbfs + 6.02).abs() < 0.1);
+        assert!((reading.rms_dbfs + 9.03).abs() < 0.1);
+        assert_eq!(reading.clipped_samples, 0);
+        assert!((reading.duration_sec - 0.1).abs() < 1e-9);
+
+        // 取り出すと集計はリセットされる
+        assert!(meter.take().is_none());
+    }
+
+    #[test]
+    fn test_meter_counts_clipping() {
+        let mut meter = LevelMeter::new(16000);
+        meter.add(&[1.0, -1.0, 0.2, 1.5], 16000);
+        assert_eq!(meter.take().unwrap().clipped_samples, 3);
+    }
+
+    #[test]
+    fn test_silence_warning_after_timeout() {
+        let mut watch = LevelWatch::new(LevelWatchConfig {
+            silence_timeout: Duration::from_secs(1),
+            ..Default::default()
+        });
+
+        let mut warnings = Vec::new();
+        for _ in 0..15 {
+            warnings.extend(watch.update(&reading(-90.0, 0)));
+        }
+        assert_eq!(warnings.len(), 1);
+        assert!(matches!(warnings[0], LevelWarning::Silence { seconds } if seconds >= 1.0));
+
+        // 音声が入ると回復し、再び無音が続けばもう一度警告する
+        assert!(watch.update(&reading(-20.0, 0)).is_empty());
+        let again: Vec<_> = (0..15).flat_map(|_| watch.update(&reading(-90.0, 0))).collect();
+        assert_eq!(again.len(), 1);
+    }
+
+    #[test]
+    fn test_clipping_warning_needs_repetition() {
+        let mut watch = LevelWatch::default();
+
+        // 単発のクリップでは警告しない
+        assert!(watch.update(&reading(-10.0, 1)).is_empty());
+        for _ in 0..3 {
+            assert!(watch.update(&reading(-10.0, 0)).is_empty());
+        }
+
+        let warnings: Vec<_> = (0..6).flat_map(|_| watch.update(&reading(-3.0, 10))).collect();
+        assert_eq!(warnings, vec![LevelWarning::Clipping { clipped_blocks: 5 }]);
+    }
+
+    #[test]
+    fn test_analyze_clip_estimates_snr() {
+        // 前半は振幅0.001の小さなノイズ、後半は振幅0.3の「発話」
+        let samples: Vec<f32> = (0..32000)
+            .map(|i| {
+                let t = i as f32 / 16000.0;
+                if i < 16000 {
+                    0.001 * (2.0 * std::f32::consts::PI * 3000.0 * t).sin()
+                } else {
+                    0.3 * (2.0 * std::f32::consts::PI * 300.0 * t).sin()
+                }
+            })
+            .collect();
+
+        let analysis = analyze_clip(&samples, 16000);
+        assert!((analysis.noise_floor_dbfs - to_dbfs(0.001 / 2f32.sqrt())).abs() < 1.0);
+        assert!((analysis.speech_level_dbfs - to_dbfs(0.3 / 2f32.sqrt())).abs() < 1.0);
+        assert!((analysis.snr_db - 49.5).abs() < 1.5);
+        assert_eq!(analysis.clipped_samples, 0);
+        assert!((analysis.duration_sec - 2.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_analyze_empty_clip() {
+        let analysis = analyze_clip(&[], 16000);
+        assert_eq!(analysis.snr_db, 0.0);
+        assert_eq!(analysis.duration_sec, 0.0);
+    }
+}
@@ -0,0 +1,170 @@
+/// 2系統の入力（マイクとシステム音声など）の時刻合わせとミックス
+///
+/// 別々のデバイスはコールバックの間隔も届くタイミングも異なるため、
+/// 主入力を基準に副入力を待ち合わせ、一定以上遅れた分は無音で埋めて時刻を保つ
+use std::collections::VecDeque;
+
+/// ミックス済みの1ブロック（3つとも同じ長さ・同じ時刻）
+#[derive(Debug, Clone, PartialEq)]
+pub struct MixedBlock {
+    /// 主入力（ゲイン適用前）
+    pub primary: Vec<f32>,
+    /// 副入力（ゲイン適用前。届かなかった区間は無音）
+    pub secondary: Vec<f32>,
+    /// 各入力にゲインを掛けて足し合わせた音声
+    pub mixed: Vec<f32>,
+}
+
+/// 主入力を基準に副入力を揃えてミックスする
+pub struct StreamMixer {
+    primary: VecDeque<f32>,
+    secondary: VecDeque<f32>,
+    /// 無音で埋めたため、今後届く副入力から捨てるサンプル数
+    secondary_debt: usize,
+    /// 副入力を待つ最大サンプル数（これを超えると無音で埋めて先に進む）
+    max_latency: usize,
+    primary_gain: f32,
+    secondary_gain: f32,
+}
+
+impl StreamMixer {
+    pub fn new(max_latency: usize) -> Self {
+        StreamMixer {
+            primary: VecDeque::new(),
+            secondary: VecDeque::new(),
+            secondary_debt: 0,
+            max_latency: max_latency.max(1),
+            primary_gain: 1.0,
+            secondary_gain: 1.0,
+        }
+    }
+
+    /// 各入力のゲイン（線形）を設定
+    pub fn set_gains(&mut self, primary: f32, secondary: f32) {
+        self.primary_gain = primary;
+        self.secondary_gain = secondary;
+    }
+
+    /// 主入力のサンプルを追加
+    pub fn push_primary(&mut self, samples: &[f32]) {
+        self.primary.extend(samples);
+    }
+
+    /// 副入力のサンプルを追加（主入力と同じサンプルレートに変換済みであること）
+    pub fn push_secondary(&mut self, samples: &[f32]) {
+        // 無音で埋めた区間に相当する遅れて届いた分は捨てる
+        let skip = self.secondary_debt.min(samples.len());
+        self.secondary_debt -= skip;
+        self.secondary.extend(&samples[skip..]);
+
+        // 主入力が止まっている・副入力のクロックが速いなどで溜まりすぎた分は古い方から捨てる
+        let limit = self.primary.len() + self.max_latency;
+        if self.secondary.len() > limit {
+            let excess = self.secondary.len() - limit;
+            self.secondary.drain(..excess);
+        }
+    }
+
+    /// 揃った分をミックスして取り出す
+    ///
+    /// 副入力が `max_latency` を超えて遅れている場合は、その分を無音としてミックスする
+    pub fn mix(&mut self) -> Option<MixedBlock> {
+        let mut ready = self.primary.len().min(self.secondary.len());
+        if self.primary.len() > self.secondary.len() + self.max_latency {
+            ready = self.primary.len() - self.max_latency;
+        }
+        self.take(ready)
+    }
+
+    /// 残っている主入力をすべて取り出す（停止時）
+    pub fn flush(&mut self) -> Option<MixedBlock> {
+        let block = self.take(self.primary.len());
+        self.secondary.clear();
+        self.secondary_debt = 0;
+        block
+    }
+
+    fn take(&mut self, len: usize) -> Option<MixedBlock> {
+        if len == 0 {
+            return None;
+        }
+
+        let primary: Vec<f32> = self.primary.drain(..len).collect();
+        let available = self.secondary.len().min(len);
+        let mut secondary: Vec<f32> = self.secondary.drain(..available).collect();
+        self.secondary_debt += len - available;
+        secondary.resize(len, 0.0);
+
+        let mixed = primary
+            .iter()
+            .zip(&secondary)
+            .map(|(p, s)| p * self.primary_gain + s * self.secondary_gain)
+            .collect();
+
+        Some(MixedBlock {
+            primary,
+            secondary,
+            mixed,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn ramp(start: usize, len: usize) -> Vec<f32> {
+        (start..start + len).map(|i| i as f32).collect()
+    }
+
+    #[test]
+    fn test_waits_for_late_secondary_within_latency() {
+        let mut mixer = StreamMixer::new(100);
+        mixer.push_primary(&ramp(0, 50));
+        assert!(mixer.mix().is_none());
+
+        mixer.push_secondary(&ramp(0, 80));
+        let block = mixer.mix().unwrap();
+        assert_eq!(block.primary, ramp(0, 50));
+        assert_eq!(block.secondary, ramp(0, 50));
+        assert_eq!(block.mixed, ramp(0, 50).iter().map(|v| v * 2.0).collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn test_fills_silence_and_keeps_alignment_when_secondary_stalls() {
+        let mut mixer = StreamMixer::new(100);
+        mixer.push_primary(&ramp(0, 300));
+        let block = mixer.mix().unwrap();
+        assert_eq!(block.primary.len(), 200);
+        assert!(block.secondary.iter().all(|&s| s == 0.0));
+
+        // 遅れて届いた副入力のうち、無音で埋めた200サンプル分は捨てられる
+        mixer.push_secondary(&ramp(0, 300));
+        let block = mixer.mix().unwrap();
+        assert_eq!(block.primary, ramp(200, 100));
+        assert_eq!(block.secondary, ramp(200, 100));
+    }
+
+    #[test]
+    fn test_drops_secondary_backlog() {
+        let mut mixer = StreamMixer::new(10);
+        mixer.push_secondary(&ramp(0, 50));
+        mixer.push_primary(&ramp(0, 5));
+
+        let block = mixer.mix().unwrap();
+        // 主入力がない間に許容量10を超えた古い40サンプルは捨てられている
+        assert_eq!(block.secondary, ramp(40, 5));
+    }
+
+    #[test]
+    fn test_gains_and_flush() {
+        let mut mixer = StreamMixer::new(100);
+        mixer.set_gains(0.5, 2.0);
+        mixer.push_primary(&[1.0, 1.0, 1.0]);
+        mixer.push_secondary(&[1.0]);
+
+        let block = mixer.flush().unwrap();
+        assert_eq!(block.mixed, vec![2.5, 0.5, 0.5]);
+        assert!(mixer.flush().is_none());
+    }
+}
@@ -2,8 +2,28 @@ pub mod capture;
 pub mod buffer;
 pub mod resample;
 pub mod mel;
+pub mod ring;
+pub mod bus;
+pub mod pipeline;
+pub mod level;
+pub mod source;
+pub mod mix;
 
-pub use capture::AudioCapture;
-pub use buffer::AudioBuffer;
-pub use resample::{resample_linear, resample_for_whisper};
+pub use capture::{AudioCapture, AudioChunk, ChannelMode, CpalSource, InputDeviceInfo, InputTrack};
+pub use buffer::{AudioBuffer, BufferError};
+pub use resample::{
+    resample_linear, resample_sinc, resample_for_whisper, SincResampler, StreamingResampler,
+    WHISPER_SAMPLE_RATE,
+};
 pub use mel::{MelConfig, log_mel_spectrogram};
+pub use ring::{ring_buffer, RingConsumer, RingProducer};
+pub use bus::{AudioBus, AudioSubscriber, BusError};
+pub use pipeline::{AudioProcessor, PipelineConfig, ProcessingPipeline};
+pub use level::{
+    analyze_clip, ClipAnalysis, LevelMeter, LevelReading, LevelWarning, LevelWatch, LevelWatchConfig,
+};
+pub use source::{
+    AudioSource, AudioSourceConfig, Pacing, PcmEncoding, RawPcmSource, SourceFormat, SourceSink,
+    ToneSource, WavFileSource,
+};
+pub use mix::{MixedBlock, StreamMixer};
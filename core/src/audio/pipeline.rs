@@ -0,0 +1,708 @@
+/// 音声前処理パイプライン
+///
+/// キャプチャした音声をASRへ渡す前に、DC成分除去・ハイパスフィルタ・自動ゲイン調整・
+/// スペクトル減算によるノイズ抑制・ピークリミッターを順に適用する。
+/// 各段は設定から個別に有効/無効（バイパス）を切り替えられる
+use rustfft::{num_complex::Complex, Fft, FftPlanner};
+use serde::{Deserialize, Serialize};
+use std::collections::VecDeque;
+use std::f32::consts::PI;
+use std::sync::Arc;
+
+/// 音声処理段の共通インターフェース
+pub trait AudioProcessor: Send {
+    /// 段の名前（バイパス切り替えに使用）
+    fn name(&self) -> &'static str;
+
+    /// サンプルをその場で処理
+    fn process(&mut self, samples: &mut [f32]);
+
+    /// 内部状態を初期化
+    fn reset(&mut self) {}
+
+    /// 処理による出力の遅延（サンプル数）
+    fn latency(&self) -> usize {
+        0
+    }
+}
+
+/// 前処理パイプラインの設定
+///
+/// 既定ではすべての段を無効にし、前処理なしの音声をそのままASRへ渡す
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct PipelineConfig {
+    /// DC成分除去
+    pub dc_removal: bool,
+    /// ハイパスフィルタ
+    pub high_pass: bool,
+    /// ハイパスフィルタのカットオフ周波数（Hz）
+    pub high_pass_cutoff_hz: f32,
+    /// 自動ゲイン調整
+    pub agc: bool,
+    /// 自動ゲイン調整の目標レベル（dBFS, RMS）
+    pub agc_target_dbfs: f32,
+    /// 自動ゲイン調整の最大ゲイン（dB）
+    pub agc_max_gain_db: f32,
+    /// スペクトル減算によるノイズ抑制
+    pub noise_suppression: bool,
+    /// ノイズ抑制の強さ（過減算係数。1.0で推定ノイズ分をそのまま減算）
+    pub noise_suppression_strength: f32,
+    /// ピークリミッター
+    pub limiter: bool,
+    /// リミッターの閾値（線形振幅）
+    pub limiter_threshold: f32,
+}
+
+impl Default for PipelineConfig {
+    fn default() -> Self {
+        PipelineConfig {
+            dc_removal: false,
+            high_pass: false,
+            high_pass_cutoff_hz: 80.0,
+            agc: false,
+            agc_target_dbfs: -20.0,
+            agc_max_gain_db: 30.0,
+            noise_suppression: false,
+            noise_suppression_strength: 1.5,
+            limiter: false,
+            limiter_threshold: 0.95,
+        }
+    }
+}
+
+struct Stage {
+    processor: Box<dyn AudioProcessor>,
+    enabled: bool,
+}
+
+/// 処理段を順に適用するパイプライン
+#[derive(Default)]
+pub struct ProcessingPipeline {
+    stages: Vec<Stage>,
+    /// `process_aligned` で読み捨てた遅延分のサンプル数
+    skipped: usize,
+}
+
+impl ProcessingPipeline {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// 設定に従って全段を構築（無効な段はバイパス状態で追加）
+    pub fn from_config(config: &PipelineConfig, sample_rate: u32) -> Self {
+        ProcessingPipeline::new()
+            .with_stage(Box::new(DcRemover::new()), config.dc_removal)
+            .with_stage(
+                Box::new(HighPassFilter::new(config.high_pass_cutoff_hz, sample_rate)),
+                config.high_pass,
+            )
+            .with_stage(
+                Box::new(NoiseSuppressor::new(config.noise_suppression_strength)),
+                config.noise_suppression,
+            )
+            .with_stage(
+                Box::new(AutomaticGainControl::new(
+                    config.agc_target_dbfs,
+                    config.agc_max_gain_db,
+                    sample_rate,
+                )),
+                config.agc,
+            )
+            .with_stage(
+                Box::new(PeakLimiter::new(config.limiter_threshold, sample_rate)),
+                config.limiter,
+            )
+    }
+
+    /// 処理段を末尾に追加
+    pub fn with_stage(mut self, processor: Box<dyn AudioProcessor>, enabled: bool) -> Self {
+        self.stages.push(Stage { processor, enabled });
+        self
+    }
+
+    /// 名前を指定して段の有効/無効を切り替え
+    ///
+    /// # Returns
+    /// 該当する段が見つかったかどうか
+    pub fn set_enabled(&mut self, name: &str, enabled: bool) -> bool {
+        match self.stages.iter_mut().find(|s| s.processor.name() == name) {
+            Some(stage) => {
+                if stage.enabled != enabled {
+                    stage.processor.reset();
+                }
+                stage.enabled = enabled;
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// 遅延を除いて処理し、出力の位置を入力と揃える
+    ///
+    /// 先頭の遅延分を読み捨てるため出力は入力より短くなる。残りは最後に `flush` で取り出す
+    pub fn process_aligned(&mut self, mut samples: Vec<f32>) -> Vec<f32> {
+        self.process(&mut samples);
+        let skip = self.latency().saturating_sub(self.skipped).min(samples.len());
+        self.skipped += skip;
+        samples.drain(..skip);
+        samples
+    }
+
+    /// 遅延により残っている末尾を取り出す（`process_aligned` の出力と合わせて入力と同じ長さになる）
+    pub fn flush(&mut self) -> Vec<f32> {
+        let tail = self.process_aligned(vec![0.0; self.latency()]);
+        self.skipped = 0;
+        tail
+    }
+
+    /// 有効な段の名前一覧
+    pub fn enabled_stages(&self) -> Vec<&'static str> {
+        self.stages
+            .iter()
+            .filter(|s| s.enabled)
+            .map(|s| s.processor.name())
+            .collect()
+    }
+}
+
+impl AudioProcessor for ProcessingPipeline {
+    fn name(&self) -> &'static str {
+        "pipeline"
+    }
+
+    fn process(&mut self, samples: &mut [f32]) {
+        for stage in self.stages.iter_mut().filter(|s| s.enabled) {
+            stage.processor.process(samples);
+        }
+    }
+
+    fn reset(&mut self) {
+        for stage in self.stages.iter_mut() {
+            stage.processor.reset();
+        }
+        self.skipped = 0;
+    }
+
+    fn latency(&self) -> usize {
+        self.stages
+            .iter()
+            .filter(|s| s.enabled)
+            .map(|s| s.processor.latency())
+            .sum()
+    }
+}
+
+/// DC成分除去（1次のDCブロッカー）
+pub struct DcRemover {
+    pole: f32,
+    prev_input: f32,
+    prev_output: f32,
+}
+
+impl DcRemover {
+    pub fn new() -> Self {
+        DcRemover {
+            pole: 0.995,
+            prev_input: 0.0,
+            prev_output: 0.0,
+        }
+    }
+}
+
+impl Default for DcRemover {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl AudioProcessor for DcRemover {
+    fn name(&self) -> &'static str {
+        "dc_removal"
+    }
+
+    fn process(&mut self, samples: &mut [f32]) {
+        for sample in samples.iter_mut() {
+            let output = *sample - self.prev_input + self.pole * self.prev_output;
+            self.prev_input = *sample;
+            self.prev_output = output;
+            *sample = output;
+        }
+    }
+
+    fn reset(&mut self) {
+        self.prev_input = 0.0;
+        self.prev_output = 0.0;
+    }
+}
+
+/// 2次バターワース・ハイパスフィルタ（空調音や机の振動などの低域ノイズ除去）
+pub struct HighPassFilter {
+    b: [f32; 3],
+    a: [f32; 2],
+    x: [f32; 2],
+    y: [f32; 2],
+}
+
+impl HighPassFilter {
+    pub fn new(cutoff_hz: f32, sample_rate: u32) -> Self {
+        let w0 = 2.0 * PI * cutoff_hz / sample_rate as f32;
+        let alpha = w0.sin() / (2.0 * std::f32::consts::FRAC_1_SQRT_2);
+        let cos = w0.cos();
+        let a0 = 1.0 + alpha;
+
+        HighPassFilter {
+            b: [(1.0 + cos) / 2.0 / a0, -(1.0 + cos) / a0, (1.0 + cos) / 2.0 / a0],
+            a: [-2.0 * cos / a0, (1.0 - alpha) / a0],
+            x: [0.0; 2],
+            y: [0.0; 2],
+        }
+    }
+}
+
+impl AudioProcessor for HighPassFilter {
+    fn name(&self) -> &'static str {
+        "high_pass"
+    }
+
+    fn process(&mut self, samples: &mut [f32]) {
+        for sample in samples.iter_mut() {
+            let input = *sample;
+            let output = self.b[0] * input + self.b[1] * self.x[0] + self.b[2] * self.x[1]
+                - self.a[0] * self.y[0]
+                - self.a[1] * self.y[1];
+            self.x = [input, self.x[0]];
+            self.y = [output, self.y[0]];
+            *sample = output;
+        }
+    }
+
+    fn reset(&mut self) {
+        self.x = [0.0; 2];
+        self.y = [0.0; 2];
+    }
+}
+
+/// 自動ゲイン調整（小さな声や遠いマイクを目標レベルまで持ち上げる）
+pub struct AutomaticGainControl {
+    target_rms: f32,
+    max_gain: f32,
+    /// これより小さいレベルは無音とみなし、ゲインを上げない
+    gate_rms: f32,
+    /// 平均二乗レベルの追従係数
+    level_coeff: f32,
+    /// ゲインを下げるときの追従係数
+    attack_coeff: f32,
+    /// ゲインを上げるときの追従係数
+    release_coeff: f32,
+    mean_square: f32,
+    gain: f32,
+}
+
+impl AutomaticGainControl {
+    pub fn new(target_dbfs: f32, max_gain_db: f32, sample_rate: u32) -> Self {
+        AutomaticGainControl {
+            target_rms: db_to_linear(target_dbfs),
+            max_gain: db_to_linear(max_gain_db),
+            gate_rms: db_to_linear(-60.0),
+            level_coeff: time_constant(0.3, sample_rate),
+            attack_coeff: time_constant(0.05, sample_rate),
+            release_coeff: time_constant(1.0, sample_rate),
+            mean_square: 0.0,
+            gain: 1.0,
+        }
+    }
+
+    /// 現在のゲイン（線形）
+    pub fn gain(&self) -> f32 {
+        self.gain
+    }
+}
+
+impl AudioProcessor for AutomaticGainControl {
+    fn name(&self) -> &'static str {
+        "agc"
+    }
+
+    fn process(&mut self, samples: &mut [f32]) {
+        for sample in samples.iter_mut() {
+            self.mean_square += (*sample * *sample - self.mean_square) * (1.0 - self.level_coeff);
+            let rms = self.mean_square.sqrt();
+
+            let desired = if rms > self.gate_rms {
+                (self.target_rms / rms).min(self.max_gain)
+            } else {
+                self.gain
+            };
+            let coeff = if desired < self.gain { self.attack_coeff } else { self.release_coeff };
+            self.gain += (desired - self.gain) * (1.0 - coeff);
+
+            *sample *= self.gain;
+        }
+    }
+
+    fn reset(&mut self) {
+        self.mean_square = 0.0;
+        self.gain = 1.0;
+    }
+}
+
+/// ピークリミッター（閾値を超える振幅を即座に抑え、ゆっくり戻す）
+pub struct PeakLimiter {
+    threshold: f32,
+    release_coeff: f32,
+    gain: f32,
+}
+
+impl PeakLimiter {
+    pub fn new(threshold: f32, sample_rate: u32) -> Self {
+        PeakLimiter {
+            threshold,
+            release_coeff: time_constant(0.1, sample_rate),
+            gain: 1.0,
+        }
+    }
+}
+
+impl AudioProcessor for PeakLimiter {
+    fn name(&self) -> &'static str {
+        "limiter"
+    }
+
+    fn process(&mut self, samples: &mut [f32]) {
+        for sample in samples.iter_mut() {
+            let peak = sample.abs();
+            let required = if peak > self.threshold { self.threshold / peak } else { 1.0 };
+
+            if required < self.gain {
+                self.gain = required;
+            } else {
+                self.gain += (1.0 - self.gain) * (1.0 - self.release_coeff);
+                self.gain = self.gain.min(required);
+            }
+            *sample *= self.gain;
+        }
+    }
+
+    fn reset(&mut self) {
+        self.gain = 1.0;
+    }
+}
+
+/// スペクトル減算によるノイズ抑制
+///
+/// 音声が小さいフレームからノイズスペクトルを推定し、各周波数ビンから減算する。
+/// 50%オーバーラップのSTFT（平方根ハン窓）で処理するため、
+/// 出力は `latency_samples` だけ遅れる
+pub struct NoiseSuppressor {
+    strength: f32,
+    /// 減算後に残す最小ゲイン（ミュージカルノイズ対策）
+    floor: f32,
+    fft: Arc<dyn Fft<f32>>,
+    ifft: Arc<dyn Fft<f32>>,
+    window: Vec<f32>,
+    /// 推定ノイズ振幅スペクトル
+    noise: Vec<f32>,
+    /// ノイズ推定に使ったフレーム数
+    noise_frames: usize,
+    input: Vec<f32>,
+    overlap: Vec<f32>,
+    output: VecDeque<f32>,
+}
+
+impl NoiseSuppressor {
+    const FRAME_LEN: usize = 512;
+    const HOP: usize = Self::FRAME_LEN / 2;
+    /// 最初にノイズを学習するフレーム数
+    const INITIAL_NOISE_FRAMES: usize = 8;
+
+    pub fn new(strength: f32) -> Self {
+        let mut planner = FftPlanner::new();
+        let window = (0..Self::FRAME_LEN)
+            .map(|i| (0.5 - 0.5 * (2.0 * PI * i as f32 / Self::FRAME_LEN as f32).cos()).sqrt())
+            .collect();
+
+        NoiseSuppressor {
+            strength,
+            floor: 0.1,
+            fft: planner.plan_fft_forward(Self::FRAME_LEN),
+            ifft: planner.plan_fft_inverse(Self::FRAME_LEN),
+            window,
+            noise: vec![0.0; Self::FRAME_LEN / 2 + 1],
+            noise_frames: 0,
+            input: Vec::with_capacity(Self::FRAME_LEN * 2),
+            overlap: vec![0.0; Self::FRAME_LEN],
+            output: VecDeque::from(vec![0.0; Self::latency_samples()]),
+        }
+    }
+
+    /// 処理による遅延（サンプル数）
+    pub const fn latency_samples() -> usize {
+        Self::FRAME_LEN - 1
+    }
+
+    fn process_frame(&mut self) {
+        let mut spectrum: Vec<Complex<f32>> = self.input[..Self::FRAME_LEN]
+            .iter()
+            .zip(self.window.iter())
+            .map(|(&x, &w)| Complex::new(x * w, 0.0))
+            .collect();
+        self.fft.process(&mut spectrum);
+
+        let bins = Self::FRAME_LEN / 2 + 1;
+        let magnitudes: Vec<f32> = spectrum[..bins].iter().map(|c| c.norm()).collect();
+        self.update_noise(&magnitudes);
+
+        for (k, &mag) in magnitudes.iter().enumerate() {
+            let gain = if mag > 0.0 {
+                (1.0 - self.strength * self.noise[k] / mag).max(self.floor)
+            } else {
+                self.floor
+            };
+            spectrum[k] *= gain;
+            // 実信号のため負の周波数側も同じゲインにする
+            if k > 0 && k < Self::FRAME_LEN - k {
+                spectrum[Self::FRAME_LEN - k] *= gain;
+            }
+        }
+
+        self.ifft.process(&mut spectrum);
+        let scale = 1.0 / Self::FRAME_LEN as f32;
+        for (i, c) in spectrum.iter().enumerate() {
+            self.overlap[i] += c.re * scale * self.window[i];
+        }
+
+        self.output.extend(self.overlap.drain(..Self::HOP));
+        self.overlap.resize(Self::FRAME_LEN, 0.0);
+        self.input.drain(..Self::HOP);
+    }
+
+    /// 学習期間は平均し、その後はノイズ相当の小さなフレームでのみゆっくり更新する
+    fn update_noise(&mut self, magnitudes: &[f32]) {
+        if self.noise_frames < Self::INITIAL_NOISE_FRAMES {
+            self.noise_frames += 1;
+            let weight = 1.0 / self.noise_frames as f32;
+            for (n, &m) in self.noise.iter_mut().zip(magnitudes) {
+                *n += (m - *n) * weight;
+            }
+            return;
+        }
+
+        let frame_energy: f32 = magnitudes.iter().map(|m| m * m).sum();
+        let noise_energy: f32 = self.noise.iter().map(|n| n * n).sum();
+        if frame_energy < noise_energy * 2.0 {
+            for (n, &m) in self.noise.iter_mut().zip(magnitudes) {
+                *n += (m - *n) * 0.05;
+            }
+        }
+    }
+}
+
+impl AudioProcessor for NoiseSuppressor {
+    fn name(&self) -> &'static str {
+        "noise_suppression"
+    }
+
+    fn process(&mut self, samples: &mut [f32]) {
+        self.input.extend_from_slice(samples);
+        while self.input.len() >= Self::FRAME_LEN {
+            self.process_frame();
+        }
+
+        for sample in samples.iter_mut() {
+            *sample = self.output.pop_front().unwrap_or(0.0);
+        }
+    }
+
+    fn reset(&mut self) {
+        self.noise.iter_mut().for_each(|n| *n = 0.0);
+        self.noise_frames = 0;
+        self.input.clear();
+        self.overlap.iter_mut().for_each(|s| *s = 0.0);
+        self.output = VecDeque::from(vec![0.0; Self::latency_samples()]);
+    }
+
+    fn latency(&self) -> usize {
+        Self::latency_samples()
+    }
+}
+
+fn db_to_linear(db: f32) -> f32 {
+    10f32.powf(db / 20.0)
+}
+
+/// 時定数（秒）に対応する1次平滑化係数
+fn time_constant(seconds: f32, sample_rate: u32) -> f32 {
+    (-1.0 / (seconds * sample_rate as f32)).exp()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const RATE: u32 = 16000;
+
+    fn sine(freq: f32, amplitude: f32, seconds: f32) -> Vec<f32> {
+        (0..(RATE as f32 * seconds) as usize)
+            .map(|i| amplitude * (2.0 * PI * freq * i as f32 / RATE as f32).sin())
+            .collect()
+    }
+
+    /// 再現性のある白色ノイズ（線形合同法）
+    fn noise(amplitude: f32, seconds: f32) -> Vec<f32> {
+        let mut state: u32 = 12345;
+        (0..(RATE as f32 * seconds) as usize)
+            .map(|_| {
+                state = state.wrapping_mul(1_103_515_245).wrapping_add(12345);
+                amplitude * ((state >> 8) as f32 / (1u32 << 24) as f32 * 2.0 - 1.0)
+            })
+            .collect()
+    }
+
+    fn rms(samples: &[f32]) -> f32 {
+        (samples.iter().map(|s| s * s).sum::<f32>() / samples.len() as f32).sqrt()
+    }
+
+    /// 後半（過渡応答を除いた部分）のRMS
+    fn settled_rms(samples: &[f32]) -> f32 {
+        rms(&samples[samples.len() / 2..])
+    }
+
+    #[test]
+    fn test_dc_removal() {
+        let mut signal: Vec<f32> = sine(440.0, 0.1, 2.0).iter().map(|s| s + 0.3).collect();
+        DcRemover::new().process(&mut signal);
+
+        let tail = &signal[signal.len() / 2..];
+        let mean = tail.iter().sum::<f32>() / tail.len() as f32;
+        assert!(mean.abs() < 1e-3, "mean {mean}");
+    }
+
+    #[test]
+    fn test_high_pass_attenuates_rumble_and_keeps_voice() {
+        let mut rumble = sine(20.0, 0.5, 2.0);
+        let mut voice = sine(1000.0, 0.5, 2.0);
+        HighPassFilter::new(80.0, RATE).process(&mut rumble);
+        HighPassFilter::new(80.0, RATE).process(&mut voice);
+
+        assert!(settled_rms(&rumble) < 0.5 / 2f32.sqrt() * 0.1);
+        assert!((settled_rms(&voice) - 0.5 / 2f32.sqrt()).abs() < 0.01);
+    }
+
+    #[test]
+    fn test_agc_raises_quiet_input_to_target() {
+        let mut signal = sine(440.0, 0.01, 6.0);
+        let mut agc = AutomaticGainControl::new(-20.0, 30.0, RATE);
+        agc.process(&mut signal);
+
+        let level_db = 20.0 * settled_rms(&signal[signal.len() / 2..]).log10();
+        assert!((level_db + 20.0).abs() < 2.0, "level {level_db}dBFS");
+    }
+
+    #[test]
+    fn test_agc_does_not_boost_silence() {
+        let mut silence = vec![0.0; RATE as usize];
+        let mut agc = AutomaticGainControl::new(-20.0, 30.0, RATE);
+        agc.process(&mut silence);
+        assert_eq!(agc.gain(), 1.0);
+    }
+
+    #[test]
+    fn test_limiter_caps_peaks() {
+        let mut signal = sine(440.0, 2.0, 1.0);
+        PeakLimiter::new(0.9, RATE).process(&mut signal);
+        assert!(signal.iter().all(|s| s.abs() <= 0.9 + 1e-6));
+    }
+
+    #[test]
+    fn test_noise_suppression_reduces_stationary_noise() {
+        let background = noise(0.05, 4.0);
+        let tone = sine(1000.0, 0.3, 4.0);
+        // 前半2秒はノイズのみ、後半2秒は音声（トーン）+ノイズ
+        let half = background.len() / 2;
+        let input: Vec<f32> = background
+            .iter()
+            .enumerate()
+            .map(|(i, &n)| if i < half { n } else { n + tone[i] })
+            .collect();
+
+        let mut output = input.clone();
+        let mut suppressor = NoiseSuppressor::new(1.5);
+        // 不揃いなブロックで処理しても問題ないこと
+        for block in output.chunks_mut(700) {
+            suppressor.process(block);
+        }
+
+        let latency = NoiseSuppressor::latency_samples();
+        let noise_in = rms(&input[RATE as usize..half]);
+        let noise_out = rms(&output[RATE as usize + latency..half + latency]);
+        assert!(20.0 * (noise_out / noise_in).log10() < -10.0);
+
+        let speech_in = rms(&tone[half + RATE as usize / 2..]);
+        let speech_out = rms(&output[half + RATE as usize / 2 + latency..]);
+        assert!((20.0 * (speech_out / speech_in).log10()).abs() < 1.5);
+    }
+
+    #[test]
+    fn test_bypassed_pipeline_is_transparent() {
+        let config = PipelineConfig {
+            dc_removal: false,
+            high_pass: false,
+            agc: false,
+            noise_suppression: false,
+            limiter: false,
+            ..PipelineConfig::default()
+        };
+        let mut pipeline = ProcessingPipeline::from_config(&config, RATE);
+        let original = sine(440.0, 0.5, 0.5);
+        let mut signal = original.clone();
+        pipeline.process(&mut signal);
+
+        assert!(pipeline.enabled_stages().is_empty());
+        assert_eq!(signal, original);
+    }
+
+    #[test]
+    fn test_aligned_output_compensates_latency() {
+        let config = PipelineConfig {
+            noise_suppression: true,
+            ..PipelineConfig::default()
+        };
+        let mut pipeline = ProcessingPipeline::from_config(&config, RATE);
+        assert_eq!(pipeline.latency(), NoiseSuppressor::latency_samples());
+
+        // 無音の後のトーンの立ち上がりが、入力と同じ位置に現れること
+        let onset = RATE as usize;
+        let mut input = vec![0.0; onset];
+        input.extend(sine(1000.0, 0.5, 1.0));
+        let mut output = Vec::new();
+        for block in input.chunks(700) {
+            output.extend(pipeline.process_aligned(block.to_vec()));
+        }
+        output.extend(pipeline.flush());
+
+        assert_eq!(output.len(), input.len());
+        let first = output.iter().position(|s| s.abs() > 0.05).unwrap();
+        assert!(first.abs_diff(onset) < 16, "onset at {first}");
+    }
+
+    #[test]
+    fn test_default_config_is_bypassed() {
+        let pipeline = ProcessingPipeline::from_config(&PipelineConfig::default(), RATE);
+        assert!(pipeline.enabled_stages().is_empty());
+    }
+
+    #[test]
+    fn test_pipeline_stage_toggle() {
+        let config = PipelineConfig {
+            agc: true,
+            ..PipelineConfig::default()
+        };
+        let mut pipeline = ProcessingPipeline::from_config(&config, RATE);
+        assert!(pipeline.enabled_stages().contains(&"agc"));
+
+        assert!(pipeline.set_enabled("agc", false));
+        assert!(!pipeline.enabled_stages().contains(&"agc"));
+        assert!(!pipeline.set_enabled("unknown", true));
+    }
+}
@@ -1,7 +1,8 @@
 /// 音声リサンプリング機能
 /// 
 /// WhisperモデルはサンプルレートとしてWhisperモデルは16kHzを期待するため、
-/// cpalから取得した48kHzの音声を16kHzにダウンサンプリングする
+/// cpalから取得した48kHz等の音声を16kHzにダウンサンプリングする。
+/// 折り返し雑音を防ぐため、Whisper向けには窓付きsincリサンプラーを使用する
 
 /// シンプルなリサンプリング（線形補間）
 /// 
@@ -35,10 +36,309 @@ pub fn resample_linear(input: &[f32], input_rate: u32, output_rate: u32) -> Vec<
     output
 }
 
-/// Whisper用に48kHz → 16kHzへリサンプリング
+/// Whisperモデルの入力サンプルレート（Hz）
+pub const WHISPER_SAMPLE_RATE: u32 = 16000;
+
+/// 窓付きsincフィルタの片側ゼロ交差数（大きいほど遷移帯域が狭くなる）
+const SINC_ZERO_CROSSINGS: usize = 32;
+
+/// Kaiser窓のβ（阻止域減衰量を決める。8.6で約-90dB）
+const KAISER_BETA: f64 = 8.6;
+
+/// カットオフ周波数を出力ナイキストに対してどれだけ手前に置くか
+const SINC_ROLLOFF: f64 = 0.9;
+
+/// 窓付きsinc（ポリフェーズ）による帯域制限リサンプラー
+///
+/// 入出力レート比を既約分数 `up / down` に分解し、位相ごとのフィルタ係数を
+/// 事前計算する。ダウンサンプリング時は出力ナイキスト以下にカットオフを置くため、
+/// 48kHz → 16kHz で8kHz以上の成分が音声帯域に折り返さない。
+/// 44.1kHz / 22.05kHz / 96kHz など任意の整数レート比に対応する。
+#[derive(Debug, Clone)]
+pub struct SincResampler {
+    input_rate: u32,
+    output_rate: u32,
+    up: usize,
+    down: usize,
+    /// フィルタの片側長（入力サンプル単位）
+    half_len: usize,
+    /// 位相ごとの係数。`phases[p][k]` は入力オフセット `k - half_len + 1` に対応
+    phases: Vec<Vec<f32>>,
+}
+
+impl SincResampler {
+    /// 新しいリサンプラーを作成
+    ///
+    /// # Panics
+    /// サンプルレートに0が指定された場合
+    pub fn new(input_rate: u32, output_rate: u32) -> Self {
+        assert!(input_rate > 0 && output_rate > 0, "sample rate must be non-zero");
+
+        let g = gcd(input_rate as u64, output_rate as u64);
+        let up = (output_rate as u64 / g) as usize;
+        let down = (input_rate as u64 / g) as usize;
+
+        // 入力ナイキストを1としたカットオフ（ダウンサンプリング時は出力ナイキストに合わせる）
+        let cutoff = (up as f64 / down as f64).min(1.0) * SINC_ROLLOFF;
+        let half_len = (SINC_ZERO_CROSSINGS as f64 / cutoff).ceil() as usize;
+
+        let phases = (0..up)
+            .map(|p| {
+                let frac = p as f64 / up as f64;
+                let mut taps: Vec<f64> = (0..2 * half_len)
+                    .map(|k| {
+                        let t = (k as f64 - half_len as f64 + 1.0) - frac;
+                        cutoff * sinc(cutoff * t) * kaiser(t / half_len as f64, KAISER_BETA)
+                    })
+                    .collect();
+
+                // 位相ごとにDCゲインを1に正規化
+                let sum: f64 = taps.iter().sum();
+                if sum.abs() > f64::EPSILON {
+                    taps.iter_mut().for_each(|c| *c /= sum);
+                }
+                taps.into_iter().map(|c| c as f32).collect()
+            })
+            .collect();
+
+        SincResampler {
+            input_rate,
+            output_rate,
+            up,
+            down,
+            half_len,
+            phases,
+        }
+    }
+
+    /// 入力サンプルレート（Hz）
+    pub fn input_rate(&self) -> u32 {
+        self.input_rate
+    }
+
+    /// 出力サンプルレート（Hz）
+    pub fn output_rate(&self) -> u32 {
+        self.output_rate
+    }
+
+    /// 入力長に対する出力サンプル数
+    pub fn output_len(&self, input_len: usize) -> usize {
+        (input_len * self.up).div_ceil(self.down)
+    }
+
+    /// 入力全体をリサンプリング
+    ///
+    /// 端点の外側は最初/最後のサンプルを延長したものとして扱う
+    pub fn process(&self, input: &[f32]) -> Vec<f32> {
+        if input.is_empty() {
+            return Vec::new();
+        }
+        if self.up == self.down {
+            return input.to_vec();
+        }
+
+        let last = input.len() as i64 - 1;
+        (0..self.output_len(input.len()) as u64)
+            .map(|n| self.output_sample(n, |i| input[i.clamp(0, last) as usize]))
+            .collect()
+    }
+
+    /// 出力サンプル `n` が参照する入力区間 `[first, first + 2 * half_len)` の先頭位置
+    fn window_start(&self, n: u64) -> i64 {
+        (n * self.down as u64 / self.up as u64) as i64 - self.half_len as i64 + 1
+    }
+
+    /// 出力サンプル `n` を計算（`input(i)` は入力の絶対位置 `i` のサンプルを返す）
+    fn output_sample(&self, n: u64, input: impl Fn(i64) -> f32) -> f32 {
+        let pos = n * self.down as u64;
+        let taps = &self.phases[(pos % self.up as u64) as usize];
+        let first = self.window_start(n);
+
+        taps.iter()
+            .enumerate()
+            .map(|(k, &c)| c * input(first + k as i64))
+            .sum()
+    }
+}
+
+/// チャンク境界をまたいで状態を保持するストリーミングリサンプラー
+///
+/// キャプチャした音声を到着順に `process` へ渡すと、フィルタ履歴を保持したまま
+/// 逐次的に変換する。チャンクごとに変換し直す場合と違い境界で不連続が生じず、
+/// 全チャンクの出力を連結すると入力全体を一括変換した結果と一致する。
+/// 出力はフィルタ長の半分だけ遅れて確定し、残りは `flush` で取り出す。
+#[derive(Debug, Clone)]
+pub struct StreamingResampler {
+    resampler: SincResampler,
+    /// 未消費の入力履歴（先頭は絶対位置 `history_start`）
+    history: Vec<f32>,
+    history_start: u64,
+    /// これまでに受け取った入力サンプル数
+    input_count: u64,
+    /// これまでに出力したサンプル数
+    output_count: u64,
+    /// 入力開始前の区間として延長する先頭サンプル
+    first_sample: Option<f32>,
+}
+
+impl StreamingResampler {
+    pub fn new(input_rate: u32, output_rate: u32) -> Self {
+        StreamingResampler {
+            resampler: SincResampler::new(input_rate, output_rate),
+            history: Vec::new(),
+            history_start: 0,
+            input_count: 0,
+            output_count: 0,
+            first_sample: None,
+        }
+    }
+
+    /// 入力サンプルレート（Hz）
+    pub fn input_rate(&self) -> u32 {
+        self.resampler.input_rate()
+    }
+
+    /// 出力サンプルレート（Hz）
+    pub fn output_rate(&self) -> u32 {
+        self.resampler.output_rate()
+    }
+
+    /// これまでに出力したサンプル数
+    pub fn output_count(&self) -> u64 {
+        self.output_count
+    }
+
+    /// 入力を追加し、確定した出力サンプルを返す
+    pub fn process(&mut self, input: &[f32]) -> Vec<f32> {
+        if input.is_empty() {
+            return Vec::new();
+        }
+        if self.resampler.up == self.resampler.down {
+            self.input_count += input.len() as u64;
+            self.output_count += input.len() as u64;
+            return input.to_vec();
+        }
+        if self.first_sample.is_none() {
+            self.first_sample = input.first().copied();
+        }
+
+        self.history.extend_from_slice(input);
+        self.input_count += input.len() as u64;
+
+        // 窓の末尾が受信済みの入力に収まる出力だけを確定させる
+        let half_len = self.resampler.half_len as i64;
+        let mut output = Vec::new();
+        while self.resampler.window_start(self.output_count) + 2 * half_len <= self.input_count as i64 {
+            output.push(self.sample_at(self.output_count, None));
+            self.output_count += 1;
+        }
+
+        self.trim_history();
+        output
+    }
+
+    /// 残りの入力を末尾サンプルの延長で補って出力し、状態をリセットする
+    pub fn flush(&mut self) -> Vec<f32> {
+        let total = self.resampler.output_len(self.input_count as usize) as u64;
+        let last = self.history.last().copied();
+        let output = (self.output_count..total)
+            .map(|n| self.sample_at(n, last))
+            .collect();
+
+        self.reset();
+        output
+    }
+
+    /// 履歴を破棄して初期状態に戻す
+    pub fn reset(&mut self) {
+        self.history.clear();
+        self.history_start = 0;
+        self.input_count = 0;
+        self.output_count = 0;
+        self.first_sample = None;
+    }
+
+    fn sample_at(&self, n: u64, tail: Option<f32>) -> f32 {
+        let start = self.history_start as i64;
+        let end = self.input_count as i64;
+        let head = self.first_sample.unwrap_or(0.0);
+
+        self.resampler.output_sample(n, |i| {
+            if i < 0 {
+                head
+            } else if i >= end {
+                tail.unwrap_or(0.0)
+            } else {
+                self.history[(i - start) as usize]
+            }
+        })
+    }
+
+    /// 次の出力で参照されない入力を履歴から取り除く
+    fn trim_history(&mut self) {
+        let needed = self.resampler.window_start(self.output_count).max(0) as u64;
+        if needed > self.history_start {
+            let drop = ((needed - self.history_start) as usize).min(self.history.len());
+            self.history.drain(..drop);
+            self.history_start += drop as u64;
+        }
+    }
+}
+
+/// 窓付きsincによる帯域制限リサンプリング
+///
+/// 単発の変換用。同じレート比で繰り返し変換する場合は [`SincResampler`] を使い回す
+pub fn resample_sinc(input: &[f32], input_rate: u32, output_rate: u32) -> Vec<f32> {
+    if input_rate == output_rate {
+        return input.to_vec();
+    }
+    SincResampler::new(input_rate, output_rate).process(input)
+}
+
+/// Whisper用に16kHzへリサンプリング（アンチエイリアスフィルタ付き）
 pub fn resample_for_whisper(input: &[f32], input_rate: u32) -> Vec<f32> {
-    const WHISPER_SAMPLE_RATE: u32 = 16000;
-    resample_linear(input, input_rate, WHISPER_SAMPLE_RATE)
+    resample_sinc(input, input_rate, WHISPER_SAMPLE_RATE)
+}
+
+fn gcd(mut a: u64, mut b: u64) -> u64 {
+    while b != 0 {
+        let r = a % b;
+        a = b;
+        b = r;
+    }
+    a
+}
+
+fn sinc(x: f64) -> f64 {
+    if x.abs() < 1e-12 {
+        1.0
+    } else {
+        let px = std::f64::consts::PI * x;
+        px.sin() / px
+    }
+}
+
+/// Kaiser窓（`x` は -1.0〜1.0 に正規化した位置）
+fn kaiser(x: f64, beta: f64) -> f64 {
+    if x.abs() >= 1.0 {
+        return 0.0;
+    }
+    bessel_i0(beta * (1.0 - x * x).sqrt()) / bessel_i0(beta)
+}
+
+/// 第1種変形ベッセル関数 I0（級数展開）
+fn bessel_i0(x: f64) -> f64 {
+    let half = x / 2.0;
+    let mut term = 1.0;
+    let mut sum = 1.0;
+    for k in 1..50 {
+        term *= (half / k as f64) * (half / k as f64);
+        sum += term;
+        if term < sum * 1e-12 {
+            break;
+        }
+    }
+    sum
 }
 
 #[cfg(test)]
@@ -81,4 +381,123 @@ mod tests {
             assert!((sample - 0.5).abs() < 0.1);
         }
     }
+
+    fn sine(freq: f32, rate: u32, seconds: f32) -> Vec<f32> {
+        let len = (rate as f32 * seconds) as usize;
+        (0..len)
+            .map(|i| (2.0 * std::f64::consts::PI * freq as f64 * i as f64 / rate as f64).sin() as f32)
+            .collect()
+    }
+
+    /// フィルタの立ち上がりを避けるため中央部分のRMSを計算
+    fn rms_center(samples: &[f32]) -> f32 {
+        let skip = samples.len() / 4;
+        let center = &samples[skip..samples.len() - skip];
+        (center.iter().map(|s| s * s).sum::<f32>() / center.len() as f32).sqrt()
+    }
+
+    fn gain_db(input: &[f32], output: &[f32]) -> f32 {
+        20.0 * (rms_center(output) / rms_center(input)).log10()
+    }
+
+    #[test]
+    fn test_sinc_output_length() {
+        let resampler = SincResampler::new(44100, 16000);
+        assert_eq!(resampler.output_len(44100), 16000);
+        assert_eq!(resample_sinc(&vec![0.0; 44100], 44100, 16000).len(), 16000);
+        assert_eq!(resample_sinc(&vec![0.0; 96000], 96000, 16000).len(), 16000);
+        assert_eq!(resample_sinc(&vec![0.0; 22050], 22050, 16000).len(), 16000);
+    }
+
+    #[test]
+    fn test_sinc_passband_is_flat() {
+        for &rate in &[48000u32, 44100, 22050, 96000] {
+            let input = sine(1000.0, rate, 1.0);
+            let output = resample_sinc(&input, rate, 16000);
+            let gain = gain_db(&input, &output);
+            assert!(gain.abs() < 0.1, "{rate}Hz: passband gain {gain}dB");
+        }
+    }
+
+    #[test]
+    fn test_sinc_stopband_attenuation() {
+        // 出力ナイキスト(8kHz)を超える成分は折り返さずに除去されること
+        let cases = [(48000u32, 12000.0f32), (44100, 10000.0), (96000, 20000.0), (22050, 10500.0)];
+        for &(rate, freq) in &cases {
+            let input = sine(freq, rate, 1.0);
+            let output = resample_sinc(&input, rate, 16000);
+            let gain = gain_db(&input, &output);
+            assert!(gain < -80.0, "{rate}Hz/{freq}Hz: stopband gain {gain}dB");
+        }
+    }
+
+    #[test]
+    fn test_linear_aliases_where_sinc_does_not() {
+        let input = sine(12000.0, 48000, 1.0);
+        let linear = resample_linear(&input, 48000, 16000);
+        let sinc = resample_sinc(&input, 48000, 16000);
+        assert!(gain_db(&input, &linear) > -20.0);
+        assert!(gain_db(&input, &sinc) < -80.0);
+    }
+
+    #[test]
+    fn test_sinc_upsample_preserves_tone() {
+        let input = sine(1000.0, 8000, 1.0);
+        let output = resample_sinc(&input, 8000, 16000);
+        assert_eq!(output.len(), 16000);
+        assert!(gain_db(&input, &output).abs() < 0.1);
+    }
+
+    #[test]
+    fn test_sinc_preserves_dc() {
+        let input = vec![0.5; 44100];
+        let output = resample_sinc(&input, 44100, 16000);
+        for sample in output.iter() {
+            assert!((sample - 0.5).abs() < 1e-3);
+        }
+    }
+
+    #[test]
+    fn test_streaming_matches_one_shot() {
+        let input = sine(1000.0, 44100, 0.5);
+        let expected = resample_sinc(&input, 44100, 16000);
+
+        let mut resampler = StreamingResampler::new(44100, 16000);
+        let mut output = Vec::new();
+        // 不揃いなチャンクサイズで投入
+        for chunk in input.chunks(517) {
+            output.extend(resampler.process(chunk));
+        }
+        output.extend(resampler.flush());
+
+        assert_eq!(output.len(), expected.len());
+        for (a, b) in output.iter().zip(expected.iter()) {
+            assert!((a - b).abs() < 1e-5);
+        }
+    }
+
+    #[test]
+    fn test_streaming_keeps_history_bounded() {
+        let mut resampler = StreamingResampler::new(48000, 16000);
+        for _ in 0..100 {
+            resampler.process(&[0.1; 480]);
+        }
+        assert!(resampler.history.len() <= 2 * resampler.resampler.half_len + 3);
+        assert_eq!(resampler.output_count() + resampler.flush().len() as u64, 16000);
+    }
+
+    #[test]
+    fn test_streaming_no_discontinuity_at_chunk_edges() {
+        let input = vec![0.5; 48000];
+        let mut resampler = StreamingResampler::new(48000, 16000);
+        let mut output = Vec::new();
+        for chunk in input.chunks(480) {
+            output.extend(resampler.process(chunk));
+        }
+        output.extend(resampler.flush());
+
+        for sample in output.iter() {
+            assert!((sample - 0.5).abs() < 1e-3);
+        }
+    }
 }
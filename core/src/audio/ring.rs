@@ -0,0 +1,218 @@
+/// リアルタイムスレッド向けのロックフリー単一生産者・単一消費者リングバッファ
+///
+/// cpal のコールバックスレッドではロック・メモリ確保・ブロッキングを避ける必要があるため、
+/// 事前確保した領域にアトミック操作のみで書き込み、別スレッドで取り出す。
+/// 空きが足りない場合は書き込めなかったサンプル数をオーバーランとして数える
+use std::sync::atomic::{AtomicU32, AtomicU64, AtomicUsize, Ordering};
+use std::sync::Arc;
+
+struct Shared {
+    /// f32 をビット列として保持（unsafe を使わずに共有するため）
+    slots: Box<[AtomicU32]>,
+    /// 読み出し位置（消費者のみが更新）
+    head: AtomicUsize,
+    /// 書き込み位置（生産者のみが更新）
+    tail: AtomicUsize,
+    /// 空き不足で破棄したサンプル数
+    overruns: AtomicU64,
+}
+
+/// 書き込み側（リアルタイムスレッドで使用）
+pub struct RingProducer {
+    shared: Arc<Shared>,
+}
+
+/// 読み出し側
+pub struct RingConsumer {
+    shared: Arc<Shared>,
+}
+
+/// 指定した容量のリングバッファを作成
+pub fn ring_buffer(capacity: usize) -> (RingProducer, RingConsumer) {
+    // 満杯と空を区別するため1スロット余分に確保する
+    let slots = (0..capacity + 1).map(|_| AtomicU32::new(0)).collect();
+    let shared = Arc::new(Shared {
+        slots,
+        head: AtomicUsize::new(0),
+        tail: AtomicUsize::new(0),
+        overruns: AtomicU64::new(0),
+    });
+
+    (
+        RingProducer { shared: Arc::clone(&shared) },
+        RingConsumer { shared },
+    )
+}
+
+impl Shared {
+    fn len(&self, head: usize, tail: usize) -> usize {
+        (tail + self.slots.len() - head) % self.slots.len()
+    }
+
+    fn capacity(&self) -> usize {
+        self.slots.len() - 1
+    }
+}
+
+impl RingProducer {
+    /// サンプルを書き込む（ブロックしない）
+    ///
+    /// # Returns
+    /// 書き込んだサンプル数。空きが足りない分は破棄してオーバーランに加算する
+    pub fn push_iter(&mut self, samples: impl IntoIterator<Item = f32>) -> usize {
+        self.push_frames(samples, 1)
+    }
+
+    /// `frame_len` サンプル単位（インターリーブされた複数チャンネルなど）で書き込む
+    ///
+    /// 空きが足りない場合もフレームの途中では切らないため、読み出し側の
+    /// チャンネルの並びがずれない
+    pub fn push_frames(&mut self, samples: impl IntoIterator<Item = f32>, frame_len: usize) -> usize {
+        let shared = &*self.shared;
+        let head = shared.head.load(Ordering::Acquire);
+        let mut tail = shared.tail.load(Ordering::Relaxed);
+        let free = shared.capacity() - shared.len(head, tail);
+        let free = free - free % frame_len.max(1);
+
+        let mut written = 0;
+        let mut dropped = 0u64;
+        for sample in samples {
+            if written < free {
+                shared.slots[tail].store(sample.to_bits(), Ordering::Relaxed);
+                tail = (tail + 1) % shared.slots.len();
+                written += 1;
+            } else {
+                dropped += 1;
+            }
+        }
+
+        shared.tail.store(tail, Ordering::Release);
+        if dropped > 0 {
+            shared.overruns.fetch_add(dropped, Ordering::Relaxed);
+        }
+        written
+    }
+
+    /// これまでに破棄したサンプル数
+    pub fn overruns(&self) -> u64 {
+        self.shared.overruns.load(Ordering::Relaxed)
+    }
+
+    /// 書き込み可能なサンプル数
+    pub fn free_len(&self) -> usize {
+        let shared = &*self.shared;
+        let head = shared.head.load(Ordering::Acquire);
+        let tail = shared.tail.load(Ordering::Relaxed);
+        shared.capacity() - shared.len(head, tail)
+    }
+}
+
+impl RingConsumer {
+    /// 読み出し可能なサンプルを `out` に取り出す
+    ///
+    /// # Returns
+    /// 取り出したサンプル数
+    pub fn pop_slice(&mut self, out: &mut [f32]) -> usize {
+        let shared = &*self.shared;
+        let tail = shared.tail.load(Ordering::Acquire);
+        let mut head = shared.head.load(Ordering::Relaxed);
+        let count = shared.len(head, tail).min(out.len());
+
+        for slot in out.iter_mut().take(count) {
+            *slot = f32::from_bits(shared.slots[head].load(Ordering::Relaxed));
+            head = (head + 1) % shared.slots.len();
+        }
+
+        shared.head.store(head, Ordering::Release);
+        count
+    }
+
+    /// 読み出し可能なサンプル数
+    pub fn len(&self) -> usize {
+        let shared = &*self.shared;
+        shared.len(shared.head.load(Ordering::Acquire), shared.tail.load(Ordering::Acquire))
+    }
+
+    /// 読み出し可能なサンプルがないかどうか
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// これまでに破棄したサンプル数
+    pub fn overruns(&self) -> u64 {
+        self.shared.overruns.load(Ordering::Relaxed)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_push_and_pop_wraps_around() {
+        let (mut producer, mut consumer) = ring_buffer(4);
+        let mut out = [0.0; 4];
+
+        for round in 0..10 {
+            let base = round as f32 * 3.0;
+            assert_eq!(producer.push_iter([base, base + 1.0, base + 2.0]), 3);
+            assert_eq!(consumer.pop_slice(&mut out), 3);
+            assert_eq!(&out[..3], &[base, base + 1.0, base + 2.0]);
+        }
+        assert!(consumer.is_empty());
+    }
+
+    #[test]
+    fn test_overrun_is_counted() {
+        let (mut producer, mut consumer) = ring_buffer(4);
+
+        assert_eq!(producer.push_iter([1.0; 6]), 4);
+        assert_eq!(producer.overruns(), 2);
+        assert_eq!(consumer.len(), 4);
+
+        let mut out = [0.0; 8];
+        assert_eq!(consumer.pop_slice(&mut out), 4);
+        assert_eq!(consumer.overruns(), 2);
+    }
+
+    #[test]
+    fn test_concurrent_transfer_preserves_order() {
+        let (mut producer, mut consumer) = ring_buffer(1024);
+        const TOTAL: usize = 200_000;
+
+        let writer = std::thread::spawn(move || {
+            let mut next = 0;
+            while next < TOTAL {
+                let end = (next + 128).min(TOTAL);
+                let written = producer.push_iter((next..end).map(|i| i as f32));
+                // テストでは取りこぼさないよう、書けた分だけ進める
+                next += written;
+                if written == 0 {
+                    std::thread::yield_now();
+                }
+            }
+        });
+
+        let mut expected = 0;
+        let mut out = vec![0.0; 256];
+        while expected < TOTAL {
+            let count = consumer.pop_slice(&mut out);
+            for &sample in &out[..count] {
+                assert_eq!(sample, expected as f32);
+                expected += 1;
+            }
+        }
+        writer.join().unwrap();
+    }
+
+    #[test]
+    fn test_push_frames_keeps_frames_whole() {
+        let (mut producer, mut consumer) = ring_buffer(5);
+        assert_eq!(producer.push_frames([1.0, 2.0, 1.0, 2.0, 1.0, 2.0], 2), 4);
+        assert_eq!(producer.overruns(), 2);
+
+        let mut out = [0.0; 6];
+        assert_eq!(consumer.pop_slice(&mut out), 4);
+        assert_eq!(&out[..4], &[1.0, 2.0, 1.0, 2.0]);
+    }
+}
@@ -0,0 +1,726 @@
+/// 音声入力ソース
+///
+/// `AudioCapture` はソースからリングバッファへ書き込まれた音声を配るだけにし、
+/// 入力元（cpalのデバイス、WAVファイルの再生、標準入力/名前付きパイプのPCM、
+/// テスト用の正弦波）を差し替えられるようにする。
+/// デバイスのないサーバーやテストでも録音パイプライン全体を動かせる
+use serde::{Deserialize, Serialize};
+use std::f32::consts::PI;
+use std::io::Read;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::thread::JoinHandle;
+use std::time::{Duration, Instant};
+
+use super::capture::{AudioCaptureError, CpalSource, Result};
+use super::RingProducer;
+
+/// 録音停止中・空き待ちのときに確認する間隔
+const FEED_POLL_INTERVAL: Duration = Duration::from_millis(5);
+
+/// ソースが出力する音声の形式
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SourceFormat {
+    pub sample_rate: u32,
+    pub channels: usize,
+}
+
+/// 音声入力ソースの共通インターフェース
+///
+/// cpalのストリームはスレッドをまたげないため `Send` は要求しない
+pub trait AudioSource {
+    /// 表示用の名前（デバイス名やファイル名）
+    fn name(&self) -> Option<String>;
+
+    /// 出力する音声の形式
+    fn format(&self) -> Result<SourceFormat>;
+
+    /// 取り込みを開始し、インターリーブされたサンプルを `sink` へ書き込む
+    fn start(&mut self, sink: SourceSink) -> Result<()>;
+
+    /// 取り込みを停止（再度 `start` で続きから再開できる）
+    fn stop(&mut self) -> Result<()>;
+
+    /// 入力の喪失などのエラーを取り出す
+    fn take_error(&mut self) -> Option<String> {
+        None
+    }
+
+    /// 有限の入力（ファイルなど）を最後まで読み終えたか
+    fn is_finished(&self) -> bool {
+        false
+    }
+
+    /// 失われた入力を開き直す
+    fn reopen(&mut self) -> Result<()> {
+        Err(AudioCaptureError::SourceError(
+            "This audio source cannot be reopened".to_string(),
+        ))
+    }
+}
+
+/// ソースの書き込み先（リングバッファの生産者側）
+///
+/// `AudioCapture` がチャンネルを個別に扱う場合はフレームのまま、
+/// そうでなければモノラルに平均して書き込む
+pub struct SourceSink {
+    producer: RingProducer,
+    channels: usize,
+    interleaved: bool,
+}
+
+impl SourceSink {
+    pub(crate) fn new(producer: RingProducer, channels: usize, interleaved: bool) -> Self {
+        SourceSink {
+            producer,
+            channels: channels.max(1),
+            interleaved,
+        }
+    }
+
+    /// 1フレームのチャンネル数
+    pub fn channels(&self) -> usize {
+        self.channels
+    }
+
+    /// インターリーブされたサンプルを書き込む（メモリ確保・ロックなし）
+    ///
+    /// # Returns
+    /// 書き込んだフレーム数。空きが足りない分は破棄してオーバーランに加算する
+    pub fn write(&mut self, samples: impl IntoIterator<Item = f32>) -> usize {
+        let channels = self.channels;
+        if self.interleaved {
+            return self.producer.push_frames(samples, channels) / channels;
+        }
+
+        let mut samples = samples.into_iter();
+        let mono = std::iter::from_fn(move || {
+            let mut sum = 0.0;
+            for _ in 0..channels {
+                sum += samples.next()?;
+            }
+            Some(sum / channels as f32)
+        });
+        self.producer.push_iter(mono)
+    }
+
+    /// 書き込み可能なフレーム数
+    pub fn free_frames(&self) -> usize {
+        let free = self.producer.free_len();
+        if self.interleaved {
+            free / self.channels
+        } else {
+            free
+        }
+    }
+}
+
+/// ファイル等から読み出す速さ
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Pacing {
+    /// 実時間と同じ速さで供給する（録音のシミュレーション）
+    RealTime,
+    /// 取り出しが追いつく限り速く供給する（テスト・一括処理）
+    AsFastAsPossible,
+}
+
+/// インターリーブされたサンプルを読み出す関数（0を返すと終端）
+type BlockReader = Box<dyn FnMut(&mut [f32]) -> std::io::Result<usize> + Send>;
+
+/// 供給スレッドと共有する状態
+#[derive(Default)]
+struct FeedShared {
+    /// 録音中の書き込み先（停止中はNone）
+    sink: Mutex<Option<SourceSink>>,
+    closed: AtomicBool,
+    finished: AtomicBool,
+    error: Mutex<Option<String>>,
+}
+
+/// 読み出し関数から一定の速さでサンプルを供給するスレッド
+///
+/// スレッドは最初の `start` で起動し、ソースが破棄されるか終端に達するまで動き続ける。
+/// 停止中は書き込み先を外すだけなので、パイプの読み出しでブロックしていても停止できる。
+/// スレッドは破棄時に終了を待つ（ライブ入力は読み出しを中断できないため待たずに切り離す）
+struct Feeder {
+    shared: Arc<FeedShared>,
+    reader: Option<BlockReader>,
+    handle: Option<JoinHandle<()>>,
+    format: SourceFormat,
+    pacing: Pacing,
+    /// 停止中も読み出して捨てるか（外部から流れ込むライブ入力向け）
+    live: bool,
+}
+
+impl Feeder {
+    fn new(reader: BlockReader, format: SourceFormat, pacing: Pacing, live: bool) -> Self {
+        Feeder {
+            shared: Arc::new(FeedShared::default()),
+            reader: Some(reader),
+            handle: None,
+            format,
+            pacing,
+            live,
+        }
+    }
+
+    fn start(&mut self, sink: SourceSink) {
+        *self.shared.sink.lock().unwrap() = Some(sink);
+
+        if let Some(reader) = self.reader.take() {
+            let shared = Arc::clone(&self.shared);
+            let (format, pacing, live) = (self.format, self.pacing, self.live);
+            self.handle = Some(std::thread::spawn(move || {
+                feed(shared, reader, format, pacing, live)
+            }));
+        }
+    }
+
+    fn stop(&mut self) {
+        // ロックを取ることで、戻った時点以降は書き込まれないことを保証する
+        self.shared.sink.lock().unwrap().take();
+    }
+
+    fn take_error(&mut self) -> Option<String> {
+        self.shared.error.lock().unwrap().take()
+    }
+
+    fn is_finished(&self) -> bool {
+        self.shared.finished.load(Ordering::Acquire)
+    }
+}
+
+impl Drop for Feeder {
+    fn drop(&mut self) {
+        self.shared.closed.store(true, Ordering::Release);
+        self.shared.sink.lock().unwrap().take();
+
+        let Some(handle) = self.handle.take() else { return };
+        // パイプや標準入力の読み出しでブロックしているスレッドは中断できないため切り離す。
+        // 書き込み先は外してあるので、以降は何も書き込まずに次の読み出しが戻った時点で終了する
+        if self.live && !handle.is_finished() {
+            return;
+        }
+        if handle.join().is_err() {
+            eprintln!("[Audio] feeder thread panicked");
+        }
+    }
+}
+
+fn feed(
+    shared: Arc<FeedShared>,
+    mut reader: BlockReader,
+    format: SourceFormat,
+    pacing: Pacing,
+    live: bool,
+) {
+    let channels = format.channels.max(1);
+    // 10ms単位で供給する
+    let block_frames = (format.sample_rate as usize / 100).max(1);
+    let mut block = vec![0.0; block_frames * channels];
+    let mut clock: Option<(Instant, u64)> = None;
+
+    while !shared.closed.load(Ordering::Acquire) {
+        let free = shared
+            .sink
+            .lock()
+            .unwrap()
+            .as_ref()
+            .map(|sink| sink.free_frames());
+        let recording = free.is_some();
+
+        if !recording {
+            clock = None;
+            if !live {
+                std::thread::sleep(FEED_POLL_INTERVAL);
+                continue;
+            }
+        } else {
+            match pacing {
+                Pacing::RealTime => {
+                    let (started, sent) = *clock.get_or_insert((Instant::now(), 0));
+                    let due =
+                        started + Duration::from_secs_f64(sent as f64 / format.sample_rate as f64);
+                    let now = Instant::now();
+                    if due > now {
+                        std::thread::sleep(due - now);
+                    }
+                }
+                Pacing::AsFastAsPossible => {
+                    if free.unwrap_or(0) < block_frames {
+                        std::thread::sleep(FEED_POLL_INTERVAL);
+                        continue;
+                    }
+                }
+            }
+        }
+
+        let count = match reader(&mut block) {
+            Ok(0) => break,
+            Ok(count) => count,
+            Err(e) => {
+                *shared.error.lock().unwrap() = Some(e.to_string());
+                break;
+            }
+        };
+
+        // 端数のフレームは切り捨てる
+        let frames = count / channels;
+        if let Some(sink) = shared.sink.lock().unwrap().as_mut() {
+            sink.write(block[..frames * channels].iter().copied());
+        }
+        if let Some((_, sent)) = clock.as_mut() {
+            *sent += frames as u64;
+        }
+    }
+
+    shared.finished.store(true, Ordering::Release);
+}
+
+/// WAVファイルを再生するソース
+pub struct WavFileSource {
+    path: PathBuf,
+    feeder: Feeder,
+}
+
+impl WavFileSource {
+    pub fn open(path: impl AsRef<Path>, pacing: Pacing) -> Result<Self> {
+        let path = path.as_ref().to_path_buf();
+        let reader = hound::WavReader::open(&path)
+            .map_err(|e| AudioCaptureError::SourceError(format!("{}: {}", path.display(), e)))?;
+        let spec = reader.spec();
+        let format = SourceFormat {
+            sample_rate: spec.sample_rate,
+            channels: spec.channels as usize,
+        };
+
+        let mut samples: Box<dyn Iterator<Item = hound::Result<f32>> + Send> =
+            match spec.sample_format {
+                hound::SampleFormat::Float => Box::new(reader.into_samples::<f32>()),
+                hound::SampleFormat::Int => {
+                    let scale = 1.0 / (1i64 << (spec.bits_per_sample - 1)) as f32;
+                    Box::new(
+                        reader
+                            .into_samples::<i32>()
+                            .map(move |s| s.map(|v| v as f32 * scale)),
+                    )
+                }
+            };
+
+        let block_reader: BlockReader = Box::new(move |block: &mut [f32]| {
+            let mut count = 0;
+            for slot in block.iter_mut() {
+                match samples.next() {
+                    Some(Ok(sample)) => *slot = sample,
+                    Some(Err(e)) => return Err(std::io::Error::other(e)),
+                    None => break,
+                }
+                count += 1;
+            }
+            Ok(count)
+        });
+
+        Ok(WavFileSource {
+            path,
+            feeder: Feeder::new(block_reader, format, pacing, false),
+        })
+    }
+}
+
+impl AudioSource for WavFileSource {
+    fn name(&self) -> Option<String> {
+        self.path
+            .file_name()
+            .map(|n| n.to_string_lossy().to_string())
+    }
+
+    fn format(&self) -> Result<SourceFormat> {
+        Ok(self.feeder.format)
+    }
+
+    fn start(&mut self, sink: SourceSink) -> Result<()> {
+        self.feeder.start(sink);
+        Ok(())
+    }
+
+    fn stop(&mut self) -> Result<()> {
+        self.feeder.stop();
+        Ok(())
+    }
+
+    fn take_error(&mut self) -> Option<String> {
+        self.feeder.take_error()
+    }
+
+    fn is_finished(&self) -> bool {
+        self.feeder.is_finished()
+    }
+}
+
+/// 生PCMのサンプル形式
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PcmEncoding {
+    /// 16bit符号付き整数（リトルエンディアン）
+    S16Le,
+    /// 32bit浮動小数点（リトルエンディアン）
+    F32Le,
+}
+
+impl PcmEncoding {
+    fn bytes_per_sample(self) -> usize {
+        match self {
+            PcmEncoding::S16Le => 2,
+            PcmEncoding::F32Le => 4,
+        }
+    }
+
+    fn decode(self, bytes: &[u8]) -> f32 {
+        match self {
+            PcmEncoding::S16Le => i16::from_le_bytes([bytes[0], bytes[1]]) as f32 / i16::MAX as f32,
+            PcmEncoding::F32Le => f32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]),
+        }
+    }
+}
+
+/// 標準入力・名前付きパイプ・任意のリーダーから生PCMを読み込むソース
+///
+/// 書き込み側が実時間で流すことを前提に、読めた分をそのまま供給する
+pub struct RawPcmSource {
+    name: String,
+    feeder: Feeder,
+}
+
+impl RawPcmSource {
+    /// 標準入力から読み込む
+    pub fn stdin(sample_rate: u32, channels: usize, encoding: PcmEncoding) -> Self {
+        Self::from_reader("stdin", std::io::stdin(), sample_rate, channels, encoding)
+    }
+
+    /// ファイルまたは名前付きパイプから読み込む（パイプは書き込み側が開くまで待つ）
+    pub fn open(
+        path: impl AsRef<Path>,
+        sample_rate: u32,
+        channels: usize,
+        encoding: PcmEncoding,
+    ) -> Result<Self> {
+        let path = path.as_ref();
+        let file = std::fs::File::open(path)
+            .map_err(|e| AudioCaptureError::SourceError(format!("{}: {}", path.display(), e)))?;
+        Ok(Self::from_reader(
+            &path.display().to_string(),
+            file,
+            sample_rate,
+            channels,
+            encoding,
+        ))
+    }
+
+    pub fn from_reader(
+        name: &str,
+        mut reader: impl Read + Send + 'static,
+        sample_rate: u32,
+        channels: usize,
+        encoding: PcmEncoding,
+    ) -> Self {
+        let bytes_per_sample = encoding.bytes_per_sample();
+        let mut bytes = Vec::new();
+
+        let block_reader: BlockReader = Box::new(move |block: &mut [f32]| {
+            // ブロックが埋まるか終端に達するまで読む（パイプは細切れに届くため）
+            bytes.resize(block.len() * bytes_per_sample, 0);
+            let mut filled = 0;
+            while filled < bytes.len() {
+                match reader.read(&mut bytes[filled..]) {
+                    Ok(0) => break,
+                    Ok(n) => filled += n,
+                    Err(e) if e.kind() == std::io::ErrorKind::Interrupted => continue,
+                    Err(e) => return Err(e),
+                }
+            }
+
+            let count = filled / bytes_per_sample;
+            for (slot, sample) in block
+                .iter_mut()
+                .zip(bytes[..count * bytes_per_sample].chunks(bytes_per_sample))
+            {
+                *slot = encoding.decode(sample);
+            }
+            Ok(count)
+        });
+
+        let format = SourceFormat {
+            sample_rate,
+            channels: channels.max(1),
+        };
+        RawPcmSource {
+            name: name.to_string(),
+            feeder: Feeder::new(block_reader, format, Pacing::AsFastAsPossible, true),
+        }
+    }
+}
+
+impl AudioSource for RawPcmSource {
+    fn name(&self) -> Option<String> {
+        Some(self.name.clone())
+    }
+
+    fn format(&self) -> Result<SourceFormat> {
+        Ok(self.feeder.format)
+    }
+
+    fn start(&mut self, sink: SourceSink) -> Result<()> {
+        self.feeder.start(sink);
+        Ok(())
+    }
+
+    fn stop(&mut self) -> Result<()> {
+        self.feeder.stop();
+        Ok(())
+    }
+
+    fn take_error(&mut self) -> Option<String> {
+        self.feeder.take_error()
+    }
+
+    fn is_finished(&self) -> bool {
+        self.feeder.is_finished()
+    }
+}
+
+/// 正弦波を生成するソース（動作確認・テスト用）
+pub struct ToneSource {
+    frequency: f32,
+    feeder: Feeder,
+}
+
+impl ToneSource {
+    /// `duration` を指定しない場合は停止するまで生成し続ける
+    pub fn new(
+        frequency: f32,
+        amplitude: f32,
+        sample_rate: u32,
+        duration: Option<Duration>,
+        pacing: Pacing,
+    ) -> Self {
+        let mut remaining = duration.map(|d| (d.as_secs_f64() * sample_rate as f64) as u64);
+        let mut index: u64 = 0;
+
+        let block_reader: BlockReader = Box::new(move |block: &mut [f32]| {
+            let count = match remaining {
+                Some(left) => (left as usize).min(block.len()),
+                None => block.len(),
+            };
+            for slot in block.iter_mut().take(count) {
+                let t = (index % sample_rate as u64) as f32 / sample_rate as f32;
+                *slot = amplitude * (2.0 * PI * frequency * t).sin();
+                index += 1;
+            }
+            if let Some(left) = remaining.as_mut() {
+                *left -= count as u64;
+            }
+            Ok(count)
+        });
+
+        let format = SourceFormat {
+            sample_rate,
+            channels: 1,
+        };
+        ToneSource {
+            frequency,
+            feeder: Feeder::new(block_reader, format, pacing, false),
+        }
+    }
+}
+
+impl AudioSource for ToneSource {
+    fn name(&self) -> Option<String> {
+        Some(format!("Tone {} Hz", self.frequency))
+    }
+
+    fn format(&self) -> Result<SourceFormat> {
+        Ok(self.feeder.format)
+    }
+
+    fn start(&mut self, sink: SourceSink) -> Result<()> {
+        self.feeder.start(sink);
+        Ok(())
+    }
+
+    fn stop(&mut self) -> Result<()> {
+        self.feeder.stop();
+        Ok(())
+    }
+
+    fn is_finished(&self) -> bool {
+        self.feeder.is_finished()
+    }
+}
+
+/// 設定から選べる音声入力ソース
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum AudioSourceConfig {
+    /// 入力デバイス（未指定時はシステム既定のデバイス）
+    Device { name: Option<String> },
+    /// WAVファイルの再生
+    WavFile { path: PathBuf, pacing: Pacing },
+    /// 生PCM（`path` 未指定時は標準入力）
+    RawPcm {
+        path: Option<PathBuf>,
+        sample_rate: u32,
+        channels: usize,
+        encoding: PcmEncoding,
+    },
+    /// 正弦波
+    Tone {
+        frequency: f32,
+        amplitude: f32,
+        sample_rate: u32,
+    },
+}
+
+impl AudioSourceConfig {
+    /// ソースを開く
+    pub fn open(&self) -> Result<Box<dyn AudioSource>> {
+        Ok(match self {
+            AudioSourceConfig::Device { name } => Box::new(CpalSource::open(name.as_deref())?),
+            AudioSourceConfig::WavFile { path, pacing } => {
+                Box::new(WavFileSource::open(path, *pacing)?)
+            }
+            AudioSourceConfig::RawPcm {
+                path: Some(path),
+                sample_rate,
+                channels,
+                encoding,
+            } => Box::new(RawPcmSource::open(
+                path,
+                *sample_rate,
+                *channels,
+                *encoding,
+            )?),
+            AudioSourceConfig::RawPcm {
+                path: None,
+                sample_rate,
+                channels,
+                encoding,
+            } => Box::new(RawPcmSource::stdin(*sample_rate, *channels, *encoding)),
+            AudioSourceConfig::Tone {
+                frequency,
+                amplitude,
+                sample_rate,
+            } => Box::new(ToneSource::new(
+                *frequency,
+                *amplitude,
+                *sample_rate,
+                None,
+                Pacing::RealTime,
+            )),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::audio::AudioCapture;
+
+    /// ソースを最後まで読み終えるまで録音する
+    fn record_to_end(source: Box<dyn AudioSource>) -> AudioCapture {
+        let mut capture = AudioCapture::from_source(source).unwrap();
+        capture.start_recording().unwrap();
+
+        let deadline = Instant::now() + Duration::from_secs(10);
+        while !capture.is_source_finished() {
+            assert!(Instant::now() < deadline, "source did not finish");
+            std::thread::sleep(Duration::from_millis(5));
+        }
+        capture.finish().unwrap();
+        capture
+    }
+
+    #[test]
+    fn test_wav_replay_mixes_down_whole_file() {
+        let path =
+            std::env::temp_dir().join(format!("gijiroku21-source-{}.wav", uuid::Uuid::new_v4()));
+        let spec = hound::WavSpec {
+            channels: 2,
+            sample_rate: 8000,
+            bits_per_sample: 16,
+            sample_format: hound::SampleFormat::Int,
+        };
+        let mut writer = hound::WavWriter::create(&path, spec).unwrap();
+        for _ in 0..8000 {
+            writer.write_sample(i16::MAX / 2).unwrap();
+            writer.write_sample(0i16).unwrap();
+        }
+        writer.finalize().unwrap();
+
+        let source = WavFileSource::open(&path, Pacing::AsFastAsPossible).unwrap();
+        let capture = record_to_end(Box::new(source));
+        std::fs::remove_file(&path).ok();
+
+        let samples = capture.get_buffer().get_all_blocking();
+        assert_eq!(capture.sample_rate(), 8000);
+        assert_eq!(samples.len(), 8000);
+        assert!(samples.iter().all(|&s| (s - 0.25).abs() < 1e-3));
+        assert!((capture.timeline_position_sec() - 1.0).abs() < 0.01);
+        assert_eq!(capture.overrun_count(), 0);
+    }
+
+    #[test]
+    fn test_raw_pcm_from_reader() {
+        let bytes: Vec<u8> = (0..1600)
+            .flat_map(|i| ((i % 2) as f32 * 0.5).to_le_bytes())
+            .collect();
+        let source = RawPcmSource::from_reader(
+            "test",
+            std::io::Cursor::new(bytes),
+            16000,
+            1,
+            PcmEncoding::F32Le,
+        );
+        let capture = record_to_end(Box::new(source));
+
+        let samples = capture.get_buffer().get_all_blocking();
+        assert_eq!(samples.len(), 1600);
+        assert_eq!(&samples[..4], &[0.0, 0.5, 0.0, 0.5]);
+    }
+
+    #[test]
+    fn test_tone_source_real_time_pacing() {
+        let source = ToneSource::new(
+            440.0,
+            0.5,
+            16000,
+            Some(Duration::from_millis(200)),
+            Pacing::RealTime,
+        );
+        let started = Instant::now();
+        let capture = record_to_end(Box::new(source));
+
+        assert!(started.elapsed() >= Duration::from_millis(180));
+        let samples = capture.get_buffer().get_all_blocking();
+        assert_eq!(samples.len(), 3200);
+        let peak = samples.iter().fold(0.0f32, |m, s| m.max(s.abs()));
+        assert!((peak - 0.5).abs() < 0.01);
+    }
+
+    #[test]
+    fn test_sink_mixdown_and_frames() {
+        let (producer, mut consumer) = crate::audio::ring_buffer(8);
+        let mut sink = SourceSink::new(producer, 2, false);
+        assert_eq!(sink.write([1.0, 0.0, 0.5, 0.5, 0.2]), 2);
+
+        let mut out = [0.0; 8];
+        assert_eq!(consumer.pop_slice(&mut out), 2);
+        assert_eq!(&out[..2], &[0.5, 0.5]);
+
+        let (producer, mut consumer) = crate::audio::ring_buffer(8);
+        let mut sink = SourceSink::new(producer, 2, true);
+        assert_eq!(sink.write([1.0, 0.0, 0.5, 0.5]), 2);
+        assert_eq!(consumer.pop_slice(&mut out), 4);
+    }
+}
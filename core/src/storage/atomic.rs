@@ -0,0 +1,189 @@
+use std::fmt::Display;
+use std::fs::File;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+/// `path` のバックアップ（1世代前）のパス（`meeting.json` → `meeting.json.bak`）
+pub fn backup_path(path: &Path) -> PathBuf {
+    let mut name = path.file_name().unwrap_or_default().to_os_string();
+    name.push(".bak");
+    path.with_file_name(name)
+}
+
+/// ファイルを途中で切れた状態にならないように書き込む
+///
+/// 同じディレクトリの一時ファイルに書いてfsyncしてから、本体の上へリネームして置き換える。
+/// 置き換える前の内容は `.bak` として1世代だけ残す
+pub fn write_atomic(path: impl AsRef<Path>, contents: impl AsRef<[u8]>) -> std::io::Result<()> {
+    write_atomic_validated(path, contents, |_| true)
+}
+
+/// `write_atomic` と同じだが、置き換える前の内容が `is_valid` を満たすときだけ `.bak` にする
+///
+/// 本体が壊れていれば、唯一読める前回のバックアップを壊れた内容で上書きしない
+pub fn write_atomic_validated(
+    path: impl AsRef<Path>,
+    contents: impl AsRef<[u8]>,
+    is_valid: impl Fn(&str) -> bool,
+) -> std::io::Result<()> {
+    let path = path.as_ref();
+    let dir = path.parent().unwrap_or_else(|| Path::new("."));
+    let mut temp_name = path.file_name().unwrap_or_default().to_os_string();
+    temp_name.push(format!(".{}.tmp", uuid::Uuid::new_v4()));
+    let temp_path = dir.join(temp_name);
+
+    let result = (|| {
+        let mut file = File::create(&temp_path)?;
+        file.write_all(contents.as_ref())?;
+        file.sync_all()?;
+        drop(file);
+
+        // 本体を残したままバックアップを作り、本体がない瞬間を作らない
+        if path.exists() {
+            if std::fs::read_to_string(path).is_ok_and(|current| is_valid(&current)) {
+                keep_backup(path)?;
+            } else {
+                eprintln!(
+                    "[Storage] {} が壊れているためバックアップを更新しません",
+                    path.display()
+                );
+            }
+        }
+        std::fs::rename(&temp_path, path)?;
+        sync_dir(dir)
+    })();
+
+    if result.is_err() {
+        std::fs::remove_file(&temp_path).ok();
+    }
+    result
+}
+
+/// `path` の今の内容を `.bak` として残す
+///
+/// ハードリンクを張り、張れないファイルシステムではコピーする
+fn keep_backup(path: &Path) -> std::io::Result<()> {
+    let backup = backup_path(path);
+    match std::fs::remove_file(&backup) {
+        Err(e) if e.kind() != std::io::ErrorKind::NotFound => return Err(e),
+        _ => {}
+    }
+    if std::fs::hard_link(path, &backup).is_err() {
+        std::fs::copy(path, &backup)?;
+    }
+    Ok(())
+}
+
+/// ファイルを読んで `parse` し、読めなければバックアップを読む
+///
+/// 本体もバックアップも読めなければ本体のエラーを返す
+pub fn read_with_backup<T, E>(
+    path: impl AsRef<Path>,
+    parse: impl Fn(&str) -> Result<T, E>,
+) -> Result<T, E>
+where
+    E: From<std::io::Error> + Display,
+{
+    let path = path.as_ref();
+    let primary = std::fs::read_to_string(path)
+        .map_err(E::from)
+        .and_then(|content| parse(&content));
+    let error = match primary {
+        Ok(value) => return Ok(value),
+        Err(e) => e,
+    };
+
+    let backup = backup_path(path);
+    if !backup.is_file() {
+        return Err(error);
+    }
+    match std::fs::read_to_string(&backup)
+        .map_err(E::from)
+        .and_then(|content| parse(&content))
+    {
+        Ok(value) => {
+            eprintln!(
+                "[Storage] {} を読み込めないためバックアップを使います: {}",
+                path.display(),
+                error
+            );
+            Ok(value)
+        }
+        Err(_) => Err(error),
+    }
+}
+
+/// リネームをディスクへ確定させる（ディレクトリをfsyncできるのはUnix系のみ）
+#[cfg(unix)]
+fn sync_dir(dir: &Path) -> std::io::Result<()> {
+    File::open(dir)?.sync_all()
+}
+
+#[cfg(not(unix))]
+fn sync_dir(_dir: &Path) -> std::io::Result<()> {
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::storage::testing::TempDir;
+
+    fn parse(content: &str) -> Result<serde_json::Value, std::io::Error> {
+        serde_json::from_str(content).map_err(std::io::Error::other)
+    }
+
+    #[test]
+    fn test_write_keeps_one_backup_and_no_temp_files() {
+        let dir = TempDir::new("atomic");
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("meeting.json");
+
+        write_atomic(&path, "1").unwrap();
+        write_atomic(&path, "2").unwrap();
+        write_atomic(&path, "3").unwrap();
+
+        assert_eq!(std::fs::read_to_string(&path).unwrap(), "3");
+        assert_eq!(std::fs::read_to_string(backup_path(&path)).unwrap(), "2");
+        assert_eq!(std::fs::read_dir(&dir).unwrap().count(), 2);
+    }
+
+    #[test]
+    fn test_falls_back_to_backup() {
+        let dir = TempDir::new("atomic");
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("settings.json");
+
+        write_atomic(&path, r#"{"v":1}"#).unwrap();
+        write_atomic(&path, r#"{"v":2}"#).unwrap();
+        // 本体が途中で切れた場合
+        std::fs::write(&path, r#"{"v":"#).unwrap();
+        assert_eq!(read_with_backup(&path, parse).unwrap()["v"], 1);
+
+        // 置き換えの途中で本体がなくなった場合
+        std::fs::remove_file(&path).unwrap();
+        assert_eq!(read_with_backup(&path, parse).unwrap()["v"], 1);
+
+        // どちらも読めなければ本体のエラー
+        std::fs::write(backup_path(&path), "broken").unwrap();
+        let error = read_with_backup(&path, parse).unwrap_err();
+        assert_eq!(error.kind(), std::io::ErrorKind::NotFound);
+    }
+
+    #[test]
+    fn test_corrupt_primary_does_not_replace_backup() {
+        let dir = TempDir::new("atomic");
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("settings.json");
+        let is_valid = |content: &str| parse(content).is_ok();
+
+        write_atomic_validated(&path, r#"{"v":1}"#, is_valid).unwrap();
+        write_atomic_validated(&path, r#"{"v":2}"#, is_valid).unwrap();
+        // 本体が壊れた状態で保存し直しても、読めるバックアップ（1）は残る
+        std::fs::write(&path, r#"{"v":"#).unwrap();
+        write_atomic_validated(&path, r#"{"v":3}"#, is_valid).unwrap();
+
+        assert_eq!(std::fs::read_to_string(&path).unwrap(), r#"{"v":3}"#);
+        assert_eq!(std::fs::read_to_string(backup_path(&path)).unwrap(), r#"{"v":1}"#);
+    }
+}
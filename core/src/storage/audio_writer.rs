@@ -0,0 +1,247 @@
+use std::fs::{File, OpenOptions};
+use std::io::{BufWriter, Read, Seek, SeekFrom, Write};
+use std::path::Path;
+
+use super::meeting_storage::{Result, StorageError};
+use crate::audio::StreamingResampler;
+
+/// ヘッダを更新する既定の間隔（秒）
+const DEFAULT_FLUSH_INTERVAL_SEC: u32 = 5;
+
+/// 録音中の音声をWAVファイルへ逐次追記するライター
+///
+/// メモリ上のASR用バッファとは独立して会議全体を保存する。
+/// 一定間隔でヘッダを確定させるため、途中でプロセスが終了しても
+/// 直近のフラッシュまでの音声は再生可能なファイルとして残る。
+pub struct StreamingAudioWriter {
+    writer: hound::WavWriter<BufWriter<File>>,
+    sample_rate: u32,
+    samples_written: u64,
+    unflushed: u64,
+    flush_interval: u64,
+    /// ファイルと異なるレートの入力を変換するためのリサンプラー
+    resampler: Option<StreamingResampler>,
+}
+
+impl StreamingAudioWriter {
+    /// 新しいWAVファイルを作成（16bit モノラル）
+    pub fn create(path: impl AsRef<Path>, sample_rate: u32) -> Result<Self> {
+        if let Some(parent) = path.as_ref().parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+
+        let spec = hound::WavSpec {
+            channels: 1,
+            sample_rate,
+            bits_per_sample: 16,
+            sample_format: hound::SampleFormat::Int,
+        };
+        let writer = hound::WavWriter::create(path, spec).map_err(wav_error)?;
+
+        Ok(StreamingAudioWriter {
+            writer,
+            sample_rate,
+            samples_written: 0,
+            unflushed: 0,
+            flush_interval: (sample_rate * DEFAULT_FLUSH_INTERVAL_SEC) as u64,
+            resampler: None,
+        })
+    }
+
+    /// ヘッダを更新する間隔を変更
+    pub fn with_flush_interval(mut self, seconds: f32) -> Self {
+        self.flush_interval = ((self.sample_rate as f32 * seconds) as u64).max(1);
+        self
+    }
+
+    /// ファイルのサンプルレート（Hz）
+    pub fn sample_rate(&self) -> u32 {
+        self.sample_rate
+    }
+
+    /// 書き込み済みの長さ（秒）
+    pub fn duration_sec(&self) -> f64 {
+        self.samples_written as f64 / self.sample_rate as f64
+    }
+
+    /// ファイルと同じレートのサンプルを追記
+    pub fn write_samples(&mut self, samples: &[f32]) -> Result<()> {
+        for &sample in samples {
+            let amplitude = (sample * i16::MAX as f32) as i16;
+            self.writer.write_sample(amplitude).map_err(wav_error)?;
+        }
+
+        self.samples_written += samples.len() as u64;
+        self.unflushed += samples.len() as u64;
+        if self.unflushed >= self.flush_interval {
+            self.flush()?;
+        }
+        Ok(())
+    }
+
+    /// 任意のレートのサンプルを追記（必要に応じてファイルのレートへ変換）
+    ///
+    /// デバイスの再接続などで入力レートが変わっても同じファイルへ書き続けられる
+    pub fn write_at_rate(&mut self, samples: &[f32], input_rate: u32) -> Result<()> {
+        if input_rate == self.sample_rate {
+            self.flush_resampler()?;
+            return self.write_samples(samples);
+        }
+
+        if self.resampler.as_ref().map(|r| r.input_rate()) != Some(input_rate) {
+            self.flush_resampler()?;
+            self.resampler = Some(StreamingResampler::new(input_rate, self.sample_rate));
+        }
+        let converted = self.resampler.as_mut().unwrap().process(samples);
+        self.write_samples(&converted)
+    }
+
+    /// ヘッダを更新してディスクへ書き出す
+    pub fn flush(&mut self) -> Result<()> {
+        self.writer.flush().map_err(wav_error)?;
+        self.unflushed = 0;
+        Ok(())
+    }
+
+    /// 書き込みを完了してファイルを閉じる
+    ///
+    /// # Returns
+    /// 書き込んだサンプル数
+    pub fn finalize(mut self) -> Result<u64> {
+        self.flush_resampler()?;
+        self.writer.finalize().map_err(wav_error)?;
+        Ok(self.samples_written)
+    }
+
+    /// 変換途中のサンプルを書き出してリサンプラーを破棄
+    fn flush_resampler(&mut self) -> Result<()> {
+        if let Some(mut resampler) = self.resampler.take() {
+            let tail = resampler.flush();
+            self.write_samples(&tail)?;
+        }
+        Ok(())
+    }
+}
+
+/// 異常終了でヘッダが最後のフラッシュ時点のままになったWAVファイルを修復する
+///
+/// ファイルに実際に書き込まれている長さに合わせてRIFF/dataチャンクのサイズを書き直し、
+/// 途中で切れたサンプルは切り捨てる
+///
+/// # Returns
+/// 修復後のサンプル数（全チャンネル合計）
+pub fn repair_wav_header(path: impl AsRef<Path>) -> Result<u64> {
+    let mut file = OpenOptions::new().read(true).write(true).open(path)?;
+    let file_len = file.metadata()?.len();
+
+    let mut riff = [0u8; 12];
+    file.read_exact(&mut riff)?;
+    if &riff[0..4] != b"RIFF" || &riff[8..12] != b"WAVE" {
+        return Err(StorageError::Corrupted("Not a WAV file".to_string()));
+    }
+
+    // fmt チャンクからチャンネル数とブロック長を読み、data チャンクの位置を探す
+    let mut format = None;
+    let mut position = 12u64;
+    let data_start = loop {
+        let mut header = [0u8; 8];
+        if position + 8 > file_len {
+            return Err(StorageError::Corrupted("WAV data chunk not found".to_string()));
+        }
+        file.seek(SeekFrom::Start(position))?;
+        file.read_exact(&mut header)?;
+        let size = u32::from_le_bytes([header[4], header[5], header[6], header[7]]) as u64;
+
+        match &header[0..4] {
+            b"data" => break position + 8,
+            b"fmt " => {
+                let mut fmt = [0u8; 14];
+                file.read_exact(&mut fmt)?;
+                let channels = u16::from_le_bytes([fmt[2], fmt[3]]).max(1) as u64;
+                let block_align = u16::from_le_bytes([fmt[12], fmt[13]]).max(1) as u64;
+                format = Some((channels, block_align));
+            }
+            _ => {}
+        }
+        // チャンクは2バイト境界に揃えられる
+        position += 8 + size + (size & 1);
+    };
+    let (channels, block_align) =
+        format.ok_or_else(|| StorageError::Corrupted("WAV fmt chunk not found".to_string()))?;
+
+    let data_len = (file_len - data_start) / block_align * block_align;
+    let data_len = data_len.min(u32::MAX as u64 - data_start);
+    file.set_len(data_start + data_len)?;
+
+    file.seek(SeekFrom::Start(4))?;
+    file.write_all(&((data_start + data_len - 8) as u32).to_le_bytes())?;
+    file.seek(SeekFrom::Start(data_start - 4))?;
+    file.write_all(&(data_len as u32).to_le_bytes())?;
+    file.sync_all()?;
+
+    // block_align はチャンネル数×1サンプルのバイト数
+    Ok(data_len / block_align * channels)
+}
+
+fn wav_error(e: hound::Error) -> StorageError {
+    StorageError::Io(std::io::Error::other(e))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::storage::testing::TempDir;
+
+    #[test]
+    fn test_flushed_file_is_readable_before_finalize() {
+        let dir = TempDir::new("flush");
+        let path = dir.join("audio.wav");
+        let mut writer = StreamingAudioWriter::create(&path, 16000)
+            .unwrap()
+            .with_flush_interval(1.0);
+
+        writer.write_samples(&vec![0.25; 16000 * 2]).unwrap();
+
+        // finalize前でもヘッダが更新されていること
+        let reader = hound::WavReader::open(&path).unwrap();
+        assert_eq!(reader.len(), 16000 * 2);
+
+        assert_eq!(writer.finalize().unwrap(), 16000 * 2);
+    }
+
+    #[test]
+    fn test_write_at_rate_converts_to_file_rate() {
+        let dir = TempDir::new("rate");
+        let path = dir.join("audio.wav");
+        let mut writer = StreamingAudioWriter::create(&path, 16000).unwrap();
+
+        writer.write_at_rate(&vec![0.0; 48000], 48000).unwrap();
+        writer.write_at_rate(&vec![0.0; 16000], 16000).unwrap();
+
+        assert_eq!(writer.finalize().unwrap(), 32000);
+        let reader = hound::WavReader::open(&path).unwrap();
+        assert_eq!(reader.spec().sample_rate, 16000);
+        assert_eq!(reader.len(), 32000);
+    }
+
+    #[test]
+    fn test_repair_header_after_crash() {
+        let dir = TempDir::new("repair");
+        let path = dir.join("audio.wav");
+        let mut writer = StreamingAudioWriter::create(&path, 16000).unwrap();
+        writer.write_samples(&vec![0.5; 24000]).unwrap();
+        writer.finalize().unwrap();
+
+        // ヘッダが16000サンプル時点のまま残り、末尾に書きかけのサンプルがある状態を再現する
+        let mut file = OpenOptions::new().read(true).write(true).open(&path).unwrap();
+        file.seek(SeekFrom::Start(40)).unwrap();
+        file.write_all(&(16000u32 * 2).to_le_bytes()).unwrap();
+        file.seek(SeekFrom::End(0)).unwrap();
+        file.write_all(&[0x12]).unwrap();
+        drop(file);
+
+        assert_eq!(hound::WavReader::open(&path).unwrap().len(), 16000);
+        assert_eq!(repair_wav_header(&path).unwrap(), 24000);
+        assert_eq!(hound::WavReader::open(&path).unwrap().len(), 24000);
+    }
+}
@@ -0,0 +1,938 @@
+use chrono::{DateTime, Utc};
+use rusqlite::types::{FromSql, FromSqlError, FromSqlResult, ToSqlOutput, ValueRef};
+use rusqlite::{params, params_from_iter, Connection, ToSql};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use super::library::{MeetingPage, MeetingSort, MeetingSummary};
+use super::meeting_storage::{MeetingData, MeetingStorage, Result, StorageError};
+use super::MeetingId;
+use super::schema::{MeetingLoad, UnreadableMeeting};
+
+/// 検索インデックスのファイル名（保存先ディレクトリ直下）
+const INDEX_FILE: &str = "index.sqlite3";
+/// インデックスのスキーマのバージョン（異なれば meeting.json から作り直す）
+const INDEX_VERSION: i64 = 3;
+/// 検索で返す会議数の既定値
+const DEFAULT_SEARCH_LIMIT: usize = 50;
+/// 1会議あたりに返すヒットの最大数
+const MAX_HITS_PER_MEETING: usize = 20;
+/// スニペットでヒット箇所より前に含める文字数
+const SNIPPET_BEFORE_CHARS: usize = 30;
+/// スニペットの最大文字数
+const SNIPPET_CHARS: usize = 100;
+
+const SCHEMA: &str = "
+DROP TABLE IF EXISTS meetings;
+DROP TABLE IF EXISTS meeting_tags;
+DROP TABLE IF EXISTS segments;
+DROP TABLE IF EXISTS segments_fts;
+DROP TABLE IF EXISTS unreadable_meetings;
+
+CREATE TABLE meetings (
+    id TEXT PRIMARY KEY,
+    title TEXT NOT NULL,
+    started_at INTEGER NOT NULL,
+    ended_at INTEGER,
+    duration_sec REAL,
+    snippet TEXT NOT NULL,
+    stamp TEXT NOT NULL
+);
+CREATE INDEX meetings_by_start ON meetings(started_at);
+
+CREATE TABLE meeting_tags (
+    meeting_id TEXT NOT NULL,
+    tag TEXT NOT NULL,
+    position INTEGER NOT NULL,
+    PRIMARY KEY (meeting_id, tag)
+);
+
+CREATE TABLE segments (
+    id INTEGER PRIMARY KEY,
+    meeting_id TEXT NOT NULL,
+    position INTEGER NOT NULL,
+    timestamp REAL NOT NULL,
+    speaker TEXT,
+    text TEXT NOT NULL,
+    normalized TEXT NOT NULL
+);
+CREATE INDEX segments_by_meeting ON segments(meeting_id, position);
+
+-- 日本語は単語の区切りがないため、正規化した文字列の2-gramを空白区切りで入れる
+CREATE VIRTUAL TABLE segments_fts USING fts5(grams, tokenize = 'ascii');
+
+-- meeting.json を読み込めなかった会議
+CREATE TABLE unreadable_meetings (
+    meeting_id TEXT PRIMARY KEY,
+    error TEXT NOT NULL,
+    stamp TEXT NOT NULL
+);
+";
+
+/// 会議の検索条件
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct SearchQuery {
+    /// 検索語（空白区切りの語をすべて含む会議を探す。空なら絞り込みのみ）
+    #[serde(default)]
+    pub text: String,
+    /// 開始日時がこの時刻以降の会議
+    pub from: Option<DateTime<Utc>>,
+    /// 開始日時がこの時刻より前の会議
+    pub to: Option<DateTime<Utc>>,
+    /// すべてのタグが付いている会議
+    #[serde(default)]
+    pub tags: Vec<String>,
+    /// この話者の発言だけを対象にする
+    pub speaker: Option<String>,
+    /// 返す会議数の上限
+    pub limit: Option<usize>,
+}
+
+/// スニペットの一部分
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SnippetPart {
+    pub text: String,
+    /// 検索語に一致した部分か
+    pub highlighted: bool,
+}
+
+/// 検索に一致した文字起こしセグメント
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SearchHit {
+    /// 文字起こし中の位置（0始まり）
+    pub position: usize,
+    /// 音声上の時刻（秒）
+    pub timestamp: f64,
+    pub speaker: Option<String>,
+    pub snippet: Vec<SnippetPart>,
+}
+
+/// 会議ごとの検索結果
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SearchResult {
+    pub meeting: MeetingSummary,
+    /// 一致したセグメント（先頭から最大 `MAX_HITS_PER_MEETING` 件）
+    pub hits: Vec<SearchHit>,
+    /// 一致したセグメントの総数
+    pub hit_count: usize,
+}
+
+/// 保存先ディレクトリ直下のSQLiteに置く会議の索引
+///
+/// 正はあくまで各会議の meeting.json で、索引は保存・削除のたびに追従させる。
+/// 一覧・検索の前には meeting.json の更新日時とサイズを比べ、アプリの外で
+/// 変わった会議だけを読み直す。失われたりスキーマが変わったりした場合は作り直す
+struct MeetingIndex {
+    conn: Connection,
+}
+
+impl MeetingIndex {
+    fn open(path: &Path) -> Result<Self> {
+        let conn = Connection::open(path)?;
+        // 録音スレッドの自動保存とUIからの検索が重なっても待てるようにする
+        conn.busy_timeout(Duration::from_secs(5))?;
+        Ok(MeetingIndex { conn })
+    }
+
+    fn is_current(&self) -> Result<bool> {
+        let version: i64 = self
+            .conn
+            .pragma_query_value(None, "user_version", |row| row.get(0))?;
+        Ok(version == INDEX_VERSION)
+    }
+
+    /// 次に開いたときに作り直させる
+    fn invalidate(&self) -> Result<()> {
+        self.conn.pragma_update(None, "user_version", 0)?;
+        Ok(())
+    }
+
+    /// テーブルを作り直して全会議を登録する
+    ///
+    /// `stamps` は読み込む前に取った各会議の meeting.json の更新値
+    fn rebuild(&mut self, load: &MeetingLoad, stamps: &HashMap<MeetingId, String>) -> Result<()> {
+        let stamp = |meeting_id: &MeetingId| stamps.get(meeting_id).cloned().unwrap_or_default();
+        let tx = self.conn.transaction()?;
+        tx.execute_batch(SCHEMA)?;
+        for meeting in &load.meetings {
+            insert_rows(&tx, meeting, &stamp(&meeting.id))?;
+        }
+        for unreadable in &load.unreadable {
+            insert_unreadable(&tx, unreadable, &stamp(&unreadable.meeting_id))?;
+        }
+        tx.pragma_update(None, "user_version", INDEX_VERSION)?;
+        tx.commit()?;
+        Ok(())
+    }
+
+    fn upsert(&mut self, meeting: &MeetingData, stamp: &str) -> Result<()> {
+        let tx = self.conn.transaction()?;
+        delete_rows(&tx, &meeting.id)?;
+        insert_rows(&tx, meeting, stamp)?;
+        tx.commit()?;
+        Ok(())
+    }
+
+    /// 読み込めなくなった会議を一覧・検索から外し、読み込めない会議として登録する
+    fn mark_unreadable(&mut self, unreadable: &UnreadableMeeting, stamp: &str) -> Result<()> {
+        let tx = self.conn.transaction()?;
+        delete_rows(&tx, &unreadable.meeting_id)?;
+        insert_unreadable(&tx, unreadable, stamp)?;
+        tx.commit()?;
+        Ok(())
+    }
+
+    /// 登録済みの会議（読み込めない会議を含む）と、登録したときの meeting.json の更新値
+    fn stamps(&self) -> Result<HashMap<MeetingId, String>> {
+        let mut statement = self.conn.prepare(
+            "SELECT id, stamp FROM meetings UNION ALL SELECT meeting_id, stamp FROM unreadable_meetings",
+        )?;
+        let stamps = statement
+            .query_map([], |row| Ok((row.get(0)?, row.get(1)?)))?
+            .collect::<rusqlite::Result<HashMap<_, _>>>()?;
+        Ok(stamps)
+    }
+
+    fn remove(&mut self, meeting_id: &MeetingId) -> Result<()> {
+        let tx = self.conn.transaction()?;
+        delete_rows(&tx, meeting_id)?;
+        tx.commit()?;
+        Ok(())
+    }
+
+    fn list(&self, sort: MeetingSort, offset: usize, limit: usize) -> Result<MeetingPage> {
+        let order = match sort {
+            MeetingSort::NewestFirst => "started_at DESC",
+            MeetingSort::OldestFirst => "started_at ASC",
+            MeetingSort::Title => "title ASC, started_at DESC",
+            MeetingSort::LongestFirst => "COALESCE(duration_sec, 0) DESC, started_at DESC",
+        };
+        let total: i64 = self
+            .conn
+            .query_row("SELECT COUNT(*) FROM meetings", [], |row| row.get(0))?;
+
+        let sql = format!(
+            "SELECT id, title, started_at, ended_at, duration_sec, snippet FROM meetings
+             ORDER BY {} LIMIT ?1 OFFSET ?2",
+            order
+        );
+        let mut statement = self.conn.prepare(&sql)?;
+        let rows = statement.query_map(params![to_sql_count(limit), to_sql_count(offset)], read_summary)?;
+        let mut meetings = rows.collect::<rusqlite::Result<Vec<_>>>()?;
+        for meeting in &mut meetings {
+            meeting.tags = self.tags(&meeting.id)?;
+        }
+
+        let mut statement = self
+            .conn
+            .prepare("SELECT meeting_id, error FROM unreadable_meetings ORDER BY meeting_id")?;
+        let unreadable = statement
+            .query_map([], |row| {
+                Ok(UnreadableMeeting {
+                    meeting_id: row.get(0)?,
+                    error: row.get(1)?,
+                })
+            })?
+            .collect::<rusqlite::Result<Vec<_>>>()?;
+
+        Ok(MeetingPage {
+            meetings,
+            total: total as usize,
+            unreadable,
+        })
+    }
+
+    fn search(&self, query: &SearchQuery) -> Result<Vec<SearchResult>> {
+        let limit = query.limit.unwrap_or(DEFAULT_SEARCH_LIMIT);
+        let terms: Vec<SearchTerm> = query
+            .text
+            .split_whitespace()
+            .filter_map(SearchTerm::parse)
+            .collect();
+
+        let mut conditions: Vec<String> = Vec::new();
+        let mut values: Vec<Box<dyn ToSql>> = Vec::new();
+        if let Some(from) = query.from {
+            conditions.push("m.started_at >= ?".to_string());
+            values.push(Box::new(from.timestamp_millis()));
+        }
+        if let Some(to) = query.to {
+            conditions.push("m.started_at < ?".to_string());
+            values.push(Box::new(to.timestamp_millis()));
+        }
+        for tag in query.tags.iter().map(|t| t.trim()).filter(|t| !t.is_empty()) {
+            conditions.push(
+                "EXISTS (SELECT 1 FROM meeting_tags t WHERE t.meeting_id = m.id AND t.tag = ?)"
+                    .to_string(),
+            );
+            values.push(Box::new(tag.to_string()));
+        }
+
+        // 検索語も話者もなければ会議の絞り込みだけを行う
+        let speaker = query.speaker.as_deref().map(str::trim).filter(|s| !s.is_empty());
+        if terms.is_empty() && speaker.is_none() {
+            let sql = format!(
+                "SELECT m.id, m.title, m.started_at, m.ended_at, m.duration_sec, m.snippet
+                 FROM meetings m {} ORDER BY m.started_at DESC LIMIT ?",
+                where_clause(&conditions)
+            );
+            values.push(Box::new(to_sql_count(limit)));
+            let mut statement = self.conn.prepare(&sql)?;
+            let rows = statement.query_map(params_from_iter(values.iter()), read_summary)?;
+            let mut results = Vec::new();
+            for meeting in rows {
+                let mut meeting = meeting?;
+                meeting.tags = self.tags(&meeting.id)?;
+                results.push(SearchResult {
+                    meeting,
+                    hits: Vec::new(),
+                    hit_count: 0,
+                });
+            }
+            return Ok(results);
+        }
+
+        if let Some(speaker) = speaker {
+            conditions.push("s.speaker = ?".to_string());
+            values.push(Box::new(speaker.to_string()));
+        }
+        if !terms.is_empty() {
+            let mut alternatives = Vec::new();
+            for term in &terms {
+                let (condition, term_values) = term.condition();
+                alternatives.push(condition);
+                values.extend(term_values);
+            }
+            conditions.push(format!("({})", alternatives.join(" OR ")));
+        }
+
+        let sql = format!(
+            "SELECT s.meeting_id, s.position, s.timestamp, s.speaker, s.text, s.normalized
+             FROM segments s JOIN meetings m ON m.id = s.meeting_id
+             {} ORDER BY m.started_at DESC, s.meeting_id, s.position",
+            where_clause(&conditions)
+        );
+        let mut statement = self.conn.prepare(&sql)?;
+        let mut rows = statement.query(params_from_iter(values.iter()))?;
+
+        // 会議ごとにまとめ、n-gramの偶然の一致を除くため実際の文字列で照合し直す
+        let mut grouped: Vec<(MeetingId, Vec<SearchHit>, Vec<bool>)> = Vec::new();
+        while let Some(row) = rows.next()? {
+            let meeting_id: MeetingId = row.get(0)?;
+            let text: String = row.get(4)?;
+            let normalized: String = row.get(5)?;
+
+            let original: Vec<char> = text.chars().collect();
+            let normalized: Vec<char> = normalized.chars().collect();
+            let mut marks = vec![false; normalized.len()];
+            let mut matched_terms = vec![false; terms.len()];
+            for (index, term) in terms.iter().enumerate() {
+                matched_terms[index] = term.mark(&normalized, &mut marks);
+            }
+            if !terms.is_empty() && !matched_terms.contains(&true) {
+                continue;
+            }
+
+            let hit = SearchHit {
+                position: row.get::<_, i64>(1)? as usize,
+                timestamp: row.get(2)?,
+                speaker: row.get(3)?,
+                snippet: build_snippet(&original, &marks),
+            };
+            match grouped.last_mut().filter(|(id, _, _)| *id == meeting_id) {
+                Some((_, hits, found)) => {
+                    hits.push(hit);
+                    for (found, matched) in found.iter_mut().zip(matched_terms) {
+                        *found |= matched;
+                    }
+                }
+                None => grouped.push((meeting_id, vec![hit], matched_terms)),
+            }
+        }
+
+        let mut results = Vec::new();
+        for (meeting_id, mut hits, found) in grouped {
+            if found.contains(&false) {
+                continue;
+            }
+            if results.len() >= limit {
+                break;
+            }
+            let mut meeting = self.conn.query_row(
+                "SELECT id, title, started_at, ended_at, duration_sec, snippet FROM meetings WHERE id = ?1",
+                params![meeting_id],
+                read_summary,
+            )?;
+            meeting.tags = self.tags(&meeting.id)?;
+
+            let hit_count = hits.len();
+            hits.truncate(MAX_HITS_PER_MEETING);
+            results.push(SearchResult {
+                meeting,
+                hits,
+                hit_count,
+            });
+        }
+        Ok(results)
+    }
+
+    fn tags(&self, meeting_id: &MeetingId) -> Result<Vec<String>> {
+        let mut statement = self
+            .conn
+            .prepare_cached("SELECT tag FROM meeting_tags WHERE meeting_id = ?1 ORDER BY position")?;
+        let tags = statement
+            .query_map(params![meeting_id], |row| row.get(0))?
+            .collect::<rusqlite::Result<Vec<String>>>()?;
+        Ok(tags)
+    }
+}
+
+impl MeetingStorage {
+    fn index_path(&self) -> PathBuf {
+        self.base_dir().join(INDEX_FILE)
+    }
+
+    /// 索引を開く（ないかスキーマが古ければ meeting.json から作り直す）
+    fn open_index(&self) -> Result<MeetingIndex> {
+        let mut index = MeetingIndex::open(&self.index_path())?;
+        if !index.is_current()? {
+            self.rebuild_into(&mut index)?;
+        }
+        Ok(index)
+    }
+
+    /// 全会議の meeting.json から索引を作り直す
+    ///
+    /// # Returns
+    /// 登録した会議の数
+    pub fn rebuild_index(&self) -> Result<usize> {
+        let mut index = MeetingIndex::open(&self.index_path())?;
+        self.rebuild_into(&mut index)
+    }
+
+    fn rebuild_into(&self, index: &mut MeetingIndex) -> Result<usize> {
+        let stamps = self
+            .list_meetings()?
+            .into_iter()
+            .map(|meeting_id| {
+                let stamp = self.meeting_json_stamp(&meeting_id);
+                (meeting_id, stamp)
+            })
+            .collect();
+        let load = self.load_all_meetings()?;
+        index.rebuild(&load, &stamps)?;
+        println!(
+            "[Storage] 検索インデックスを作成しました（{}件、読み込めない会議{}件）",
+            load.meetings.len(),
+            load.unreadable.len()
+        );
+        Ok(load.meetings.len())
+    }
+
+    /// 保存した会議を索引に反映（失敗しても保存自体は成功として扱い、索引は次に開いたときに作り直す）
+    pub(super) fn index_meeting(&self, meeting: &MeetingData) {
+        let stamp = self.meeting_json_stamp(&meeting.id);
+        if let Err(e) = self.open_index().and_then(|mut index| index.upsert(meeting, &stamp)) {
+            eprintln!("[Storage] 検索インデックスを更新できません ({}): {}", meeting.id, e);
+            self.invalidate_index();
+        }
+    }
+
+    /// 削除した会議を索引から除く
+    pub(super) fn unindex_meeting(&self, meeting_id: &MeetingId) {
+        if let Err(e) = self.open_index().and_then(|mut index| index.remove(meeting_id)) {
+            eprintln!("[Storage] 検索インデックスを更新できません ({}): {}", meeting_id, e);
+            self.invalidate_index();
+        }
+    }
+
+    /// meeting.json と食い違った索引を、次に開いたときに作り直させる
+    ///
+    /// バージョンを戻せなければ索引ファイルごと削除する
+    fn invalidate_index(&self) {
+        let result = MeetingIndex::open(&self.index_path())
+            .and_then(|index| index.invalidate())
+            .or_else(|_| self.discard_index());
+        if let Err(e) = result {
+            eprintln!("[Storage] 検索インデックスを無効にできません: {}", e);
+        }
+    }
+
+    /// 索引ファイルを削除する（次に開いたときに作り直される）
+    pub(super) fn discard_index(&self) -> Result<()> {
+        match std::fs::remove_file(self.index_path()) {
+            Err(e) if e.kind() != std::io::ErrorKind::NotFound => Err(e.into()),
+            _ => Ok(()),
+        }
+    }
+
+    /// 索引を開き、アプリの外で書き換え・追加・削除された meeting.json を反映する
+    ///
+    /// 更新日時とサイズが登録時と変わった会議だけを読み直し、読み込めなければ
+    /// 読み込めない会議として登録する
+    fn open_checked_index(&self) -> Result<MeetingIndex> {
+        let mut index = self.open_index()?;
+        let mut indexed = index.stamps()?;
+        for meeting_id in self.list_meetings()? {
+            let stamp = self.meeting_json_stamp(&meeting_id);
+            match indexed.remove(&meeting_id) {
+                Some(indexed_stamp) if indexed_stamp == stamp => continue,
+                // meeting.json がまだない（録音中の）会議
+                None if stamp.is_empty() => continue,
+                _ => {}
+            }
+            match self.load_meeting(&meeting_id) {
+                Ok(meeting) => index.upsert(&meeting, &stamp)?,
+                Err(StorageError::NotFound(_)) => index.remove(&meeting_id)?,
+                Err(e) => {
+                    eprintln!("[Storage] 会議を読み込めません ({}): {}", meeting_id, e);
+                    let unreadable = UnreadableMeeting {
+                        meeting_id,
+                        error: e.to_string(),
+                    };
+                    index.mark_unreadable(&unreadable, &stamp)?;
+                }
+            }
+        }
+        // フォルダごと消えた会議
+        for meeting_id in indexed.into_keys() {
+            index.remove(&meeting_id)?;
+        }
+        Ok(index)
+    }
+
+    /// 索引を使って会議の概要を取得
+    pub(super) fn list_indexed_summaries(
+        &self,
+        sort: MeetingSort,
+        offset: usize,
+        limit: usize,
+    ) -> Result<MeetingPage> {
+        self.open_checked_index()?.list(sort, offset, limit)
+    }
+
+    /// 文字起こしを全文検索し、日付・タグ・話者で絞り込む
+    pub fn search_meetings(&self, query: &SearchQuery) -> Result<Vec<SearchResult>> {
+        self.open_checked_index()?.search(query)
+    }
+}
+
+/// 読み込んだ会議からメモリ上に一時的な索引を作って検索する（索引ファイルを持たない保存先用）
+pub(super) fn search_loaded(load: &MeetingLoad, query: &SearchQuery) -> Result<Vec<SearchResult>> {
+    let mut index = MeetingIndex {
+        conn: Connection::open_in_memory()?,
+    };
+    index.rebuild(load, &HashMap::new())?;
+    index.search(query)
+}
+
+/// 検索語1つ（記号で区切られた部分ごとに照合する）
+struct SearchTerm {
+    runs: Vec<Vec<char>>,
+}
+
+impl SearchTerm {
+    fn parse(text: &str) -> Option<Self> {
+        let runs = runs(&normalize(text));
+        (!runs.is_empty()).then_some(SearchTerm { runs })
+    }
+
+    /// 候補のセグメントを絞り込むSQLの条件
+    ///
+    /// 2文字以上の部分は2-gramのフレーズとして全文検索し、1文字の部分はLIKEで探す
+    fn condition(&self) -> (String, Vec<Box<dyn ToSql>>) {
+        let mut conditions = Vec::new();
+        let mut values: Vec<Box<dyn ToSql>> = Vec::new();
+
+        let phrases: Vec<String> = self
+            .runs
+            .iter()
+            .filter(|run| run.len() >= 2)
+            .map(|run| format!("\"{}\"", bigrams(run).join(" ")))
+            .collect();
+        if !phrases.is_empty() {
+            conditions
+                .push("s.id IN (SELECT rowid FROM segments_fts WHERE segments_fts MATCH ?)".to_string());
+            values.push(Box::new(phrases.join(" AND ")));
+        }
+        for run in self.runs.iter().filter(|run| run.len() == 1) {
+            conditions.push("s.normalized LIKE ? ESCAPE '\\'".to_string());
+            values.push(Box::new(format!("%{}%", escape_like(&run[0].to_string()))));
+        }
+
+        (format!("({})", conditions.join(" AND ")), values)
+    }
+
+    /// 正規化済みの文字列中の一致箇所に印を付け、すべての部分が見つかったかを返す
+    fn mark(&self, normalized: &[char], marks: &mut [bool]) -> bool {
+        let mut all_found = true;
+        for run in &self.runs {
+            let mut found = false;
+            if run.len() <= normalized.len() {
+                for start in 0..=normalized.len() - run.len() {
+                    if normalized[start..start + run.len()] == run[..] {
+                        marks[start..start + run.len()].iter_mut().for_each(|m| *m = true);
+                        found = true;
+                    }
+                }
+            }
+            all_found &= found;
+        }
+        all_found
+    }
+}
+
+/// 検索用に正規化する（全角英数字を半角に、英字を小文字に）
+///
+/// 1文字を1文字に置き換えるため、正規化前後で文字の位置が対応する
+fn normalize(text: &str) -> String {
+    text.chars()
+        .map(|c| {
+            let c = match c {
+                '\u{FF01}'..='\u{FF5E}' => char::from_u32(c as u32 - 0xFEE0).unwrap_or(c),
+                '\u{3000}' => ' ',
+                _ => c,
+            };
+            let mut lower = c.to_lowercase();
+            match (lower.next(), lower.next()) {
+                (Some(lower), None) => lower,
+                _ => c,
+            }
+        })
+        .collect()
+}
+
+/// 文字・数字の連続した部分（記号や空白で区切る）
+fn runs(normalized: &str) -> Vec<Vec<char>> {
+    normalized
+        .split(|c: char| !c.is_alphanumeric())
+        .filter(|run| !run.is_empty())
+        .map(|run| run.chars().collect())
+        .collect()
+}
+
+fn bigrams(run: &[char]) -> Vec<String> {
+    run.windows(2).map(|pair| pair.iter().collect()).collect()
+}
+
+/// 正規化済みの文字列から全文検索に入れる2-gram列を作る
+fn index_grams(normalized: &str) -> String {
+    runs(normalized)
+        .iter()
+        .flat_map(|run| bigrams(run))
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+fn escape_like(text: &str) -> String {
+    text.replace('\\', "\\\\").replace('%', "\\%").replace('_', "\\_")
+}
+
+/// 最初の一致箇所の少し前から始まるスニペットを作る
+fn build_snippet(original: &[char], marks: &[bool]) -> Vec<SnippetPart> {
+    let first = marks.iter().position(|&m| m).unwrap_or(0);
+    let start = first.saturating_sub(SNIPPET_BEFORE_CHARS);
+    let end = (start + SNIPPET_CHARS).min(original.len());
+
+    let mut parts: Vec<SnippetPart> = Vec::new();
+    if start > 0 {
+        parts.push(SnippetPart {
+            text: "…".to_string(),
+            highlighted: false,
+        });
+    }
+    for (&c, &highlighted) in original[start..end].iter().zip(&marks[start..end]) {
+        match parts.last_mut().filter(|part| part.highlighted == highlighted) {
+            Some(part) => part.text.push(c),
+            None => parts.push(SnippetPart {
+                text: c.to_string(),
+                highlighted,
+            }),
+        }
+    }
+    if end < original.len() {
+        match parts.last_mut().filter(|part| !part.highlighted) {
+            Some(part) => part.text.push('…'),
+            None => parts.push(SnippetPart {
+                text: "…".to_string(),
+                highlighted: false,
+            }),
+        }
+    }
+    parts
+}
+
+fn insert_rows(conn: &Connection, meeting: &MeetingData, stamp: &str) -> Result<()> {
+    let summary = MeetingSummary::from_meeting(meeting);
+    conn.execute(
+        "INSERT INTO meetings (id, title, started_at, ended_at, duration_sec, snippet, stamp)
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
+        params![
+            summary.id,
+            summary.title,
+            summary.started_at.timestamp_millis(),
+            summary.ended_at.map(|t| t.timestamp_millis()),
+            summary.duration_sec,
+            summary.snippet,
+            stamp,
+        ],
+    )?;
+
+    let mut insert_tag = conn.prepare_cached(
+        "INSERT OR IGNORE INTO meeting_tags (meeting_id, tag, position) VALUES (?1, ?2, ?3)",
+    )?;
+    for (position, tag) in meeting.tags.iter().enumerate() {
+        insert_tag.execute(params![meeting.id, tag, position as i64])?;
+    }
+
+    let mut insert_segment = conn.prepare_cached(
+        "INSERT INTO segments (meeting_id, position, timestamp, speaker, text, normalized)
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+    )?;
+    let mut insert_grams =
+        conn.prepare_cached("INSERT INTO segments_fts (rowid, grams) VALUES (?1, ?2)")?;
+    for (position, segment) in meeting.transcript.iter().enumerate() {
+        let normalized = normalize(&segment.text);
+        insert_segment.execute(params![
+            meeting.id,
+            position as i64,
+            segment.timestamp,
+            segment.speaker,
+            segment.text,
+            normalized,
+        ])?;
+        insert_grams.execute(params![conn.last_insert_rowid(), index_grams(&normalized)])?;
+    }
+    Ok(())
+}
+
+fn insert_unreadable(conn: &Connection, unreadable: &UnreadableMeeting, stamp: &str) -> Result<()> {
+    conn.execute(
+        "INSERT INTO unreadable_meetings (meeting_id, error, stamp) VALUES (?1, ?2, ?3)",
+        params![unreadable.meeting_id, unreadable.error, stamp],
+    )?;
+    Ok(())
+}
+
+fn delete_rows(conn: &Connection, meeting_id: &MeetingId) -> Result<()> {
+    conn.execute(
+        "DELETE FROM segments_fts WHERE rowid IN (SELECT id FROM segments WHERE meeting_id = ?1)",
+        params![meeting_id],
+    )?;
+    conn.execute("DELETE FROM segments WHERE meeting_id = ?1", params![meeting_id])?;
+    conn.execute("DELETE FROM meeting_tags WHERE meeting_id = ?1", params![meeting_id])?;
+    conn.execute("DELETE FROM meetings WHERE id = ?1", params![meeting_id])?;
+    conn.execute(
+        "DELETE FROM unreadable_meetings WHERE meeting_id = ?1",
+        params![meeting_id],
+    )?;
+    Ok(())
+}
+
+impl ToSql for MeetingId {
+    fn to_sql(&self) -> rusqlite::Result<ToSqlOutput<'_>> {
+        Ok(ToSqlOutput::from(self.as_str()))
+    }
+}
+
+impl FromSql for MeetingId {
+    fn column_result(value: ValueRef<'_>) -> FromSqlResult<Self> {
+        MeetingId::parse(value.as_str()?).map_err(|e| FromSqlError::Other(Box::new(e)))
+    }
+}
+
+fn read_summary(row: &rusqlite::Row<'_>) -> rusqlite::Result<MeetingSummary> {
+    Ok(MeetingSummary {
+        id: row.get(0)?,
+        title: row.get(1)?,
+        started_at: from_millis(row.get(2)?),
+        ended_at: row.get::<_, Option<i64>>(3)?.map(from_millis),
+        duration_sec: row.get(4)?,
+        tags: Vec::new(),
+        snippet: row.get(5)?,
+    })
+}
+
+fn from_millis(millis: i64) -> DateTime<Utc> {
+    DateTime::from_timestamp_millis(millis).unwrap_or_default()
+}
+
+fn to_sql_count(count: usize) -> i64 {
+    count.min(i64::MAX as usize) as i64
+}
+
+fn where_clause(conditions: &[String]) -> String {
+    if conditions.is_empty() {
+        String::new()
+    } else {
+        format!("WHERE {}", conditions.join(" AND "))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::storage::testing::{self, at, meeting_id, temp_storage, TempDir};
+
+    /// 末尾が `n`（16進数1桁）のIDで、開始の10分後に終了した会議
+    fn meeting(n: u32, start: i64, tags: &[&str], lines: &[(&str, &str)]) -> MeetingData {
+        let mut meeting = testing::meeting(meeting_id(n), &format!("会議{:x}", n), lines);
+        meeting.started_at = at(start);
+        meeting.ended_at = Some(at(start + 600));
+        meeting.tags = tags.iter().map(|t| t.to_string()).collect();
+        meeting
+    }
+
+    fn search(storage: &MeetingStorage, text: &str) -> Vec<SearchResult> {
+        storage
+            .search_meetings(&SearchQuery {
+                text: text.to_string(),
+                ..Default::default()
+            })
+            .unwrap()
+    }
+
+    fn ids(results: &[SearchResult]) -> Vec<&str> {
+        results.iter().map(|r| &r.meeting.id.as_str()[35..]).collect()
+    }
+
+    fn setup() -> (MeetingStorage, TempDir) {
+        let (storage, dir) = temp_storage("index");
+        storage
+            .save_meeting(&meeting(
+                0xa,
+                0,
+                &["定例会", "予算"],
+                &[
+                    ("田中", "来年度の予算案について説明します"),
+                    ("佐藤", "ＡＰＩの移行は来月です"),
+                ],
+            ))
+            .unwrap();
+        storage
+            .save_meeting(&meeting(
+                0xb,
+                1000,
+                &["防災"],
+                &[("田中", "防災訓練の予算、算定は五万円です")],
+            ))
+            .unwrap();
+        (storage, dir)
+    }
+
+    #[test]
+    fn test_search_japanese_with_highlights() {
+        let (storage, _dir) = setup();
+
+        let results = search(&storage, "予算");
+        assert_eq!(ids(&results), ["b", "a"]);
+        let hit = &results[1].hits[0];
+        assert_eq!(hit.timestamp, 0.0);
+        assert_eq!(hit.speaker.as_deref(), Some("田中"));
+        assert_eq!(
+            hit.snippet,
+            vec![
+                SnippetPart { text: "来年度の".into(), highlighted: false },
+                SnippetPart { text: "予算".into(), highlighted: true },
+                SnippetPart { text: "案について説明します".into(), highlighted: false },
+            ]
+        );
+
+        // 記号をまたいで2-gramが並んだだけの偶然の一致はヒットにしない
+        assert_eq!(ids(&search(&storage, "予算案")), ["a"]);
+        assert!(search(&storage, "予算定").is_empty());
+        // 全角・半角、大文字・小文字を区別しない
+        assert_eq!(search(&storage, "api")[0].hits[0].timestamp, 10.0);
+        // 1文字の語と、すべての語を含む会議への絞り込み
+        assert_eq!(ids(&search(&storage, "訓")), ["b"]);
+        assert_eq!(ids(&search(&storage, "予算 来月")), ["a"]);
+    }
+
+    #[test]
+    fn test_filters_and_sync_on_delete() {
+        let (storage, _dir) = setup();
+
+        let by_tag = storage
+            .search_meetings(&SearchQuery {
+                text: "予算".to_string(),
+                tags: vec!["定例会".to_string()],
+                ..Default::default()
+            })
+            .unwrap();
+        assert_eq!(ids(&by_tag), ["a"]);
+
+        let by_speaker = storage
+            .search_meetings(&SearchQuery {
+                speaker: Some("佐藤".to_string()),
+                ..Default::default()
+            })
+            .unwrap();
+        assert_eq!(ids(&by_speaker), ["a"]);
+        assert_eq!(by_speaker[0].hit_count, 1);
+
+        let by_date = storage
+            .search_meetings(&SearchQuery {
+                from: Some(at(500)),
+                ..Default::default()
+            })
+            .unwrap();
+        assert_eq!(ids(&by_date), ["b"]);
+        assert_eq!(by_date[0].meeting.tags, ["防災"]);
+
+        storage.delete_meeting(&meeting_id(0xb)).unwrap();
+        assert_eq!(ids(&search(&storage, "予算")), ["a"]);
+
+        // 索引を失っても meeting.json から作り直される
+        storage.discard_index().unwrap();
+        assert_eq!(ids(&search(&storage, "予算")), ["a"]);
+    }
+
+    #[test]
+    fn test_failed_update_rebuilds_on_next_open() {
+        let (storage, _dir) = setup();
+
+        // 索引の更新が失敗する状態にする（バージョンは現在のまま）
+        let conn = Connection::open(storage.index_path()).unwrap();
+        conn.execute_batch("DROP TABLE segments;").unwrap();
+        drop(conn);
+
+        let mut updated = meeting(0xa, 0, &[], &[("田中", "議事録を確認します")]);
+        updated.title = "変更後".to_string();
+        storage.save_meeting(&updated).unwrap();
+
+        // 失敗した更新は次に開いたときに meeting.json から反映される
+        let results = search(&storage, "議事録");
+        assert_eq!(ids(&results), ["a"]);
+        assert_eq!(results[0].meeting.title, "変更後");
+    }
+
+    #[test]
+    fn test_list_picks_up_meeting_json_changed_outside() {
+        let (storage, dir) = setup();
+        let json_path = dir.join(meeting_id(0xa).as_str()).join("meeting.json");
+        let list = |storage: &MeetingStorage| {
+            storage.list_meeting_summaries(MeetingSort::NewestFirst, 0, 10).unwrap()
+        };
+        assert_eq!(list(&storage).total, 2);
+
+        // 索引を作った後で壊れた meeting.json は、読み込めない会議として一覧から外れる
+        std::fs::write(&json_path, "{").unwrap();
+        let page = list(&storage);
+        assert_eq!(page.total, 1);
+        assert_eq!(page.meetings[0].id, meeting_id(0xb));
+        assert_eq!(page.unreadable.len(), 1);
+        assert_eq!(page.unreadable[0].meeting_id, meeting_id(0xa));
+        assert!(search(&storage, "来年度").is_empty());
+
+        // 直されれば一覧に戻る
+        let mut fixed = meeting(0xa, 0, &[], &[("田中", "来年度の予算案")]);
+        fixed.title = "直した会議".to_string();
+        std::fs::write(&json_path, serde_json::to_string(&fixed).unwrap()).unwrap();
+        let page = list(&storage);
+        assert_eq!(page.total, 2);
+        assert!(page.unreadable.is_empty());
+        assert_eq!(search(&storage, "来年度")[0].meeting.title, "直した会議");
+    }
+}
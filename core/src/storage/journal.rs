@@ -0,0 +1,245 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::fs::{File, OpenOptions};
+use std::io::{BufRead, BufReader, Write};
+use std::path::Path;
+use std::time::{Duration, Instant};
+
+use super::meeting_storage::{MeetingData, Result, StorageError, TranscriptSegment};
+use super::{MeetingId, PauseInterval, MEETING_SCHEMA_VERSION};
+
+/// 既定でディスクへ同期する間隔
+const DEFAULT_SYNC_INTERVAL: Duration = Duration::from_secs(2);
+
+/// ジャーナルの1行（会議中に起きた出来事）
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "event", rename_all = "snake_case")]
+pub enum JournalEntry {
+    /// 会議を開始した
+    Started {
+        meeting_id: MeetingId,
+        title: String,
+        started_at: DateTime<Utc>,
+    },
+    /// 文字起こしセグメントが確定した
+    Segment(TranscriptSegment),
+    /// 一時停止した（`audio_position` は音声上の位置（秒））
+    Paused {
+        at: DateTime<Utc>,
+        audio_position: f64,
+    },
+    /// 再開した
+    Resumed { at: DateTime<Utc> },
+    /// 会議を終了した
+    Stopped { at: DateTime<Utc> },
+}
+
+/// 会議中の出来事を追記していくJSONLジャーナル
+///
+/// 1行ずつ追記し、一定間隔でfsyncする。クラッシュしても直近の同期までの内容から
+/// `replay` で会議を組み立て直せる
+pub struct MeetingJournal {
+    file: File,
+    sync_interval: Duration,
+    last_sync: Instant,
+    dirty: bool,
+}
+
+impl MeetingJournal {
+    /// ジャーナルを開く（なければ作成し、あれば末尾に追記する）
+    pub fn open(path: impl AsRef<Path>) -> Result<Self> {
+        if let Some(parent) = path.as_ref().parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        let file = OpenOptions::new().create(true).append(true).open(path)?;
+
+        Ok(MeetingJournal {
+            file,
+            sync_interval: DEFAULT_SYNC_INTERVAL,
+            last_sync: Instant::now(),
+            dirty: false,
+        })
+    }
+
+    /// ディスクへ同期する間隔を変更
+    pub fn with_sync_interval(mut self, interval: Duration) -> Self {
+        self.sync_interval = interval;
+        self
+    }
+
+    /// 1件追記する（前回の同期から間隔が空いていれば同期する）
+    pub fn append(&mut self, entry: &JournalEntry) -> Result<()> {
+        let mut line = serde_json::to_vec(entry)?;
+        line.push(b'\n');
+        // 1回のwriteで行全体を書き込み、途中で切れた行が混ざりにくくする
+        self.file.write_all(&line)?;
+        self.dirty = true;
+        self.sync_if_due()
+    }
+
+    /// 未同期の追記があり、間隔が経過していれば同期する
+    pub fn sync_if_due(&mut self) -> Result<()> {
+        if self.dirty && self.last_sync.elapsed() >= self.sync_interval {
+            self.sync()?;
+        }
+        Ok(())
+    }
+
+    /// 追記した内容をディスクへ同期する
+    pub fn sync(&mut self) -> Result<()> {
+        self.file.sync_data()?;
+        self.last_sync = Instant::now();
+        self.dirty = false;
+        Ok(())
+    }
+}
+
+/// ジャーナルを読み込む
+///
+/// クラッシュで途中まで書かれた行や壊れた行は読み飛ばす
+pub fn read_journal(path: impl AsRef<Path>) -> Result<Vec<JournalEntry>> {
+    let reader = BufReader::new(File::open(path)?);
+    let mut entries = Vec::new();
+
+    for (index, line) in reader.split(b'\n').enumerate() {
+        let line = line?;
+        if line.iter().all(u8::is_ascii_whitespace) {
+            continue;
+        }
+        match serde_json::from_slice(&line) {
+            Ok(entry) => entries.push(entry),
+            Err(e) => eprintln!("[Storage] ジャーナル{}行目を読み飛ばします: {}", index + 1, e),
+        }
+    }
+
+    Ok(entries)
+}
+
+/// ジャーナルの内容から会議データを組み立てる
+///
+/// 終了の記録がなければ `ended_at` はNoneのままにする
+pub fn replay(entries: &[JournalEntry]) -> Result<MeetingData> {
+    let mut meeting = match entries.first() {
+        Some(JournalEntry::Started {
+            meeting_id,
+            title,
+            started_at,
+        }) => MeetingData {
+            schema_version: MEETING_SCHEMA_VERSION,
+            id: meeting_id.clone(),
+            title: title.clone(),
+            started_at: *started_at,
+            ended_at: None,
+            transcript: Vec::new(),
+            summary: None,
+            audio_file: None,
+            pauses: Vec::new(),
+            tags: Vec::new(),
+        },
+        _ => {
+            return Err(StorageError::Corrupted(
+                "Journal does not start with a meeting".to_string(),
+            ))
+        }
+    };
+
+    for entry in &entries[1..] {
+        match entry {
+            JournalEntry::Started { .. } => {}
+            JournalEntry::Segment(segment) => meeting.transcript.push(segment.clone()),
+            JournalEntry::Paused { at, audio_position } => meeting.pauses.push(PauseInterval {
+                paused_at: *at,
+                resumed_at: None,
+                audio_position: *audio_position,
+            }),
+            JournalEntry::Resumed { at } => {
+                if let Some(pause) = meeting.pauses.last_mut().filter(|p| p.resumed_at.is_none()) {
+                    pause.resumed_at = Some(*at);
+                }
+            }
+            JournalEntry::Stopped { at } => {
+                if let Some(pause) = meeting.pauses.last_mut().filter(|p| p.resumed_at.is_none()) {
+                    pause.resumed_at = Some(*at);
+                }
+                meeting.ended_at = Some(*at);
+            }
+        }
+    }
+
+    Ok(meeting)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::storage::testing::{at, TempDir};
+
+    fn segment(timestamp: f64, text: &str) -> JournalEntry {
+        JournalEntry::Segment(TranscriptSegment {
+            timestamp,
+            text: text.to_string(),
+            speaker: None,
+        })
+    }
+
+    #[test]
+    fn test_replay_after_truncated_write() {
+        let dir = TempDir::new("journal");
+        let path = dir.join("journal.jsonl");
+        let meeting_id = MeetingId::new();
+        let mut journal = MeetingJournal::open(&path).unwrap();
+        for entry in [
+            JournalEntry::Started {
+                meeting_id: meeting_id.clone(),
+                title: "定例".to_string(),
+                started_at: at(0),
+            },
+            segment(1.0, "おはようございます"),
+            JournalEntry::Paused {
+                at: at(10),
+                audio_position: 10.0,
+            },
+            JournalEntry::Resumed { at: at(20) },
+            segment(12.0, "再開します"),
+        ] {
+            journal.append(&entry).unwrap();
+        }
+        journal.sync().unwrap();
+        drop(journal);
+
+        // クラッシュで最後の行が途中までしか書かれていない状態を再現する
+        let mut file = OpenOptions::new().append(true).open(&path).unwrap();
+        file.write_all(br#"{"event":"segment","timesta"#).unwrap();
+        drop(file);
+
+        let entries = read_journal(&path).unwrap();
+        assert_eq!(entries.len(), 5);
+
+        let meeting = replay(&entries).unwrap();
+        assert_eq!(meeting.id, meeting_id);
+        assert_eq!(meeting.transcript.len(), 2);
+        assert_eq!(meeting.pauses[0].resumed_at, Some(at(20)));
+        assert!(meeting.ended_at.is_none());
+    }
+
+    #[test]
+    fn test_replay_stopped_while_paused() {
+        let entries = [
+            JournalEntry::Started {
+                meeting_id: MeetingId::new(),
+                title: "t".to_string(),
+                started_at: at(0),
+            },
+            JournalEntry::Paused {
+                at: at(5),
+                audio_position: 5.0,
+            },
+            JournalEntry::Stopped { at: at(9) },
+        ];
+        let meeting = replay(&entries).unwrap();
+        assert_eq!(meeting.ended_at, Some(at(9)));
+        assert_eq!(meeting.pauses[0].resumed_at, Some(at(9)));
+
+        assert!(replay(&entries[1..]).is_err());
+    }
+}
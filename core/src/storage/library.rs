@@ -0,0 +1,287 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::hash::{DefaultHasher, Hash, Hasher};
+use std::time::SystemTime;
+
+use super::meeting_storage::{MeetingData, MeetingStorage, Result, StorageError, TranscriptSegment};
+use super::schema::{MeetingLoad, UnreadableMeeting};
+use super::MeetingId;
+
+/// 一覧のスニペットの最大文字数
+const SNIPPET_CHARS: usize = 120;
+
+/// 会議一覧の1件（会議の概要）
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MeetingSummary {
+    pub id: MeetingId,
+    pub title: String,
+    pub started_at: DateTime<Utc>,
+    pub ended_at: Option<DateTime<Utc>>,
+    /// 録音していた時間（秒、一時停止を除く）。終了していなければNone
+    pub duration_sec: Option<f64>,
+    pub tags: Vec<String>,
+    /// 要約（なければ文字起こし）の冒頭
+    pub snippet: String,
+}
+
+impl MeetingSummary {
+    pub fn from_meeting(meeting: &MeetingData) -> Self {
+        let duration_sec = meeting
+            .ended_at
+            .map(|ended_at| meeting.timeline().active_sec(ended_at));
+
+        let source = match meeting.summary.as_deref().map(str::trim) {
+            Some(summary) if !summary.is_empty() => summary.to_string(),
+            _ => meeting
+                .transcript
+                .iter()
+                .map(|segment| segment.text.trim())
+                .collect::<Vec<_>>()
+                .join(" "),
+        };
+
+        MeetingSummary {
+            id: meeting.id.clone(),
+            title: meeting.title.clone(),
+            started_at: meeting.started_at,
+            ended_at: meeting.ended_at,
+            duration_sec,
+            tags: meeting.tags.clone(),
+            snippet: truncate_chars(&source, SNIPPET_CHARS),
+        }
+    }
+}
+
+/// 会議一覧の並び順
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum MeetingSort {
+    /// 開始日時の新しい順
+    #[default]
+    NewestFirst,
+    /// 開始日時の古い順
+    OldestFirst,
+    /// タイトル順
+    Title,
+    /// 録音時間の長い順
+    LongestFirst,
+}
+
+/// 会議一覧の1ページ
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MeetingPage {
+    pub meetings: Vec<MeetingSummary>,
+    /// ページ分割前の件数
+    pub total: usize,
+    /// meeting.json を読み込めず一覧に含められなかった会議
+    pub unreadable: Vec<UnreadableMeeting>,
+}
+
+/// 会議の編集内容（Noneの項目は変更しない）
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct MeetingUpdate {
+    pub title: Option<String>,
+    /// 空文字なら要約を削除する
+    pub summary: Option<String>,
+    pub transcript: Option<Vec<TranscriptSegment>>,
+    pub tags: Option<Vec<String>>,
+}
+
+impl MeetingUpdate {
+    /// 会議データに編集内容を反映
+    pub fn apply(self, meeting: &mut MeetingData) {
+        if let Some(title) = self.title {
+            meeting.title = title.trim().to_string();
+        }
+        if let Some(summary) = self.summary {
+            meeting.summary = Some(summary).filter(|s| !s.trim().is_empty());
+        }
+        if let Some(mut transcript) = self.transcript {
+            transcript.sort_by(|a, b| a.timestamp.total_cmp(&b.timestamp));
+            meeting.transcript = transcript;
+        }
+        if let Some(tags) = self.tags {
+            let mut normalized: Vec<String> = Vec::new();
+            for tag in tags.iter().map(|t| t.trim()).filter(|t| !t.is_empty()) {
+                if !normalized.iter().any(|existing| existing == tag) {
+                    normalized.push(tag.to_string());
+                }
+            }
+            meeting.tags = normalized;
+        }
+    }
+}
+
+impl MeetingStorage {
+    /// 会議の概要を並べ替えて `offset` 件目から最大 `limit` 件取得
+    ///
+    /// 検索インデックスから取得し、使えなければ各会議の meeting.json を読む。
+    /// meeting.json がない（録音中・異常終了した）会議や読み込めない会議は含めない
+    pub fn list_meeting_summaries(
+        &self,
+        sort: MeetingSort,
+        offset: usize,
+        limit: usize,
+    ) -> Result<MeetingPage> {
+        match self.list_indexed_summaries(sort, offset, limit) {
+            Ok(page) => return Ok(page),
+            Err(e) => eprintln!("[Storage] 検索インデックスを使えません: {}", e),
+        }
+
+        Ok(summarize(self.load_all_meetings()?, sort, offset, limit))
+    }
+
+    /// 会議フォルダの合計サイズと更新状況（フォルダがなければ `StorageError::NotFound`）
+    pub fn meeting_footprint(&self, meeting_id: &MeetingId) -> Result<MeetingFootprint> {
+        fn visit(path: &std::path::Path, footprint: &mut MeetingFootprint) -> std::io::Result<()> {
+            for entry in std::fs::read_dir(path)? {
+                let entry = entry?;
+                let metadata = entry.metadata()?;
+                if metadata.is_dir() {
+                    visit(&entry.path(), footprint)?;
+                    continue;
+                }
+                footprint.size_bytes += metadata.len();
+                footprint.files += 1;
+                let modified = metadata.modified().ok();
+                footprint.modified = footprint.modified.max(modified);
+            }
+            Ok(())
+        }
+
+        let meeting_dir = self.base_dir().join(meeting_id.as_str());
+        if !meeting_dir.is_dir() {
+            return Err(StorageError::NotFound(meeting_id.to_string()));
+        }
+        let mut footprint = MeetingFootprint::default();
+        visit(&meeting_dir, &mut footprint)?;
+        Ok(footprint)
+    }
+}
+
+/// 会議の保存内容の合計サイズと更新状況（削除の確認に使う）
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct MeetingFootprint {
+    /// 合計サイズ（バイト）
+    pub size_bytes: u64,
+    /// ファイル数
+    pub files: u64,
+    /// 最も新しいファイルの更新日時
+    pub modified: Option<SystemTime>,
+}
+
+impl MeetingFootprint {
+    /// 保存内容が変わると変わる確認用のトークン
+    pub fn token(&self) -> String {
+        let mut hasher = DefaultHasher::new();
+        (self.size_bytes, self.files, self.modified).hash(&mut hasher);
+        format!("{:016x}", hasher.finish())
+    }
+}
+
+/// 読み込んだ会議の概要を並べ替えて `offset` 件目から最大 `limit` 件取り出す
+pub(super) fn summarize(load: MeetingLoad, sort: MeetingSort, offset: usize, limit: usize) -> MeetingPage {
+    let mut summaries: Vec<MeetingSummary> =
+        load.meetings.iter().map(MeetingSummary::from_meeting).collect();
+
+    summaries.sort_by(|a, b| match sort {
+        MeetingSort::NewestFirst => b.started_at.cmp(&a.started_at),
+        MeetingSort::OldestFirst => a.started_at.cmp(&b.started_at),
+        MeetingSort::Title => a.title.cmp(&b.title).then(b.started_at.cmp(&a.started_at)),
+        MeetingSort::LongestFirst => b
+            .duration_sec
+            .unwrap_or(0.0)
+            .total_cmp(&a.duration_sec.unwrap_or(0.0))
+            .then(b.started_at.cmp(&a.started_at)),
+    });
+
+    let total = summaries.len();
+    let meetings = summaries.into_iter().skip(offset).take(limit).collect();
+    MeetingPage {
+        meetings,
+        total,
+        unreadable: load.unreadable,
+    }
+}
+
+fn truncate_chars(text: &str, max_chars: usize) -> String {
+    match text.char_indices().nth(max_chars) {
+        Some((index, _)) => format!("{}…", &text[..index]),
+        None => text.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::storage::testing::{self, at, meeting_id as id, temp_storage};
+
+    /// `start` 秒に始まり `length` 秒で終了した、発言が長い会議
+    fn meeting(id: MeetingId, title: &str, start: i64, length: i64) -> MeetingData {
+        let mut meeting = testing::meeting(id, title, &[("", &"あ".repeat(200))]);
+        meeting.started_at = at(start);
+        meeting.ended_at = Some(at(start + length));
+        meeting
+    }
+
+    #[test]
+    fn test_list_sorted_and_paginated() {
+        let (storage, dir) = temp_storage("library");
+        storage.save_meeting(&meeting(id(1), "定例会", 0, 600)).unwrap();
+        storage.save_meeting(&meeting(id(2), "臨時会議", 100, 1800)).unwrap();
+        storage.save_meeting(&meeting(id(3), "防災", 200, 60)).unwrap();
+        // meeting.json のない会議は一覧に含めない
+        std::fs::create_dir_all(dir.join(MeetingId::new().as_str())).unwrap();
+
+        let page = storage.list_meeting_summaries(MeetingSort::NewestFirst, 0, 2).unwrap();
+        assert_eq!(page.total, 3);
+        let ids: Vec<_> = page.meetings.iter().map(|m| m.id.clone()).collect();
+        assert_eq!(ids, [id(3), id(2)]);
+
+        let page = storage.list_meeting_summaries(MeetingSort::LongestFirst, 1, 10).unwrap();
+        let ids: Vec<_> = page.meetings.iter().map(|m| m.id.clone()).collect();
+        assert_eq!(ids, [id(1), id(3)]);
+        assert_eq!(page.meetings[0].duration_sec, Some(600.0));
+        assert_eq!(page.meetings[0].snippet.chars().count(), SNIPPET_CHARS + 1);
+    }
+
+    #[test]
+    fn test_footprint_token_changes_with_contents() {
+        let (storage, dir) = temp_storage("library");
+        storage.save_meeting(&meeting(id(1), "定例会", 0, 600)).unwrap();
+
+        let before = storage.meeting_footprint(&id(1)).unwrap();
+        assert_eq!(before, storage.meeting_footprint(&id(1)).unwrap());
+        assert!(before.size_bytes > 0);
+
+        std::fs::write(dir.join(id(1).as_str()).join("audio.wav"), [0u8; 44]).unwrap();
+        let after = storage.meeting_footprint(&id(1)).unwrap();
+        assert_eq!(after.size_bytes, before.size_bytes + 44);
+        assert_ne!(after.token(), before.token());
+        assert!(storage.meeting_footprint(&id(2)).is_err());
+    }
+
+    #[test]
+    fn test_update_applies_only_given_fields() {
+        let mut data = meeting(id(1), "定例会", 0, 600);
+        data.summary = Some("予算を確定".to_string());
+
+        MeetingUpdate {
+            title: Some("  12月定例会 ".to_string()),
+            tags: Some(vec!["予算".into(), " 予算".into(), "".into(), "防災".into()]),
+            ..Default::default()
+        }
+        .apply(&mut data);
+        assert_eq!(data.title, "12月定例会");
+        assert_eq!(data.tags, ["予算", "防災"]);
+        assert_eq!(data.summary.as_deref(), Some("予算を確定"));
+        assert_eq!(MeetingSummary::from_meeting(&data).snippet, "予算を確定");
+
+        MeetingUpdate {
+            summary: Some(" ".to_string()),
+            ..Default::default()
+        }
+        .apply(&mut data);
+        assert!(data.summary.is_none());
+    }
+}
@@ -0,0 +1,126 @@
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::str::FromStr;
+use uuid::Uuid;
+
+use super::meeting_storage::{Result, StorageError};
+
+/// 会議ID（ハイフン区切りのUUID）
+///
+/// 会議フォルダ名としてそのまま保存先ディレクトリに連結するため、
+/// `..` や区切り文字を含む値はここで拒否し、検証済みの値だけをストレージAPIに渡す
+#[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(try_from = "String", into = "String")]
+pub struct MeetingId(String);
+
+impl MeetingId {
+    /// 新しい会議IDを発行
+    pub fn new() -> Self {
+        MeetingId(Uuid::new_v4().hyphenated().to_string())
+    }
+
+    /// 文字列を検証して会議IDにする（大文字は小文字にそろえる）
+    pub fn parse(value: &str) -> Result<Self> {
+        // Uuid::parse_str は波括弧・urn・ハイフンなしの形式も受け付けるため長さで絞る
+        match Uuid::try_parse(value) {
+            Ok(uuid) if value.len() == 36 => Ok(MeetingId(uuid.hyphenated().to_string())),
+            _ => Err(StorageError::InvalidPath(format!(
+                "Invalid meeting ID: {:?}",
+                value
+            ))),
+        }
+    }
+
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+}
+
+impl Default for MeetingId {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl fmt::Display for MeetingId {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+impl AsRef<str> for MeetingId {
+    fn as_ref(&self) -> &str {
+        &self.0
+    }
+}
+
+impl FromStr for MeetingId {
+    type Err = StorageError;
+
+    fn from_str(value: &str) -> Result<Self> {
+        Self::parse(value)
+    }
+}
+
+impl TryFrom<String> for MeetingId {
+    type Error = StorageError;
+
+    fn try_from(value: String) -> Result<Self> {
+        Self::parse(&value)
+    }
+}
+
+impl From<MeetingId> for String {
+    fn from(id: MeetingId) -> Self {
+        id.0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_accepts_uuid_and_normalizes_case() {
+        let id = MeetingId::new();
+        assert_eq!(MeetingId::parse(id.as_str()).unwrap(), id);
+
+        let upper = MeetingId::parse("6F9619FF-8B86-4011-B42D-00C04FC964FF").unwrap();
+        assert_eq!(upper.as_str(), "6f9619ff-8b86-4011-b42d-00c04fc964ff");
+    }
+
+    #[test]
+    fn test_rejects_hostile_input() {
+        for hostile in [
+            "",
+            ".",
+            "..",
+            "../../",
+            "../6f9619ff-8b86-4011-b42d-00c04fc964ff",
+            "6f9619ff-8b86-4011-b42d-00c04fc964ff/..",
+            "6f9619ff-8b86-4011-b42d-00c04fc964ff/../../etc",
+            "/etc/passwd",
+            "C:\\Windows",
+            "..\\..\\",
+            "6f9619ff-8b86-4011-b42d-00c04fc964f\0",
+            "{6f9619ff-8b86-4011-b42d-00c04fc964ff}",
+            "urn:uuid:6f9619ff-8b86-4011-b42d-00c04fc964ff",
+            "6f9619ff8b864011b42d00c04fc964ff",
+            " 6f9619ff-8b86-4011-b42d-00c04fc964ff",
+            "６f9619ff-8b86-4011-b42d-00c04fc964f",
+        ] {
+            assert!(
+                matches!(MeetingId::parse(hostile), Err(StorageError::InvalidPath(_))),
+                "{:?} was accepted",
+                hostile
+            );
+        }
+    }
+
+    #[test]
+    fn test_deserialize_validates() {
+        let ok: MeetingId = serde_json::from_str("\"6f9619ff-8b86-4011-b42d-00c04fc964ff\"").unwrap();
+        assert_eq!(serde_json::to_string(&ok).unwrap(), "\"6f9619ff-8b86-4011-b42d-00c04fc964ff\"");
+        assert!(serde_json::from_str::<MeetingId>("\"../../\"").is_err());
+    }
+}
@@ -3,6 +3,13 @@ use std::path::{Path, PathBuf};
 use chrono::{DateTime, Utc};
 use thiserror::Error;
 
+use super::journal::{read_journal, replay, MeetingJournal};
+use super::schema::parse_meeting;
+use super::atomic::{backup_path, read_with_backup, write_atomic_validated};
+use super::repository::audio_file_name;
+use super::MeetingId;
+use super::{repair_wav_header, PauseInterval, PauseTimeline, StreamingAudioWriter};
+
 #[derive(Debug, Error)]
 pub enum StorageError {
     #[error("IO error: {0}")]
@@ -16,6 +23,15 @@ pub enum StorageError {
     
     #[error("Invalid path: {0}")]
     InvalidPath(String),
+
+    #[error("Corrupted data: {0}")]
+    Corrupted(String),
+
+    #[error("Index error: {0}")]
+    Index(#[from] rusqlite::Error),
+
+    #[error("Unsupported schema version {found} (supported up to {supported})")]
+    UnsupportedSchema { found: u32, supported: u32 },
 }
 
 pub type Result<T> = std::result::Result<T, StorageError>;
@@ -23,16 +39,32 @@ pub type Result<T> = std::result::Result<T, StorageError>;
 /// 会議データの保存構造
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct MeetingData {
-    pub id: String,
+    /// meeting.json の版（読み込み時に `MEETING_SCHEMA_VERSION` へ変換済み）
+    pub schema_version: u32,
+    pub id: MeetingId,
     pub title: String,
     pub started_at: DateTime<Utc>,
     pub ended_at: Option<DateTime<Utc>>,
     pub transcript: Vec<TranscriptSegment>,
     pub summary: Option<String>,
+    /// 音声ファイル名（会議ディレクトリからの相対パス）
     pub audio_file: Option<String>,
+    /// 一時停止していた区間（発生順）
+    #[serde(default)]
+    pub pauses: Vec<PauseInterval>,
+    /// 会議に付けたタグ
+    #[serde(default)]
+    pub tags: Vec<String>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+impl MeetingData {
+    /// 音声上の時刻（セグメントのタイムスタンプ）と実時刻の対応
+    pub fn timeline(&self) -> PauseTimeline<'_> {
+        PauseTimeline::new(self.started_at, &self.pauses)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct TranscriptSegment {
     pub timestamp: f64,
     pub text: String,
@@ -40,6 +72,7 @@ pub struct TranscriptSegment {
 }
 
 /// 会議データのストレージ管理
+#[derive(Debug, Clone)]
 pub struct MeetingStorage {
     base_dir: PathBuf,
 }
@@ -59,47 +92,78 @@ impl MeetingStorage {
 
     /// デフォルトのストレージディレクトリを使用
     pub fn default_location() -> Result<Self> {
-        let base_dir = directories::ProjectDirs::from("com", "gijiroku21", "Gijiroku21")
+        Self::new(Self::default_base_dir()?)
+    }
+
+    /// デフォルトのストレージディレクトリ（プラットフォームのデータディレクトリ配下）
+    pub fn default_base_dir() -> Result<PathBuf> {
+        Ok(directories::ProjectDirs::from("com", "gijiroku21", "Gijiroku21")
             .ok_or_else(|| StorageError::InvalidPath("Could not determine data directory".to_string()))?
             .data_dir()
-            .join("meetings");
+            .join("meetings"))
+    }
+
+    /// 設定の保存先ディレクトリを使用（未指定・空ならデフォルト）
+    pub fn from_setting(save_directory: Option<&str>) -> Result<Self> {
+        match save_directory.map(str::trim).filter(|dir| !dir.is_empty()) {
+            Some(dir) => Self::new(dir),
+            None => Self::default_location(),
+        }
+    }
 
-        Self::new(base_dir)
+    /// 会議フォルダを置くディレクトリ
+    pub fn base_dir(&self) -> &Path {
+        &self.base_dir
     }
 
     /// 会議ディレクトリのパスを取得
-    fn meeting_dir(&self, meeting_id: &str) -> PathBuf {
-        self.base_dir.join(meeting_id)
+    fn meeting_dir(&self, meeting_id: &MeetingId) -> PathBuf {
+        self.base_dir.join(meeting_id.as_str())
     }
 
-    /// 会議データを保存
+    /// 会議データを保存（書き込み途中で終了しても前回の内容が .bak に残る）
     pub fn save_meeting(&self, meeting: &MeetingData) -> Result<()> {
         let meeting_dir = self.meeting_dir(&meeting.id);
         std::fs::create_dir_all(&meeting_dir)?;
 
         let json_path = meeting_dir.join("meeting.json");
         let json_content = serde_json::to_string_pretty(meeting)?;
-        std::fs::write(json_path, json_content)?;
+        write_atomic_validated(json_path, json_content, |current| parse_meeting(current).is_ok())?;
 
+        self.index_meeting(meeting);
         Ok(())
     }
 
-    /// 会議データを読み込み
-    pub fn load_meeting(&self, meeting_id: &str) -> Result<MeetingData> {
+    /// 会議データを読み込み（meeting.json が壊れていればバックアップを読む）
+    pub fn load_meeting(&self, meeting_id: &MeetingId) -> Result<MeetingData> {
         let json_path = self.meeting_dir(meeting_id).join("meeting.json");
         
-        if !json_path.exists() {
+        if !json_path.exists() && !backup_path(&json_path).exists() {
             return Err(StorageError::NotFound(meeting_id.to_string()));
         }
 
-        let json_content = std::fs::read_to_string(json_path)?;
-        let meeting: MeetingData = serde_json::from_str(&json_content)?;
+        read_with_backup(json_path, parse_meeting)
+    }
 
-        Ok(meeting)
+    /// meeting.json が書き換えられたかを索引で見分けるための値（サイズと更新日時。なければ空）
+    pub(super) fn meeting_json_stamp(&self, meeting_id: &MeetingId) -> String {
+        let metadata = match std::fs::metadata(self.meeting_dir(meeting_id).join("meeting.json")) {
+            Ok(metadata) => metadata,
+            Err(_) => return String::new(),
+        };
+        let modified = metadata
+            .modified()
+            .ok()
+            .and_then(|time| time.duration_since(std::time::UNIX_EPOCH).ok())
+            .unwrap_or_default();
+        format!("{}:{}", metadata.len(), modified.as_nanos())
     }
 
     /// すべての会議IDを取得
-    pub fn list_meetings(&self) -> Result<Vec<String>> {
+    ///
+    /// 会議IDの形式でない名前のフォルダと、大文字を含むなど正規形でない名前のフォルダ
+    /// （IDからそのパスを引けない）は無視する
+    pub fn list_meetings(&self) -> Result<Vec<MeetingId>> {
         let mut meetings = Vec::new();
 
         if !self.base_dir.exists() {
@@ -108,10 +172,17 @@ impl MeetingStorage {
 
         for entry in std::fs::read_dir(&self.base_dir)? {
             let entry = entry?;
-            if entry.file_type()?.is_dir() {
-                if let Some(name) = entry.file_name().to_str() {
-                    meetings.push(name.to_string());
-                }
+            if !entry.file_type()?.is_dir() {
+                continue;
+            }
+            let file_name = entry.file_name();
+            let Some(name) = file_name.to_str() else {
+                continue;
+            };
+            match MeetingId::parse(name) {
+                Ok(meeting_id) if meeting_id.as_str() == name => meetings.push(meeting_id),
+                Ok(_) => eprintln!("[Storage] 会議IDが正規形でないフォルダを無視します: {}", name),
+                Err(_) => {}
             }
         }
 
@@ -119,23 +190,142 @@ impl MeetingStorage {
     }
 
     /// 会議を削除
-    pub fn delete_meeting(&self, meeting_id: &str) -> Result<()> {
+    pub fn delete_meeting(&self, meeting_id: &MeetingId) -> Result<()> {
         let meeting_dir = self.meeting_dir(meeting_id);
         
         if meeting_dir.exists() {
             std::fs::remove_dir_all(meeting_dir)?;
         }
 
+        self.unindex_meeting(meeting_id);
         Ok(())
     }
 
+    /// 会議中の出来事を追記するジャーナルのパスを取得
+    pub fn journal_path(&self, meeting_id: &MeetingId) -> PathBuf {
+        self.meeting_dir(meeting_id).join("journal.jsonl")
+    }
+
+    /// 会議のジャーナルを開く（なければ作成）
+    pub fn open_journal(&self, meeting_id: &MeetingId) -> Result<MeetingJournal> {
+        MeetingJournal::open(self.journal_path(meeting_id))
+    }
+
+    /// ジャーナルはあるが終了済みの meeting.json がない（異常終了した）会議のIDを取得
+    pub fn list_recoverable_meetings(&self) -> Result<Vec<MeetingId>> {
+        let mut recoverable = Vec::new();
+        for meeting_id in self.list_meetings()? {
+            if !self.journal_path(&meeting_id).is_file() {
+                continue;
+            }
+            let finalized = self
+                .load_meeting(&meeting_id)
+                .map(|meeting| meeting.ended_at.is_some())
+                .unwrap_or(false);
+            if !finalized {
+                recoverable.push(meeting_id);
+            }
+        }
+        recoverable.sort();
+        Ok(recoverable)
+    }
+
+    /// ジャーナルと書きかけの音声ファイルから会議を復元して meeting.json を保存
+    ///
+    /// 音声ファイルのヘッダを実際の長さに合わせて修復し、終了時刻の記録がなければ
+    /// 音声の長さ（一時停止を除く）から推定する
+    pub fn recover_meeting(&self, meeting_id: &MeetingId) -> Result<MeetingData> {
+        let journal_path = self.journal_path(meeting_id);
+        if !journal_path.is_file() {
+            return Err(StorageError::NotFound(meeting_id.to_string()));
+        }
+        let mut meeting = replay(&read_journal(journal_path)?)?;
+
+        for entry in std::fs::read_dir(self.meeting_dir(meeting_id))? {
+            let path = entry?.path();
+            if path.extension().is_some_and(|ext| ext == "wav") {
+                if let Err(e) = repair_wav_header(&path) {
+                    eprintln!("[Storage] 音声ファイルを修復できません ({}): {}", path.display(), e);
+                }
+            }
+        }
+
+        let audio_path = self.audio_file_path(meeting_id);
+        let audio_sec = hound::WavReader::open(&audio_path)
+            .ok()
+            .map(|reader| reader.duration() as f64 / reader.spec().sample_rate as f64);
+        if audio_sec.is_some() {
+            meeting.audio_file = audio_path
+                .file_name()
+                .map(|name| name.to_string_lossy().into_owned());
+        }
+
+        if meeting.ended_at.is_none() {
+            let last_event = meeting
+                .pauses
+                .iter()
+                .flat_map(|p| [Some(p.paused_at), p.resumed_at])
+                .flatten()
+                .max();
+            let audio_end = audio_sec.map(|sec| meeting.timeline().to_wall_clock(sec));
+            let ended_at = audio_end
+                .into_iter()
+                .chain(last_event)
+                .max()
+                .unwrap_or(meeting.started_at);
+
+            if let Some(pause) = meeting.pauses.last_mut().filter(|p| p.resumed_at.is_none()) {
+                pause.resumed_at = Some(ended_at);
+            }
+            meeting.ended_at = Some(ended_at);
+        }
+
+        self.save_meeting(&meeting)?;
+        Ok(meeting)
+    }
+
     /// 音声ファイルのパスを取得
-    pub fn audio_file_path(&self, meeting_id: &str) -> PathBuf {
-        self.meeting_dir(meeting_id).join("audio.wav")
+    pub fn audio_file_path(&self, meeting_id: &MeetingId) -> PathBuf {
+        self.meeting_dir(meeting_id).join(audio_file_name(None))
+    }
+
+    /// 録音中の音声を逐次書き込むライターを作成
+    pub fn create_audio_writer(&self, meeting_id: &MeetingId, sample_rate: u32) -> Result<StreamingAudioWriter> {
+        StreamingAudioWriter::create(self.audio_file_path(meeting_id), sample_rate)
+    }
+
+    /// 名前付きトラック（`audio_{track}.wav`）の音声ファイルパスを取得
+    pub fn track_audio_file_path(&self, meeting_id: &MeetingId, track: &str) -> PathBuf {
+        self.meeting_dir(meeting_id).join(audio_file_name(Some(track)))
+    }
+
+    /// 名前付きトラックを逐次書き込むライターを作成
+    pub fn create_track_audio_writer(
+        &self,
+        meeting_id: &MeetingId,
+        track: &str,
+        sample_rate: u32,
+    ) -> Result<StreamingAudioWriter> {
+        StreamingAudioWriter::create(self.track_audio_file_path(meeting_id, track), sample_rate)
+    }
+
+    /// チャンネル別トラックの音声ファイルパスを取得（`channel` は0始まり）
+    pub fn channel_audio_file_path(&self, meeting_id: &MeetingId, channel: usize) -> PathBuf {
+        self.track_audio_file_path(meeting_id, &format!("ch{}", channel + 1))
+    }
+
+    /// チャンネル別トラックを逐次書き込むライターを作成
+    pub fn create_channel_audio_writer(
+        &self,
+        meeting_id: &MeetingId,
+        channel: usize,
+        sample_rate: u32,
+    ) -> Result<StreamingAudioWriter> {
+        StreamingAudioWriter::create(self.channel_audio_file_path(meeting_id, channel), sample_rate)
     }
 
     /// 音声データを保存
-    pub fn save_audio(&self, meeting_id: &str, samples: &[f32], sample_rate: u32) -> Result<()> {
+    pub fn save_audio(&self, meeting_id: &MeetingId, samples: &[f32], sample_rate: u32) -> Result<()> {
         let audio_path = self.audio_file_path(meeting_id);
         let meeting_dir = self.meeting_dir(meeting_id);
         std::fs::create_dir_all(&meeting_dir)?;
@@ -171,6 +361,15 @@ impl MeetingStorage {
         
         if let Some(ended_at) = meeting.ended_at {
             md.push_str(&format!("**終了時刻**: {}\n\n", ended_at.format("%Y年%m月%d日 %H:%M")));
+            if !meeting.pauses.is_empty() {
+                let timeline = meeting.timeline();
+                md.push_str(&format!(
+                    "**録音時間**: {}（一時停止 {}回・計{}）\n\n",
+                    format_duration(timeline.active_sec(ended_at)),
+                    meeting.pauses.len(),
+                    format_duration(timeline.paused_sec(ended_at)),
+                ));
+            }
         }
 
         if let Some(summary) = &meeting.summary {
@@ -180,10 +379,25 @@ impl MeetingStorage {
         }
 
         md.push_str("## 文字起こし\n\n");
+        let timeline = meeting.timeline();
+        let end = meeting.ended_at.unwrap_or_else(Utc::now);
+        let mut previous = f64::NEG_INFINITY;
         for segment in &meeting.transcript {
-            let timestamp = format!("{:02}:{:02}", 
+            // 前のセグメントとの間の一時停止は、音声の途切れと区別できるよう明示する
+            for pause in timeline.pauses_between(previous, segment.timestamp) {
+                md.push_str(&format!(
+                    "*（{} から {} 一時停止）*\n\n",
+                    pause.paused_at.format("%H:%M:%S"),
+                    format_duration(pause.duration_sec(end)),
+                ));
+            }
+            previous = segment.timestamp;
+
+            // 音声上の位置と、一時停止を除いて換算した実時刻を併記する
+            let timestamp = format!("{:02}:{:02} {}",
                 (segment.timestamp / 60.0) as u32,
-                (segment.timestamp % 60.0) as u32
+                (segment.timestamp % 60.0) as u32,
+                timeline.to_wall_clock(segment.timestamp).format("%H:%M:%S"),
             );
             
             if let Some(speaker) = &segment.speaker {
@@ -196,3 +410,101 @@ impl MeetingStorage {
         Ok(md)
     }
 }
+
+/// 秒数を「1時間2分3秒」の形式にする
+fn format_duration(seconds: f64) -> String {
+    let total = seconds.max(0.0).round() as u64;
+    let (hours, minutes, seconds) = (total / 3600, total / 60 % 60, total % 60);
+    if hours > 0 {
+        format!("{}時間{}分{}秒", hours, minutes, seconds)
+    } else if minutes > 0 {
+        format!("{}分{}秒", minutes, seconds)
+    } else {
+        format!("{}秒", seconds)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::storage::testing::{self, meeting_id, temp_storage};
+    use crate::storage::JournalEntry;
+
+    #[test]
+    fn test_recover_meeting_from_journal() {
+        let (storage, _dir) = temp_storage("storage");
+        let started_at = Utc::now() - chrono::Duration::seconds(60);
+        let meeting_id = MeetingId::new();
+
+        let mut journal = storage.open_journal(&meeting_id).unwrap();
+        journal
+            .append(&JournalEntry::Started {
+                meeting_id: meeting_id.clone(),
+                title: "障害テスト".to_string(),
+                started_at,
+            })
+            .unwrap();
+        journal
+            .append(&JournalEntry::Segment(TranscriptSegment {
+                timestamp: 0.5,
+                text: "こんにちは".to_string(),
+                speaker: None,
+            }))
+            .unwrap();
+        journal.sync().unwrap();
+
+        let mut writer = storage.create_audio_writer(&meeting_id, 16000).unwrap();
+        writer.write_samples(&vec![0.0; 32000]).unwrap();
+        writer.finalize().unwrap();
+
+        assert_eq!(storage.list_recoverable_meetings().unwrap(), vec![meeting_id.clone()]);
+
+        let meeting = storage.recover_meeting(&meeting_id).unwrap();
+        assert_eq!(meeting.transcript.len(), 1);
+        assert_eq!(meeting.audio_file.as_deref(), Some("audio.wav"));
+        assert_eq!(meeting.ended_at, Some(started_at + chrono::Duration::seconds(2)));
+
+        // 復元後は終了済みの会議として扱う
+        assert!(storage.list_recoverable_meetings().unwrap().is_empty());
+        assert_eq!(storage.load_meeting(&meeting_id).unwrap().title, "障害テスト");
+    }
+
+    #[test]
+    fn test_hostile_ids_never_reach_the_filesystem() {
+        let (storage, dir) = temp_storage("storage");
+        let meeting_id = MeetingId::new();
+        std::fs::create_dir_all(dir.join(meeting_id.as_str())).unwrap();
+        std::fs::create_dir_all(dir.join("notes")).unwrap();
+
+        // 会議IDの形式でないフォルダは会議として扱わない
+        assert_eq!(storage.list_meetings().unwrap(), vec![meeting_id]);
+
+        // Webviewから届いたIDはデシリアライズの時点で拒否される
+        let entry = r#"{"event":"started","meeting_id":"../../","title":"t","started_at":"2024-12-20T05:00:00Z"}"#;
+        assert!(serde_json::from_str::<JournalEntry>(entry).is_err());
+        assert!(serde_json::from_str::<MeetingId>(r#""..\\..\\Windows""#).is_err());
+    }
+
+    #[test]
+    fn test_uppercase_folder_is_not_listed() {
+        let (storage, dir) = temp_storage("storage");
+        let meeting = testing::meeting(meeting_id(1), "定例", &[]);
+        storage.save_meeting(&meeting).unwrap();
+
+        // 大文字のフォルダ名はIDにすると小文字になり、そのパスには会議がない
+        let upper = dir.join(meeting_id(0xabc).as_str().to_uppercase());
+        std::fs::create_dir_all(&upper).unwrap();
+        std::fs::write(
+            upper.join("meeting.json"),
+            serde_json::to_string(&testing::meeting(meeting_id(0xabc), "大文字", &[])).unwrap(),
+        )
+        .unwrap();
+
+        let listed = storage.list_meetings().unwrap();
+        assert_eq!(listed, vec![meeting_id(1)]);
+        for meeting_id in &listed {
+            storage.load_meeting(meeting_id).unwrap();
+            storage.meeting_footprint(meeting_id).unwrap();
+        }
+    }
+}
@@ -0,0 +1,227 @@
+use std::collections::{BTreeMap, BTreeSet};
+use std::sync::{Arc, Mutex};
+use std::time::SystemTime;
+
+use super::library::MeetingFootprint;
+use super::meeting_storage::{MeetingData, Result, StorageError};
+use super::repository::{audio_file_name, AudioSink, MeetingRepository};
+use super::MeetingId;
+use crate::audio::StreamingResampler;
+
+/// メモリ上に保存した音声
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct MemoryAudio {
+    pub samples: Vec<f32>,
+    pub sample_rate: u32,
+}
+
+type AudioMap = BTreeMap<(MeetingId, String), MemoryAudio>;
+/// 会議ごとの最終更新日時（削除の確認トークンに使う）
+type ModifiedMap = BTreeMap<MeetingId, SystemTime>;
+
+/// メモリ上に会議を保存するリポジトリ（テスト用）
+///
+/// ファイルには何も書かないため、録音・文字起こしの処理を
+/// ユーザーの保存先に触れずに最後まで動かして結果を確かめられる
+#[derive(Debug, Clone, Default)]
+pub struct MemoryMeetingRepository {
+    meetings: Arc<Mutex<BTreeMap<MeetingId, MeetingData>>>,
+    audio: Arc<Mutex<AudioMap>>,
+    modified: Arc<Mutex<ModifiedMap>>,
+}
+
+impl MemoryMeetingRepository {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// 保存された音声を取得（`flush` または `finalize` 済みの分まで）
+    pub fn audio(&self, meeting_id: &MeetingId, track: Option<&str>) -> Option<MemoryAudio> {
+        self.audio
+            .lock()
+            .unwrap()
+            .get(&(meeting_id.clone(), audio_file_name(track)))
+            .cloned()
+    }
+
+    fn touch(&self, meeting_id: &MeetingId) {
+        touch(&self.modified, meeting_id);
+    }
+}
+
+fn touch(modified: &Mutex<ModifiedMap>, meeting_id: &MeetingId) {
+    modified
+        .lock()
+        .unwrap()
+        .insert(meeting_id.clone(), SystemTime::now());
+}
+
+impl MeetingRepository for MemoryMeetingRepository {
+    fn save_meeting(&self, meeting: &MeetingData) -> Result<()> {
+        self.meetings
+            .lock()
+            .unwrap()
+            .insert(meeting.id.clone(), meeting.clone());
+        self.touch(&meeting.id);
+        Ok(())
+    }
+
+    fn load_meeting(&self, meeting_id: &MeetingId) -> Result<MeetingData> {
+        self.meetings
+            .lock()
+            .unwrap()
+            .get(meeting_id)
+            .cloned()
+            .ok_or_else(|| StorageError::NotFound(meeting_id.to_string()))
+    }
+
+    fn list_meetings(&self) -> Result<Vec<MeetingId>> {
+        let mut ids: BTreeSet<MeetingId> = self.meetings.lock().unwrap().keys().cloned().collect();
+        ids.extend(self.audio.lock().unwrap().keys().map(|(id, _)| id.clone()));
+        Ok(ids.into_iter().collect())
+    }
+
+    fn delete_meeting(&self, meeting_id: &MeetingId) -> Result<()> {
+        self.meetings.lock().unwrap().remove(meeting_id);
+        self.audio.lock().unwrap().retain(|(id, _), _| id != meeting_id);
+        self.modified.lock().unwrap().remove(meeting_id);
+        Ok(())
+    }
+
+    fn audio_file(&self, meeting_id: &MeetingId, track: Option<&str>) -> Option<String> {
+        let name = audio_file_name(track);
+        self.audio
+            .lock()
+            .unwrap()
+            .contains_key(&(meeting_id.clone(), name.clone()))
+            .then_some(name)
+    }
+
+    fn create_audio_sink(
+        &self,
+        meeting_id: &MeetingId,
+        track: Option<&str>,
+        sample_rate: u32,
+    ) -> Result<Box<dyn AudioSink>> {
+        let key = (meeting_id.clone(), audio_file_name(track));
+        // ファイルを作成した時点で空の音声ファイルが見えるのと同じように扱う
+        self.audio.lock().unwrap().insert(key.clone(), MemoryAudio {
+            samples: Vec::new(),
+            sample_rate,
+        });
+        self.touch(meeting_id);
+        Ok(Box::new(MemoryAudioSink {
+            audio: Arc::clone(&self.audio),
+            modified: Arc::clone(&self.modified),
+            key,
+            sample_rate,
+            samples: Vec::new(),
+            resampler: None,
+        }))
+    }
+
+    /// meeting.json に相当するJSONと、音声のサンプル（f32）の大きさを合計する
+    fn meeting_footprint(&self, meeting_id: &MeetingId) -> Result<MeetingFootprint> {
+        let mut footprint = MeetingFootprint::default();
+        if let Some(meeting) = self.meetings.lock().unwrap().get(meeting_id) {
+            footprint.size_bytes += serde_json::to_vec_pretty(meeting)?.len() as u64;
+            footprint.files += 1;
+        }
+        for ((id, _), audio) in self.audio.lock().unwrap().iter() {
+            if id == meeting_id {
+                footprint.size_bytes += (audio.samples.len() * std::mem::size_of::<f32>()) as u64;
+                footprint.files += 1;
+            }
+        }
+        if footprint.files == 0 {
+            return Err(StorageError::NotFound(meeting_id.to_string()));
+        }
+        footprint.modified = self.modified.lock().unwrap().get(meeting_id).copied();
+        Ok(footprint)
+    }
+}
+
+/// メモリ上の音声へ書き込む `AudioSink`
+///
+/// ファイルと同じく、書き込んだ音声は `flush` するまで読み出し側に見えない
+struct MemoryAudioSink {
+    audio: Arc<Mutex<AudioMap>>,
+    modified: Arc<Mutex<ModifiedMap>>,
+    key: (MeetingId, String),
+    sample_rate: u32,
+    samples: Vec<f32>,
+    resampler: Option<StreamingResampler>,
+}
+
+impl MemoryAudioSink {
+    fn flush_resampler(&mut self) {
+        if let Some(mut resampler) = self.resampler.take() {
+            let tail = resampler.flush();
+            self.samples.extend_from_slice(&tail);
+        }
+    }
+}
+
+impl AudioSink for MemoryAudioSink {
+    fn write_samples(&mut self, samples: &[f32]) -> Result<()> {
+        self.samples.extend_from_slice(samples);
+        Ok(())
+    }
+
+    fn write_at_rate(&mut self, samples: &[f32], input_rate: u32) -> Result<()> {
+        if input_rate == self.sample_rate {
+            self.flush_resampler();
+            return self.write_samples(samples);
+        }
+
+        if self.resampler.as_ref().map(|r| r.input_rate()) != Some(input_rate) {
+            self.flush_resampler();
+            self.resampler = Some(StreamingResampler::new(input_rate, self.sample_rate));
+        }
+        let converted = self.resampler.as_mut().unwrap().process(samples);
+        self.write_samples(&converted)
+    }
+
+    fn flush(&mut self) -> Result<()> {
+        self.audio.lock().unwrap().insert(self.key.clone(), MemoryAudio {
+            samples: self.samples.clone(),
+            sample_rate: self.sample_rate,
+        });
+        touch(&self.modified, &self.key.0);
+        Ok(())
+    }
+
+    fn finalize(mut self: Box<Self>) -> Result<u64> {
+        self.flush_resampler();
+        self.flush()?;
+        Ok(self.samples.len() as u64)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_audio_is_visible_after_flush_per_track() {
+        let repository = MemoryMeetingRepository::new();
+        let id = MeetingId::new();
+
+        let mut mix = repository.create_audio_sink(&id, None, 16000).unwrap();
+        let mut mic = repository.create_audio_sink(&id, Some("mic"), 16000).unwrap();
+        mix.write_samples(&[0.1; 160]).unwrap();
+        mic.write_samples(&[0.2; 320]).unwrap();
+        assert!(repository.audio(&id, None).unwrap().samples.is_empty());
+
+        mix.flush().unwrap();
+        assert_eq!(repository.audio(&id, None).unwrap().samples.len(), 160);
+        assert_eq!(mic.finalize().unwrap(), 320);
+        assert_eq!(repository.audio(&id, Some("mic")).unwrap().samples, vec![0.2; 320]);
+        assert_eq!(repository.audio_file(&id, Some("mic")).as_deref(), Some("audio_mic.wav"));
+
+        // 複製したリポジトリも同じ保存内容を共有する
+        let shared = repository.clone();
+        shared.delete_meeting(&id).unwrap();
+        assert!(repository.audio(&id, Some("mic")).is_none());
+    }
+}
@@ -0,0 +1,371 @@
+use serde::Serialize;
+use std::fs::File;
+use std::io::{BufReader, Read};
+use std::path::{Path, PathBuf};
+
+use super::meeting_storage::{MeetingStorage, Result, StorageError};
+use super::MeetingId;
+
+/// 保存先移行の段階
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum MigrationPhase {
+    /// 新しい保存先へコピー中
+    Copying,
+    /// コピーした内容を元と照合中
+    Verifying,
+    /// 元の会議フォルダを削除中
+    Removing,
+    /// 失敗したためコピーした分を削除中
+    RollingBack,
+}
+
+/// 保存先移行の進捗
+#[derive(Debug, Clone, Serialize)]
+pub struct MigrationProgress {
+    pub phase: MigrationPhase,
+    pub meeting_id: MeetingId,
+    /// この段階で処理を終えた会議の数
+    pub completed: usize,
+    pub total: usize,
+}
+
+/// 保存先移行の結果
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct MigrationReport {
+    /// 移行した会議のID
+    pub migrated: Vec<MeetingId>,
+    /// コピー・照合は済んだが元のフォルダを削除できなかった会議のID（両方に残っている）
+    pub not_removed: Vec<MeetingId>,
+    /// コピーしたバイト数
+    pub bytes_copied: u64,
+}
+
+/// すべての会議フォルダを別の保存先へ移す
+///
+/// 全件をコピーして内容を照合してから元を削除する。コピー・照合のどこかで失敗した場合は
+/// 新しい保存先にコピーした分を削除して元に戻し、元の保存先には手を付けない
+pub fn migrate_meetings(
+    from: &MeetingStorage,
+    to: &MeetingStorage,
+    mut progress: impl FnMut(&MigrationProgress),
+) -> Result<MigrationReport> {
+    let prepared = prepare_migration(from, to, &mut progress)?;
+    Ok(prepared.finish(from, to, progress))
+}
+
+/// コピー・照合まで済み、元の会議フォルダを残している移行
+///
+/// 保存先の設定を書き換えてから `finish` で元を削除する。設定を保存できなかった場合は
+/// `abort` でコピーした分を削除する
+#[derive(Debug)]
+pub struct PreparedMigration {
+    source_dir: PathBuf,
+    target_dir: PathBuf,
+    meetings: Vec<MeetingId>,
+    bytes_copied: u64,
+}
+
+/// すべての会議フォルダを別の保存先へコピーして照合する（元は削除しない）
+///
+/// 失敗した場合は新しい保存先にコピーした分を削除して元に戻す
+pub fn prepare_migration(
+    from: &MeetingStorage,
+    to: &MeetingStorage,
+    mut progress: impl FnMut(&MigrationProgress),
+) -> Result<PreparedMigration> {
+    let source_dir = from.base_dir().canonicalize()?;
+    let target_dir = to.base_dir().canonicalize()?;
+    if source_dir == target_dir {
+        return Ok(PreparedMigration {
+            source_dir,
+            target_dir,
+            meetings: Vec::new(),
+            bytes_copied: 0,
+        });
+    }
+    if target_dir.starts_with(&source_dir) {
+        return Err(StorageError::InvalidPath(format!(
+            "{} is inside the current save directory",
+            target_dir.display()
+        )));
+    }
+
+    let mut meetings = from.list_meetings()?;
+    meetings.sort();
+    if let Some(existing) = meetings.iter().find(|id| target_dir.join(id.as_str()).exists()) {
+        return Err(StorageError::InvalidPath(format!(
+            "Meeting {} already exists in {}",
+            existing,
+            target_dir.display()
+        )));
+    }
+
+    let total = meetings.len();
+    let mut bytes_copied = 0;
+    let mut copied = 0;
+
+    let result = (|| {
+        for (index, meeting_id) in meetings.iter().enumerate() {
+            progress(&MigrationProgress {
+                phase: MigrationPhase::Copying,
+                meeting_id: meeting_id.clone(),
+                completed: index,
+                total,
+            });
+            copied += 1;
+            bytes_copied += copy_dir(
+                &source_dir.join(meeting_id.as_str()),
+                &target_dir.join(meeting_id.as_str()),
+            )?;
+        }
+
+        for (index, meeting_id) in meetings.iter().enumerate() {
+            progress(&MigrationProgress {
+                phase: MigrationPhase::Verifying,
+                meeting_id: meeting_id.clone(),
+                completed: index,
+                total,
+            });
+            verify_dir(
+                &source_dir.join(meeting_id.as_str()),
+                &target_dir.join(meeting_id.as_str()),
+            )?;
+        }
+        Ok(())
+    })();
+
+    let prepared = PreparedMigration {
+        source_dir,
+        target_dir,
+        meetings,
+        bytes_copied,
+    };
+    if let Err(e) = result {
+        prepared.remove_copies(copied, progress);
+        return Err(e);
+    }
+    Ok(prepared)
+}
+
+impl PreparedMigration {
+    /// 移す会議のID
+    pub fn meetings(&self) -> &[MeetingId] {
+        &self.meetings
+    }
+
+    /// 元の会議フォルダを削除して移行を完了する
+    ///
+    /// 照合済みのため、削除に失敗しても新しい保存先のデータは完全（`not_removed` に記録する）
+    pub fn finish(
+        self,
+        from: &MeetingStorage,
+        to: &MeetingStorage,
+        mut progress: impl FnMut(&MigrationProgress),
+    ) -> MigrationReport {
+        let mut report = MigrationReport {
+            bytes_copied: self.bytes_copied,
+            ..Default::default()
+        };
+        if self.meetings.is_empty() {
+            return report;
+        }
+
+        let total = self.meetings.len();
+        for (index, meeting_id) in self.meetings.iter().enumerate() {
+            progress(&MigrationProgress {
+                phase: MigrationPhase::Removing,
+                meeting_id: meeting_id.clone(),
+                completed: index,
+                total,
+            });
+            match std::fs::remove_dir_all(self.source_dir.join(meeting_id.as_str())) {
+                Ok(()) => report.migrated.push(meeting_id.clone()),
+                Err(e) => {
+                    eprintln!("[Storage] 移行元の会議を削除できません ({}): {}", meeting_id, e);
+                    report.not_removed.push(meeting_id.clone());
+                }
+            }
+        }
+
+        // 検索インデックスは移した会議の分だけずれるため、どちらも次に開いたときに作り直させる
+        for storage in [from, to] {
+            if let Err(e) = storage.discard_index() {
+                eprintln!("[Storage] 検索インデックスを削除できません: {}", e);
+            }
+        }
+
+        report
+    }
+
+    /// 移行を取りやめ、新しい保存先にコピーした分を削除する
+    pub fn abort(self, progress: impl FnMut(&MigrationProgress)) {
+        let copied = self.meetings.len();
+        self.remove_copies(copied, progress);
+    }
+
+    /// 先頭から `count` 件のコピーを削除する
+    fn remove_copies(&self, count: usize, mut progress: impl FnMut(&MigrationProgress)) {
+        for (index, meeting_id) in self.meetings.iter().take(count).enumerate() {
+            progress(&MigrationProgress {
+                phase: MigrationPhase::RollingBack,
+                meeting_id: meeting_id.clone(),
+                completed: index,
+                total: count,
+            });
+            let destination = self.target_dir.join(meeting_id.as_str());
+            if let Err(remove_error) = std::fs::remove_dir_all(&destination) {
+                eprintln!(
+                    "[Storage] 移行先のコピーを削除できません ({}): {}",
+                    destination.display(),
+                    remove_error
+                );
+            }
+        }
+    }
+}
+
+/// ディレクトリを再帰的にコピーし、コピーしたバイト数を返す
+fn copy_dir(source: &Path, destination: &Path) -> Result<u64> {
+    std::fs::create_dir_all(destination)?;
+    let mut bytes = 0;
+
+    for entry in std::fs::read_dir(source)? {
+        let entry = entry?;
+        let target = destination.join(entry.file_name());
+        if entry.file_type()?.is_dir() {
+            bytes += copy_dir(&entry.path(), &target)?;
+        } else {
+            bytes += std::fs::copy(entry.path(), &target)?;
+            File::open(&target)?.sync_all()?;
+        }
+    }
+
+    Ok(bytes)
+}
+
+/// コピー先の内容が元と一致するか照合する
+fn verify_dir(source: &Path, destination: &Path) -> Result<()> {
+    for entry in std::fs::read_dir(source)? {
+        let entry = entry?;
+        let target = destination.join(entry.file_name());
+        if entry.file_type()?.is_dir() {
+            verify_dir(&entry.path(), &target)?;
+        } else if !same_contents(&entry.path(), &target)? {
+            return Err(StorageError::Corrupted(format!(
+                "Copied file does not match: {}",
+                target.display()
+            )));
+        }
+    }
+    Ok(())
+}
+
+fn same_contents(a: &Path, b: &Path) -> Result<bool> {
+    if !b.is_file() || std::fs::metadata(a)?.len() != std::fs::metadata(b)?.len() {
+        return Ok(false);
+    }
+
+    let mut a = BufReader::new(File::open(a)?);
+    let mut b = BufReader::new(File::open(b)?);
+    let mut buf_a = vec![0u8; 64 * 1024];
+    let mut buf_b = vec![0u8; 64 * 1024];
+    loop {
+        let read = a.read(&mut buf_a)?;
+        if read == 0 {
+            return Ok(true);
+        }
+        b.read_exact(&mut buf_b[..read])?;
+        if buf_a[..read] != buf_b[..read] {
+            return Ok(false);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::storage::testing::{meeting_id as id, temp_storage};
+
+    fn write_meeting(storage: &MeetingStorage, id: &MeetingId) {
+        let dir = storage.base_dir().join(id.as_str());
+        std::fs::create_dir_all(dir.join("nested")).unwrap();
+        std::fs::write(dir.join("meeting.json"), format!("{{\"id\":\"{}\"}}", id)).unwrap();
+        std::fs::write(dir.join("nested").join("audio.wav"), vec![7u8; 200_000]).unwrap();
+    }
+
+    #[test]
+    fn test_migrate_copies_verifies_and_removes() {
+        let (from, _from_dir) = temp_storage("migrate-from");
+        let (to, to_dir) = temp_storage("migrate-to");
+        write_meeting(&from, &id(1));
+        write_meeting(&from, &id(2));
+
+        let mut phases = Vec::new();
+        let report = migrate_meetings(&from, &to, |p| phases.push(p.phase)).unwrap();
+
+        assert_eq!(report.migrated, vec![id(1), id(2)]);
+        assert_eq!(report.bytes_copied, 2 * (200_000 + 45));
+        assert!(from.list_meetings().unwrap().is_empty());
+        assert_eq!(
+            std::fs::read(to_dir.join(id(2).as_str()).join("nested").join("audio.wav")).unwrap().len(),
+            200_000
+        );
+        assert_eq!(phases.first(), Some(&MigrationPhase::Copying));
+        assert_eq!(phases.last(), Some(&MigrationPhase::Removing));
+    }
+
+    #[test]
+    fn test_conflict_leaves_both_untouched() {
+        let (from, _from_dir) = temp_storage("migrate-from");
+        let (to, _to_dir) = temp_storage("migrate-to");
+        write_meeting(&from, &id(1));
+        write_meeting(&from, &id(2));
+        write_meeting(&to, &id(2));
+
+        assert!(migrate_meetings(&from, &to, |_| {}).is_err());
+        assert_eq!(from.list_meetings().unwrap().len(), 2);
+        assert_eq!(to.list_meetings().unwrap(), vec![id(2)]);
+    }
+
+    #[test]
+    fn test_prepared_migration_keeps_sources_until_finished() {
+        let (from, _from_dir) = temp_storage("migrate-from");
+        let (to, _to_dir) = temp_storage("migrate-to");
+        write_meeting(&from, &id(1));
+
+        let prepared = prepare_migration(&from, &to, |_| {}).unwrap();
+        assert_eq!(prepared.meetings(), &[id(1)]);
+        assert_eq!(from.list_meetings().unwrap(), vec![id(1)]);
+        assert_eq!(to.list_meetings().unwrap(), vec![id(1)]);
+
+        // 設定を保存できなかった場合などは取りやめて元の状態に戻す
+        prepared.abort(|_| {});
+        assert_eq!(from.list_meetings().unwrap(), vec![id(1)]);
+        assert!(to.list_meetings().unwrap().is_empty());
+
+        let report = prepare_migration(&from, &to, |_| {}).unwrap().finish(&from, &to, |_| {});
+        assert_eq!(report.migrated, vec![id(1)]);
+        assert!(from.list_meetings().unwrap().is_empty());
+        assert_eq!(to.list_meetings().unwrap(), vec![id(1)]);
+    }
+
+    #[test]
+    fn test_rolls_back_when_verification_fails() {
+        let (from, _from_dir) = temp_storage("migrate-from");
+        let (to, to_dir) = temp_storage("migrate-to");
+        write_meeting(&from, &id(1));
+        write_meeting(&from, &id(2));
+
+        // 照合の直前にコピー先を壊し、ロールバックされることを確かめる
+        let result = migrate_meetings(&from, &to, |p| {
+            if p.phase == MigrationPhase::Verifying && p.completed == 0 {
+                std::fs::write(to_dir.join(id(1).as_str()).join("meeting.json"), "broken!!!!!!").unwrap();
+            }
+        });
+
+        assert!(matches!(result, Err(StorageError::Corrupted(_))));
+        assert!(to.list_meetings().unwrap().is_empty());
+        assert_eq!(from.list_meetings().unwrap().len(), 2);
+    }
+}
@@ -1,3 +1,106 @@
 pub mod meeting_storage;
+pub mod meeting_id;
+pub mod audio_writer;
+pub mod timeline;
+pub mod journal;
+pub mod migration;
+pub mod library;
+pub mod index;
+pub mod schema;
+pub mod atomic;
+pub mod repository;
+pub mod memory;
 
-pub use meeting_storage::MeetingStorage;
+pub use meeting_storage::{MeetingData, MeetingStorage, StorageError, TranscriptSegment};
+pub use meeting_id::MeetingId;
+pub use audio_writer::{repair_wav_header, StreamingAudioWriter};
+pub use timeline::{PauseInterval, PauseTimeline};
+pub use journal::{JournalEntry, MeetingJournal};
+pub use migration::{
+    migrate_meetings, prepare_migration, MigrationPhase, MigrationProgress, MigrationReport,
+    PreparedMigration,
+};
+pub use library::{MeetingFootprint, MeetingPage, MeetingSort, MeetingSummary, MeetingUpdate};
+pub use index::{SearchHit, SearchQuery, SearchResult, SnippetPart};
+pub use schema::{parse_meeting, MeetingLoad, UnreadableMeeting, MEETING_SCHEMA_VERSION};
+pub use atomic::{backup_path, read_with_backup, write_atomic, write_atomic_validated};
+pub use repository::{audio_file_name, AudioSink, MeetingRepository};
+pub use memory::{MemoryAudio, MemoryMeetingRepository};
+
+/// ストレージのテストで共用する一時ディレクトリと会議データ
+#[cfg(test)]
+pub(crate) mod testing {
+    use chrono::{DateTime, TimeZone, Utc};
+    use std::path::{Path, PathBuf};
+
+    use super::{MeetingData, MeetingId, MeetingStorage, TranscriptSegment, MEETING_SCHEMA_VERSION};
+
+    /// テスト用の一時ディレクトリ（テストが失敗しても `Drop` で中身ごと削除する）
+    pub struct TempDir(PathBuf);
+
+    impl TempDir {
+        pub fn new(label: &str) -> Self {
+            TempDir(std::env::temp_dir().join(format!("gijiroku21-{}-{}", label, uuid::Uuid::new_v4())))
+        }
+    }
+
+    impl std::ops::Deref for TempDir {
+        type Target = Path;
+
+        fn deref(&self) -> &Path {
+            &self.0
+        }
+    }
+
+    impl AsRef<Path> for TempDir {
+        fn as_ref(&self) -> &Path {
+            &self.0
+        }
+    }
+
+    impl Drop for TempDir {
+        fn drop(&mut self) {
+            std::fs::remove_dir_all(&self.0).ok();
+        }
+    }
+
+    /// 一時ディレクトリを保存先にしたストレージ
+    pub fn temp_storage(label: &str) -> (MeetingStorage, TempDir) {
+        let dir = TempDir::new(label);
+        (MeetingStorage::new(&dir).unwrap(), dir)
+    }
+
+    /// 末尾が `n`（16進数）の決まった会議ID
+    pub fn meeting_id(n: u32) -> MeetingId {
+        MeetingId::parse(&format!("00000000-0000-4000-8000-{:012x}", n)).unwrap()
+    }
+
+    /// 基準時刻から `seconds` 秒後
+    pub fn at(seconds: i64) -> DateTime<Utc> {
+        Utc.timestamp_opt(1_700_000_000 + seconds, 0).unwrap()
+    }
+
+    /// `at(0)` に始まった終了前の会議（`lines` は10秒おきの `(話者, 発言)`。話者が空ならなし）
+    pub fn meeting(id: MeetingId, title: &str, lines: &[(&str, &str)]) -> MeetingData {
+        MeetingData {
+            schema_version: MEETING_SCHEMA_VERSION,
+            id,
+            title: title.to_string(),
+            started_at: at(0),
+            ended_at: None,
+            transcript: lines
+                .iter()
+                .enumerate()
+                .map(|(index, (speaker, text))| TranscriptSegment {
+                    timestamp: index as f64 * 10.0,
+                    text: text.to_string(),
+                    speaker: Some(speaker.to_string()).filter(|s| !s.is_empty()),
+                })
+                .collect(),
+            summary: None,
+            audio_file: None,
+            pauses: Vec::new(),
+            tags: Vec::new(),
+        }
+    }
+}
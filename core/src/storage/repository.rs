@@ -0,0 +1,260 @@
+use super::index::{search_loaded, SearchQuery, SearchResult};
+use super::journal::MeetingJournal;
+use super::library::{summarize, MeetingFootprint, MeetingPage, MeetingSort, MeetingUpdate};
+use super::meeting_storage::{MeetingData, MeetingStorage, Result, StorageError};
+use super::schema::load_all;
+use super::{MeetingId, StreamingAudioWriter};
+
+/// 音声ファイル名（`track` がNoneなら会議全体の音声 `audio.wav`、あれば `audio_{track}.wav`）
+pub fn audio_file_name(track: Option<&str>) -> String {
+    match track {
+        Some(track) => format!("audio_{}.wav", track),
+        None => "audio.wav".to_string(),
+    }
+}
+
+/// 録音中の音声を逐次書き込む先
+pub trait AudioSink: Send {
+    /// 書き込み先と同じレートのサンプルを追記
+    fn write_samples(&mut self, samples: &[f32]) -> Result<()>;
+
+    /// 任意のレートのサンプルを追記（必要に応じて書き込み先のレートへ変換）
+    fn write_at_rate(&mut self, samples: &[f32], input_rate: u32) -> Result<()>;
+
+    /// ここまでの音声を読める状態にする
+    fn flush(&mut self) -> Result<()>;
+
+    /// 書き込みを完了する（書き込んだサンプル数を返す）
+    fn finalize(self: Box<Self>) -> Result<u64>;
+}
+
+impl AudioSink for StreamingAudioWriter {
+    fn write_samples(&mut self, samples: &[f32]) -> Result<()> {
+        StreamingAudioWriter::write_samples(self, samples)
+    }
+
+    fn write_at_rate(&mut self, samples: &[f32], input_rate: u32) -> Result<()> {
+        StreamingAudioWriter::write_at_rate(self, samples, input_rate)
+    }
+
+    fn flush(&mut self) -> Result<()> {
+        StreamingAudioWriter::flush(self)
+    }
+
+    fn finalize(self: Box<Self>) -> Result<u64> {
+        StreamingAudioWriter::finalize(*self)
+    }
+}
+
+/// 会議データと音声の保存先
+///
+/// 録音・文字起こしの処理や会議一覧の操作はこのトレイトを通して保存先を使うため、
+/// テストでは `MemoryMeetingRepository` に差し替えてユーザーの保存先に触れずに動かせる
+pub trait MeetingRepository: Send + Sync {
+    /// 会議データを保存
+    fn save_meeting(&self, meeting: &MeetingData) -> Result<()>;
+
+    /// 会議データを読み込み（なければ `StorageError::NotFound`）
+    fn load_meeting(&self, meeting_id: &MeetingId) -> Result<MeetingData>;
+
+    /// すべての会議IDを取得（meeting.json をまだ保存していない録音中の会議も含む）
+    fn list_meetings(&self) -> Result<Vec<MeetingId>>;
+
+    /// 会議を音声ごと削除（なければ何もしない）
+    fn delete_meeting(&self, meeting_id: &MeetingId) -> Result<()>;
+
+    /// 保存済みの音声のファイル名（まだなければNone）
+    fn audio_file(&self, meeting_id: &MeetingId, track: Option<&str>) -> Option<String>;
+
+    /// 音声を逐次書き込む先を作成（同じトラックの音声は置き換える）
+    fn create_audio_sink(
+        &self,
+        meeting_id: &MeetingId,
+        track: Option<&str>,
+        sample_rate: u32,
+    ) -> Result<Box<dyn AudioSink>>;
+
+    /// 異常終了からの復元に使うジャーナルを開く（復元に対応しない保存先ならNone）
+    fn recovery_journal(&self, _meeting_id: &MeetingId) -> Result<Option<MeetingJournal>> {
+        Ok(None)
+    }
+
+    /// 会議を編集して保存
+    fn update_meeting(&self, meeting_id: &MeetingId, update: MeetingUpdate) -> Result<MeetingData> {
+        let mut meeting = self.load_meeting(meeting_id)?;
+        update.apply(&mut meeting);
+        self.save_meeting(&meeting)?;
+        Ok(meeting)
+    }
+
+    /// 会議の保存内容の合計サイズと更新状況（削除の確認に使う。なければ `StorageError::NotFound`）
+    fn meeting_footprint(&self, meeting_id: &MeetingId) -> Result<MeetingFootprint>;
+
+    /// 会議の概要を並べ替えて `offset` 件目から最大 `limit` 件取得
+    ///
+    /// meeting.json がない（録音中・異常終了した）会議や読み込めない会議は含めない
+    fn list_meeting_summaries(
+        &self,
+        sort: MeetingSort,
+        offset: usize,
+        limit: usize,
+    ) -> Result<MeetingPage> {
+        Ok(summarize(load_all(self)?, sort, offset, limit))
+    }
+
+    /// 文字起こしを全文検索し、日付・タグ・話者で絞り込む
+    fn search_meetings(&self, query: &SearchQuery) -> Result<Vec<SearchResult>> {
+        search_loaded(&load_all(self)?, query)
+    }
+
+    /// 異常終了して復元できる会議のIDを取得（復元に対応しない保存先なら空）
+    fn list_recoverable_meetings(&self) -> Result<Vec<MeetingId>> {
+        Ok(Vec::new())
+    }
+
+    /// ジャーナルから会議を復元して保存（復元に対応しない保存先なら `StorageError::NotFound`）
+    fn recover_meeting(&self, meeting_id: &MeetingId) -> Result<MeetingData> {
+        Err(StorageError::NotFound(meeting_id.to_string()))
+    }
+}
+
+impl MeetingRepository for MeetingStorage {
+    fn save_meeting(&self, meeting: &MeetingData) -> Result<()> {
+        MeetingStorage::save_meeting(self, meeting)
+    }
+
+    fn load_meeting(&self, meeting_id: &MeetingId) -> Result<MeetingData> {
+        MeetingStorage::load_meeting(self, meeting_id)
+    }
+
+    fn list_meetings(&self) -> Result<Vec<MeetingId>> {
+        MeetingStorage::list_meetings(self)
+    }
+
+    fn delete_meeting(&self, meeting_id: &MeetingId) -> Result<()> {
+        MeetingStorage::delete_meeting(self, meeting_id)
+    }
+
+    fn audio_file(&self, meeting_id: &MeetingId, track: Option<&str>) -> Option<String> {
+        let path = match track {
+            Some(track) => self.track_audio_file_path(meeting_id, track),
+            None => self.audio_file_path(meeting_id),
+        };
+        path.is_file().then(|| audio_file_name(track))
+    }
+
+    fn create_audio_sink(
+        &self,
+        meeting_id: &MeetingId,
+        track: Option<&str>,
+        sample_rate: u32,
+    ) -> Result<Box<dyn AudioSink>> {
+        let writer = match track {
+            Some(track) => self.create_track_audio_writer(meeting_id, track, sample_rate)?,
+            None => self.create_audio_writer(meeting_id, sample_rate)?,
+        };
+        Ok(Box::new(writer))
+    }
+
+    fn recovery_journal(&self, meeting_id: &MeetingId) -> Result<Option<MeetingJournal>> {
+        self.open_journal(meeting_id).map(Some)
+    }
+
+    fn meeting_footprint(&self, meeting_id: &MeetingId) -> Result<MeetingFootprint> {
+        MeetingStorage::meeting_footprint(self, meeting_id)
+    }
+
+    fn list_meeting_summaries(
+        &self,
+        sort: MeetingSort,
+        offset: usize,
+        limit: usize,
+    ) -> Result<MeetingPage> {
+        MeetingStorage::list_meeting_summaries(self, sort, offset, limit)
+    }
+
+    fn search_meetings(&self, query: &SearchQuery) -> Result<Vec<SearchResult>> {
+        MeetingStorage::search_meetings(self, query)
+    }
+
+    fn list_recoverable_meetings(&self) -> Result<Vec<MeetingId>> {
+        MeetingStorage::list_recoverable_meetings(self)
+    }
+
+    fn recover_meeting(&self, meeting_id: &MeetingId) -> Result<MeetingData> {
+        MeetingStorage::recover_meeting(self, meeting_id)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::storage::testing::{meeting, temp_storage};
+    use crate::storage::{MemoryMeetingRepository, StorageError};
+
+    /// どの実装でも同じように振る舞うことを確認する
+    fn exercise(repository: &dyn MeetingRepository) {
+        let id = MeetingId::new();
+        assert!(matches!(repository.load_meeting(&id), Err(StorageError::NotFound(_))));
+        assert!(repository.list_meetings().unwrap().is_empty());
+
+        // 録音中：音声だけがある
+        let mut sink = repository.create_audio_sink(&id, None, 16000).unwrap();
+        sink.write_samples(&[0.5; 1600]).unwrap();
+        sink.write_at_rate(&[0.5; 4800], 48000).unwrap();
+        sink.flush().unwrap();
+        assert_eq!(repository.audio_file(&id, None).as_deref(), Some("audio.wav"));
+        assert!(repository.audio_file(&id, Some("mic")).is_none());
+        assert_eq!(repository.list_meetings().unwrap(), std::slice::from_ref(&id));
+        let written = sink.finalize().unwrap();
+        assert!((3000..=3200).contains(&written), "{} samples", written);
+
+        // 終了：meeting.json を保存して編集
+        repository.save_meeting(&meeting(id.clone(), "定例会", &[("", "開会します")])).unwrap();
+        let footprint = repository.meeting_footprint(&id).unwrap();
+        assert!(footprint.size_bytes > 0);
+        let updated = repository
+            .update_meeting(&id, MeetingUpdate {
+                title: Some("12月定例会".to_string()),
+                ..Default::default()
+            })
+            .unwrap();
+        assert_eq!(updated.title, "12月定例会");
+        let loaded = repository.load_meeting(&id).unwrap();
+        assert_eq!(loaded.title, "12月定例会");
+        assert_eq!(loaded.transcript[0].text, "開会します");
+        assert_ne!(repository.meeting_footprint(&id).unwrap().token(), footprint.token());
+
+        // 一覧と検索
+        let page = repository.list_meeting_summaries(MeetingSort::NewestFirst, 0, 10).unwrap();
+        assert_eq!(page.total, 1);
+        assert_eq!(page.meetings[0].title, "12月定例会");
+        let results = repository
+            .search_meetings(&SearchQuery {
+                text: "開会".to_string(),
+                ..Default::default()
+            })
+            .unwrap();
+        assert_eq!(results.len(), 1);
+        assert_eq!(results[0].hits[0].timestamp, 0.0);
+
+        repository.delete_meeting(&id).unwrap();
+        repository.delete_meeting(&id).unwrap();
+        assert!(repository.list_meetings().unwrap().is_empty());
+        assert!(repository.audio_file(&id, None).is_none());
+        assert!(matches!(repository.load_meeting(&id), Err(StorageError::NotFound(_))));
+        assert!(matches!(repository.meeting_footprint(&id), Err(StorageError::NotFound(_))));
+        assert_eq!(repository.list_meeting_summaries(MeetingSort::NewestFirst, 0, 10).unwrap().total, 0);
+    }
+
+    #[test]
+    fn test_filesystem_repository() {
+        let (storage, _dir) = temp_storage("repository");
+        exercise(&storage);
+    }
+
+    #[test]
+    fn test_memory_repository() {
+        exercise(&MemoryMeetingRepository::new());
+    }
+}
@@ -0,0 +1,170 @@
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+
+use super::meeting_storage::{MeetingData, MeetingStorage, Result, StorageError};
+use super::repository::MeetingRepository;
+use super::MeetingId;
+
+/// 現在の meeting.json の版
+///
+/// `MeetingData` の形を変えたら上げ、`MIGRATIONS` に前の版からの変換を足す
+pub const MEETING_SCHEMA_VERSION: u32 = 1;
+
+/// 版ごとの変換（`MIGRATIONS[n]` が版nの文書を版n+1へ上げる）
+const MIGRATIONS: [fn(&mut Map<String, Value>); MEETING_SCHEMA_VERSION as usize] = [migrate_v0_to_v1];
+
+/// 版0（`schema_version` がなかった頃）→ 版1：一時停止区間とタグを追加
+fn migrate_v0_to_v1(doc: &mut Map<String, Value>) {
+    for key in ["transcript", "pauses", "tags"] {
+        doc.entry(key).or_insert_with(|| Value::Array(Vec::new()));
+    }
+    for key in ["ended_at", "summary", "audio_file"] {
+        doc.entry(key).or_insert(Value::Null);
+    }
+}
+
+/// meeting.json を読み、古い版なら現在の版へ変換する
+pub fn parse_meeting(json: &str) -> Result<MeetingData> {
+    let mut value: Value = serde_json::from_str(json)?;
+    let doc = value
+        .as_object_mut()
+        .ok_or_else(|| StorageError::Corrupted("meeting.json is not an object".to_string()))?;
+
+    let version = match doc.get("schema_version") {
+        None => 0,
+        Some(version) => version
+            .as_u64()
+            .and_then(|v| u32::try_from(v).ok())
+            .ok_or_else(|| StorageError::Corrupted(format!("Invalid schema_version: {}", version)))?,
+    };
+    if version > MEETING_SCHEMA_VERSION {
+        return Err(StorageError::UnsupportedSchema {
+            found: version,
+            supported: MEETING_SCHEMA_VERSION,
+        });
+    }
+
+    for migrate in &MIGRATIONS[version as usize..] {
+        migrate(doc);
+    }
+    doc.insert("schema_version".to_string(), MEETING_SCHEMA_VERSION.into());
+
+    Ok(serde_json::from_value(value)?)
+}
+
+/// 読み込めなかった会議
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct UnreadableMeeting {
+    pub meeting_id: MeetingId,
+    pub error: String,
+}
+
+/// 全会議の読み込み結果
+#[derive(Debug, Default)]
+pub struct MeetingLoad {
+    pub meetings: Vec<MeetingData>,
+    pub unreadable: Vec<UnreadableMeeting>,
+}
+
+impl MeetingStorage {
+    /// すべての会議を読み込む
+    ///
+    /// 壊れている・新しすぎる meeting.json があっても他の会議は読み込み、
+    /// 読めなかった会議は `unreadable` に入れて返す。meeting.json がない（録音中・異常終了した）
+    /// 会議は含めない
+    pub fn load_all_meetings(&self) -> Result<MeetingLoad> {
+        load_all(self)
+    }
+}
+
+/// 保存先のすべての会議を読み込む（読めなかった会議は `unreadable` に入れる）
+pub(super) fn load_all<R: MeetingRepository + ?Sized>(repository: &R) -> Result<MeetingLoad> {
+    let mut load = MeetingLoad::default();
+    let mut meeting_ids = repository.list_meetings()?;
+    meeting_ids.sort();
+
+    for meeting_id in meeting_ids {
+        match repository.load_meeting(&meeting_id) {
+            Ok(meeting) => load.meetings.push(meeting),
+            Err(StorageError::NotFound(_)) => {}
+            Err(e) => {
+                eprintln!("[Storage] 会議を読み込めません ({}): {}", meeting_id, e);
+                load.unreadable.push(UnreadableMeeting {
+                    meeting_id,
+                    error: e.to_string(),
+                });
+            }
+        }
+    }
+    Ok(load)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::storage::testing::temp_storage;
+
+    #[test]
+    fn test_upgrade_v0_document() {
+        // schema_version・pauses・tags がない最初の形式
+        let v0 = r#"{
+            "id": "6f9619ff-8b86-4011-b42d-00c04fc964ff",
+            "title": "昔の会議",
+            "started_at": "2024-12-20T05:00:00Z",
+            "ended_at": "2024-12-20T06:30:00Z",
+            "transcript": [{"timestamp": 1.5, "text": "開会します", "speaker": null}],
+            "summary": null
+        }"#;
+
+        let meeting = parse_meeting(v0).unwrap();
+        assert_eq!(meeting.schema_version, MEETING_SCHEMA_VERSION);
+        assert_eq!(meeting.transcript[0].text, "開会します");
+        assert!(meeting.pauses.is_empty() && meeting.tags.is_empty());
+        assert!(meeting.audio_file.is_none());
+    }
+
+    #[test]
+    fn test_rejects_newer_and_malformed_versions() {
+        let newer = r#"{"schema_version": 99, "id": "x"}"#;
+        assert!(matches!(
+            parse_meeting(newer),
+            Err(StorageError::UnsupportedSchema { found: 99, .. })
+        ));
+        assert!(matches!(
+            parse_meeting(r#"{"schema_version": "1"}"#),
+            Err(StorageError::Corrupted(_))
+        ));
+        assert!(parse_meeting("[]").is_err());
+    }
+
+    #[test]
+    fn test_load_all_reports_unreadable_meetings() {
+        let (storage, dir) = temp_storage("schema");
+
+        let (good, broken, future) = (MeetingId::new(), MeetingId::new(), MeetingId::new());
+        for (id, json) in [
+            (&good, format!(r#"{{"id":"{}","title":"t","started_at":"2024-12-20T05:00:00Z","ended_at":null,"transcript":[],"summary":null}}"#, good)),
+            (&broken, format!(r#"{{"id":"{}","title":"#, broken)),
+            (&future, format!(r#"{{"schema_version":2,"id":"{}"}}"#, future)),
+        ] {
+            std::fs::create_dir_all(dir.join(id.as_str())).unwrap();
+            std::fs::write(dir.join(id.as_str()).join("meeting.json"), json).unwrap();
+        }
+        // meeting.json がない会議は読めない扱いにしない
+        std::fs::create_dir_all(dir.join(MeetingId::new().as_str())).unwrap();
+
+        let load = storage.load_all_meetings().unwrap();
+        assert_eq!(load.meetings.len(), 1);
+        let mut expected = vec![broken, future];
+        expected.sort();
+        let unreadable: Vec<_> = load.unreadable.iter().map(|u| u.meeting_id.clone()).collect();
+        assert_eq!(unreadable, expected);
+
+        // 一覧も読めた会議だけを返し、読めなかった会議を知らせる
+        let page = storage
+            .list_meeting_summaries(crate::storage::MeetingSort::NewestFirst, 0, 10)
+            .unwrap();
+        assert_eq!(page.total, 1);
+        assert_eq!(page.unreadable, load.unreadable);
+    }
+}
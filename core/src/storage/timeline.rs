@@ -0,0 +1,166 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+/// 録音を一時停止していた区間
+///
+/// 一時停止中は音声を記録しないため、音声上の時刻は `audio_position` で途切れずにつながる
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PauseInterval {
+    /// 一時停止した時刻
+    pub paused_at: DateTime<Utc>,
+    /// 再開した時刻（一時停止したまま終了した場合はNone）
+    pub resumed_at: Option<DateTime<Utc>>,
+    /// 一時停止した時点の音声上の位置（会議開始からの秒数）
+    pub audio_position: f64,
+}
+
+impl PauseInterval {
+    /// `end` までに一時停止していた時間（秒）。再開していなければ `end` までとする
+    pub fn duration_sec(&self, end: DateTime<Utc>) -> f64 {
+        let resumed_at = self.resumed_at.map_or(end, |resumed| resumed.min(end));
+        seconds_between(self.paused_at, resumed_at).max(0.0)
+    }
+}
+
+/// 音声上の時刻（一時停止区間を含まない）と実時刻の対応
+#[derive(Debug, Clone, Copy)]
+pub struct PauseTimeline<'a> {
+    started_at: DateTime<Utc>,
+    pauses: &'a [PauseInterval],
+}
+
+impl<'a> PauseTimeline<'a> {
+    /// `pauses` は発生順に並んでいること
+    pub fn new(started_at: DateTime<Utc>, pauses: &'a [PauseInterval]) -> Self {
+        PauseTimeline { started_at, pauses }
+    }
+
+    /// `end` までに一時停止していた時間の合計（秒）
+    pub fn paused_sec(&self, end: DateTime<Utc>) -> f64 {
+        self.pauses
+            .iter()
+            .filter(|p| p.paused_at < end)
+            .map(|p| p.duration_sec(end))
+            .sum()
+    }
+
+    /// 開始から `end` までのうち、実際に録音していた時間（秒）
+    pub fn active_sec(&self, end: DateTime<Utc>) -> f64 {
+        (seconds_between(self.started_at, end) - self.paused_sec(end)).max(0.0)
+    }
+
+    /// 音声上の時刻を実時刻に変換
+    ///
+    /// 直前の再開時刻（なければ開始時刻）を基準にするため、区間ごとの誤差は累積しない
+    pub fn to_wall_clock(&self, audio_sec: f64) -> DateTime<Utc> {
+        let (anchor_wall, anchor_audio) = self
+            .pauses
+            .iter()
+            .filter(|p| p.audio_position <= audio_sec)
+            .filter_map(|p| p.resumed_at.map(|resumed| (resumed, p.audio_position)))
+            .next_back()
+            .unwrap_or((self.started_at, 0.0));
+
+        anchor_wall + seconds_to_duration(audio_sec - anchor_audio)
+    }
+
+    /// 実時刻を音声上の時刻に変換（一時停止中の時刻ならNone）
+    pub fn to_audio_time(&self, wall: DateTime<Utc>) -> Option<f64> {
+        if wall < self.started_at {
+            return None;
+        }
+
+        let mut anchor = (self.started_at, 0.0);
+        for pause in self.pauses {
+            if wall < pause.paused_at {
+                break;
+            }
+            match pause.resumed_at {
+                Some(resumed) if wall >= resumed => anchor = (resumed, pause.audio_position),
+                _ => return None,
+            }
+        }
+
+        let (anchor_wall, anchor_audio) = anchor;
+        Some(anchor_audio + seconds_between(anchor_wall, wall))
+    }
+
+    /// 音声上の `from` より後、`to` 以前の位置で起きた一時停止
+    pub fn pauses_between(&self, from: f64, to: f64) -> impl Iterator<Item = &'a PauseInterval> {
+        self.pauses
+            .iter()
+            .filter(move |p| p.audio_position > from && p.audio_position <= to)
+    }
+}
+
+fn seconds_between(from: DateTime<Utc>, to: DateTime<Utc>) -> f64 {
+    to.signed_duration_since(from).num_milliseconds() as f64 / 1000.0
+}
+
+fn seconds_to_duration(seconds: f64) -> chrono::Duration {
+    chrono::Duration::milliseconds((seconds * 1000.0).round() as i64)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    fn at(seconds: i64) -> DateTime<Utc> {
+        Utc.timestamp_opt(1_700_000_000 + seconds, 0).unwrap()
+    }
+
+    /// 10秒録音 → 20秒一時停止 → 15秒録音 → 5秒一時停止 → 5秒録音
+    fn pauses() -> Vec<PauseInterval> {
+        vec![
+            PauseInterval {
+                paused_at: at(10),
+                resumed_at: Some(at(30)),
+                audio_position: 10.0,
+            },
+            PauseInterval {
+                paused_at: at(45),
+                resumed_at: Some(at(50)),
+                audio_position: 25.0,
+            },
+        ]
+    }
+
+    #[test]
+    fn test_active_duration_excludes_pauses() {
+        let pauses = pauses();
+        let timeline = PauseTimeline::new(at(0), &pauses);
+        assert_eq!(timeline.paused_sec(at(55)), 25.0);
+        assert_eq!(timeline.active_sec(at(55)), 30.0);
+        // 一時停止中の途中までは経過分だけ数える
+        assert_eq!(timeline.paused_sec(at(20)), 10.0);
+    }
+
+    #[test]
+    fn test_audio_and_wall_clock_round_trip() {
+        let pauses = pauses();
+        let timeline = PauseTimeline::new(at(0), &pauses);
+
+        assert_eq!(timeline.to_wall_clock(5.0), at(5));
+        assert_eq!(timeline.to_wall_clock(12.0), at(32));
+        assert_eq!(timeline.to_wall_clock(27.0), at(52));
+
+        assert_eq!(timeline.to_audio_time(at(32)), Some(12.0));
+        assert_eq!(timeline.to_audio_time(at(52)), Some(27.0));
+        assert_eq!(timeline.to_audio_time(at(20)), None);
+    }
+
+    #[test]
+    fn test_open_pause_at_end() {
+        let pauses = vec![PauseInterval {
+            paused_at: at(10),
+            resumed_at: None,
+            audio_position: 10.0,
+        }];
+        let timeline = PauseTimeline::new(at(0), &pauses);
+        assert_eq!(timeline.active_sec(at(40)), 10.0);
+        assert_eq!(timeline.to_audio_time(at(15)), None);
+        assert_eq!(timeline.pauses_between(5.0, 10.0).count(), 1);
+        assert_eq!(timeline.pauses_between(10.0, 20.0).count(), 0);
+    }
+}